@@ -38,7 +38,9 @@ CREATE TABLE public.figures (
     height integer NOT NULL,
     profile_id bigint NOT NULL,
     url text NOT NULL,
-    description text
+    description text,
+    like_count bigint DEFAULT 0 NOT NULL,
+    search_vector tsvector GENERATED ALWAYS AS ((setweight(to_tsvector('english'::regconfig, title), 'A'::"char") || setweight(to_tsvector('english'::regconfig, COALESCE(description, ''::text)), 'B'::"char"))) STORED
 );
 
 --
@@ -69,7 +71,10 @@ CREATE TABLE public.profiles (
     user_id bigint,
     profile_picture text,
     bio text,
-    banner text
+    banner text,
+    follower_count bigint DEFAULT 0 NOT NULL,
+    following_count bigint DEFAULT 0 NOT NULL,
+    search_vector tsvector GENERATED ALWAYS AS (((setweight(to_tsvector('english'::regconfig, username), 'A'::"char") || setweight(to_tsvector('english'::regconfig, COALESCE(display_name, ''::text)), 'A'::"char")) || setweight(to_tsvector('english'::regconfig, COALESCE(bio, ''::text)), 'B'::"char"))) STORED
 );
 
 --
@@ -99,9 +104,260 @@ CREATE TABLE public.users (
     email text NOT NULL,
     password text NOT NULL,
     role text NOT NULL,
+    suspended boolean DEFAULT false NOT NULL,
+    verified_at timestamp with time zone,
+    totp_secret text,
+    totp_enabled_at timestamp with time zone,
+    totp_last_used_step bigint,
     CONSTRAINT email_check CHECK ((email = lower(email)))
 );
 
+--
+-- Name: access_tokens; Type: TABLE; Schema: public; Owner: figure
+--
+
+CREATE TABLE public.access_tokens (
+    id bigint NOT NULL,
+    user_id bigint NOT NULL,
+    name text NOT NULL,
+    token_hash text NOT NULL,
+    scopes text[] NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    last_used_at timestamp with time zone
+);
+
+--
+-- Name: access_token_id_seq; Type: SEQUENCE; Schema: public; Owner: figure
+--
+
+CREATE SEQUENCE public.access_token_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+--
+-- Name: access_token_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: figure
+--
+
+ALTER SEQUENCE public.access_token_id_seq OWNED BY public.access_tokens.id;
+
+
+--
+-- Name: recovery_codes; Type: TABLE; Schema: public; Owner: figure
+--
+
+CREATE TABLE public.recovery_codes (
+    id bigint NOT NULL,
+    user_id bigint NOT NULL,
+    code_hash text NOT NULL
+);
+
+--
+-- Name: recovery_code_id_seq; Type: SEQUENCE; Schema: public; Owner: figure
+--
+
+CREATE SEQUENCE public.recovery_code_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+--
+-- Name: recovery_code_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: figure
+--
+
+ALTER SEQUENCE public.recovery_code_id_seq OWNED BY public.recovery_codes.id;
+
+
+--
+-- Name: external_identities; Type: TABLE; Schema: public; Owner: figure
+--
+
+CREATE TABLE public.external_identities (
+    id bigint NOT NULL,
+    user_id bigint NOT NULL,
+    issuer text NOT NULL,
+    subject text NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+--
+-- Name: external_identity_id_seq; Type: SEQUENCE; Schema: public; Owner: figure
+--
+
+CREATE SEQUENCE public.external_identity_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+--
+-- Name: external_identity_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: figure
+--
+
+ALTER SEQUENCE public.external_identity_id_seq OWNED BY public.external_identities.id;
+
+
+--
+-- Name: tags; Type: TABLE; Schema: public; Owner: figure
+--
+
+CREATE TABLE public.tags (
+    id bigint NOT NULL,
+    name text NOT NULL
+);
+
+--
+-- Name: tag_id_seq; Type: SEQUENCE; Schema: public; Owner: figure
+--
+
+CREATE SEQUENCE public.tag_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+--
+-- Name: tag_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: figure
+--
+
+ALTER SEQUENCE public.tag_id_seq OWNED BY public.tags.id;
+
+
+--
+-- Name: figure_tags; Type: TABLE; Schema: public; Owner: figure
+--
+
+CREATE TABLE public.figure_tags (
+    figure_id bigint NOT NULL,
+    tag_id bigint NOT NULL
+);
+
+
+--
+-- Name: figure_likes; Type: TABLE; Schema: public; Owner: figure
+--
+
+CREATE TABLE public.figure_likes (
+    id bigint NOT NULL,
+    figure_id bigint NOT NULL,
+    profile_id bigint NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+--
+-- Name: figure_like_id_seq; Type: SEQUENCE; Schema: public; Owner: figure
+--
+
+CREATE SEQUENCE public.figure_like_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+--
+-- Name: figure_like_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: figure
+--
+
+ALTER SEQUENCE public.figure_like_id_seq OWNED BY public.figure_likes.id;
+
+
+--
+-- Name: comments; Type: TABLE; Schema: public; Owner: figure
+--
+
+CREATE TABLE public.comments (
+    id bigint NOT NULL,
+    figure_id bigint NOT NULL,
+    profile_id bigint NOT NULL,
+    parent_id bigint,
+    content text NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    edited_at timestamp with time zone
+);
+
+--
+-- Name: comment_id_seq; Type: SEQUENCE; Schema: public; Owner: figure
+--
+
+CREATE SEQUENCE public.comment_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+--
+-- Name: comment_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: figure
+--
+
+ALTER SEQUENCE public.comment_id_seq OWNED BY public.comments.id;
+
+
+--
+-- Name: follows; Type: TABLE; Schema: public; Owner: figure
+--
+
+CREATE TABLE public.follows (
+    id bigint NOT NULL,
+    follower_id bigint NOT NULL,
+    followed_id bigint NOT NULL,
+    CONSTRAINT follow_not_self_check CHECK ((follower_id <> followed_id))
+);
+
+--
+-- Name: follow_id_seq; Type: SEQUENCE; Schema: public; Owner: figure
+--
+
+CREATE SEQUENCE public.follow_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+--
+-- Name: follow_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: figure
+--
+
+ALTER SEQUENCE public.follow_id_seq OWNED BY public.follows.id;
+
+
+--
+-- Name: object_deletions; Type: TABLE; Schema: public; Owner: figure
+--
+
+CREATE TABLE public.object_deletions (
+    id bigint NOT NULL,
+    name text NOT NULL,
+    attempts integer DEFAULT 0 NOT NULL,
+    next_attempt_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+--
+-- Name: object_deletion_id_seq; Type: SEQUENCE; Schema: public; Owner: figure
+--
+
+CREATE SEQUENCE public.object_deletion_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+--
+-- Name: object_deletion_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: figure
+--
+
+ALTER SEQUENCE public.object_deletion_id_seq OWNED BY public.object_deletions.id;
+
+
 --
 -- Name: user_id_seq; Type: SEQUENCE; Schema: public; Owner: figure
 --
@@ -134,6 +390,62 @@ ALTER TABLE ONLY public.figures ALTER COLUMN id SET DEFAULT nextval('public.figu
 ALTER TABLE ONLY public.profiles ALTER COLUMN id SET DEFAULT nextval('public.profile_id_seq'::regclass);
 
 
+--
+-- Name: access_tokens id; Type: DEFAULT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.access_tokens ALTER COLUMN id SET DEFAULT nextval('public.access_token_id_seq'::regclass);
+
+
+--
+-- Name: recovery_codes id; Type: DEFAULT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.recovery_codes ALTER COLUMN id SET DEFAULT nextval('public.recovery_code_id_seq'::regclass);
+
+
+--
+-- Name: external_identities id; Type: DEFAULT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.external_identities ALTER COLUMN id SET DEFAULT nextval('public.external_identity_id_seq'::regclass);
+
+
+--
+-- Name: tags id; Type: DEFAULT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.tags ALTER COLUMN id SET DEFAULT nextval('public.tag_id_seq'::regclass);
+
+
+--
+-- Name: figure_likes id; Type: DEFAULT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.figure_likes ALTER COLUMN id SET DEFAULT nextval('public.figure_like_id_seq'::regclass);
+
+
+--
+-- Name: comments id; Type: DEFAULT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.comments ALTER COLUMN id SET DEFAULT nextval('public.comment_id_seq'::regclass);
+
+
+--
+-- Name: follows id; Type: DEFAULT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.follows ALTER COLUMN id SET DEFAULT nextval('public.follow_id_seq'::regclass);
+
+
+--
+-- Name: object_deletions id; Type: DEFAULT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.object_deletions ALTER COLUMN id SET DEFAULT nextval('public.object_deletion_id_seq'::regclass);
+
+
 --
 -- Name: users id; Type: DEFAULT; Schema: public; Owner: figure
 --
@@ -195,6 +507,14 @@ SELECT pg_catalog.setval('public.profile_id_seq', 4, true);
 SELECT pg_catalog.setval('public.user_id_seq', 4, true);
 
 
+--
+-- Name: figures figure_pk; Type: CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.figures
+    ADD CONSTRAINT figure_pk PRIMARY KEY (id);
+
+
 --
 -- Name: profiles profile_pk; Type: CONSTRAINT; Schema: public; Owner: figure
 --
@@ -203,6 +523,78 @@ ALTER TABLE ONLY public.profiles
     ADD CONSTRAINT profile_pk PRIMARY KEY (id);
 
 
+--
+-- Name: access_tokens access_token_pk; Type: CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.access_tokens
+    ADD CONSTRAINT access_token_pk PRIMARY KEY (id);
+
+
+--
+-- Name: recovery_codes recovery_code_pk; Type: CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.recovery_codes
+    ADD CONSTRAINT recovery_code_pk PRIMARY KEY (id);
+
+
+--
+-- Name: external_identities external_identity_pk; Type: CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.external_identities
+    ADD CONSTRAINT external_identity_pk PRIMARY KEY (id);
+
+
+--
+-- Name: tags tag_pk; Type: CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.tags
+    ADD CONSTRAINT tag_pk PRIMARY KEY (id);
+
+
+--
+-- Name: figure_tags figure_tag_pk; Type: CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.figure_tags
+    ADD CONSTRAINT figure_tag_pk PRIMARY KEY (figure_id, tag_id);
+
+
+--
+-- Name: figure_likes figure_like_pk; Type: CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.figure_likes
+    ADD CONSTRAINT figure_like_pk PRIMARY KEY (id);
+
+
+--
+-- Name: comments comment_pk; Type: CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.comments
+    ADD CONSTRAINT comment_pk PRIMARY KEY (id);
+
+
+--
+-- Name: follows follow_pk; Type: CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.follows
+    ADD CONSTRAINT follow_pk PRIMARY KEY (id);
+
+
+--
+-- Name: object_deletions object_deletion_pk; Type: CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.object_deletions
+    ADD CONSTRAINT object_deletion_pk PRIMARY KEY (id);
+
+
 --
 -- Name: users user_pk; Type: CONSTRAINT; Schema: public; Owner: figure
 --
@@ -211,6 +603,20 @@ ALTER TABLE ONLY public.users
     ADD CONSTRAINT user_pk PRIMARY KEY (id);
 
 
+--
+-- Name: figure_search_vector_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX figure_search_vector_index ON public.figures USING gin (search_vector);
+
+
+--
+-- Name: profile_search_vector_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX profile_search_vector_index ON public.profiles USING gin (search_vector);
+
+
 --
 -- Name: profile_username_uindex; Type: INDEX; Schema: public; Owner: figure
 --
@@ -218,6 +624,125 @@ ALTER TABLE ONLY public.users
 CREATE UNIQUE INDEX profile_username_uindex ON public.profiles USING btree (username);
 
 
+--
+-- Name: access_token_token_hash_uindex; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE UNIQUE INDEX access_token_token_hash_uindex ON public.access_tokens USING btree (token_hash);
+
+
+--
+-- Name: access_token_user_id_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX access_token_user_id_index ON public.access_tokens USING btree (user_id);
+
+
+--
+-- Name: recovery_code_user_id_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX recovery_code_user_id_index ON public.recovery_codes USING btree (user_id);
+
+
+--
+-- Name: external_identity_issuer_subject_uindex; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE UNIQUE INDEX external_identity_issuer_subject_uindex ON public.external_identities USING btree (issuer, subject);
+
+
+--
+-- Name: external_identity_user_id_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX external_identity_user_id_index ON public.external_identities USING btree (user_id);
+
+
+--
+-- Name: tag_name_uindex; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE UNIQUE INDEX tag_name_uindex ON public.tags USING btree (name);
+
+
+--
+-- Name: figure_tag_tag_id_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX figure_tag_tag_id_index ON public.figure_tags USING btree (tag_id);
+
+
+--
+-- Name: figure_like_figure_id_profile_id_uindex; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE UNIQUE INDEX figure_like_figure_id_profile_id_uindex ON public.figure_likes USING btree (figure_id, profile_id);
+
+
+--
+-- Name: figure_like_profile_id_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX figure_like_profile_id_index ON public.figure_likes USING btree (profile_id, id);
+
+
+--
+-- Name: comment_figure_id_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX comment_figure_id_index ON public.comments USING btree (figure_id, id) WHERE (parent_id IS NULL);
+
+
+--
+-- Name: comment_parent_id_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX comment_parent_id_index ON public.comments USING btree (parent_id, id);
+
+
+--
+-- Name: comment_profile_id_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX comment_profile_id_index ON public.comments USING btree (profile_id);
+
+
+--
+-- Name: figure_profile_id_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX figure_profile_id_index ON public.figures USING btree (profile_id, id);
+
+
+--
+-- Name: follow_follower_id_followed_id_uindex; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE UNIQUE INDEX follow_follower_id_followed_id_uindex ON public.follows USING btree (follower_id, followed_id);
+
+
+--
+-- Name: follow_follower_id_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX follow_follower_id_index ON public.follows USING btree (follower_id, id);
+
+
+--
+-- Name: follow_followed_id_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX follow_followed_id_index ON public.follows USING btree (followed_id, id);
+
+
+--
+-- Name: object_deletion_next_attempt_at_index; Type: INDEX; Schema: public; Owner: figure
+--
+
+CREATE INDEX object_deletion_next_attempt_at_index ON public.object_deletions USING btree (next_attempt_at);
+
+
 --
 -- Name: user_email_uindex; Type: INDEX; Schema: public; Owner: figure
 --
@@ -240,6 +765,102 @@ ALTER TABLE ONLY public.figures
 ALTER TABLE ONLY public.profiles
     ADD CONSTRAINT profile_user_id_fk FOREIGN KEY (user_id) REFERENCES public.users(id);
 
+
+--
+-- Name: access_tokens access_token_user_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.access_tokens
+    ADD CONSTRAINT access_token_user_id_fk FOREIGN KEY (user_id) REFERENCES public.users(id) ON DELETE CASCADE;
+
+
+--
+-- Name: recovery_codes recovery_code_user_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.recovery_codes
+    ADD CONSTRAINT recovery_code_user_id_fk FOREIGN KEY (user_id) REFERENCES public.users(id) ON DELETE CASCADE;
+
+
+--
+-- Name: external_identities external_identity_user_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.external_identities
+    ADD CONSTRAINT external_identity_user_id_fk FOREIGN KEY (user_id) REFERENCES public.users(id) ON DELETE CASCADE;
+
+
+--
+-- Name: figure_tags figure_tag_figure_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.figure_tags
+    ADD CONSTRAINT figure_tag_figure_id_fk FOREIGN KEY (figure_id) REFERENCES public.figures(id) ON DELETE CASCADE;
+
+
+--
+-- Name: figure_tags figure_tag_tag_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.figure_tags
+    ADD CONSTRAINT figure_tag_tag_id_fk FOREIGN KEY (tag_id) REFERENCES public.tags(id) ON DELETE CASCADE;
+
+
+--
+-- Name: figure_likes figure_like_figure_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.figure_likes
+    ADD CONSTRAINT figure_like_figure_id_fk FOREIGN KEY (figure_id) REFERENCES public.figures(id) ON DELETE CASCADE;
+
+
+--
+-- Name: figure_likes figure_like_profile_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.figure_likes
+    ADD CONSTRAINT figure_like_profile_id_fk FOREIGN KEY (profile_id) REFERENCES public.profiles(id) ON DELETE CASCADE;
+
+
+--
+-- Name: comments comment_figure_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.comments
+    ADD CONSTRAINT comment_figure_id_fk FOREIGN KEY (figure_id) REFERENCES public.figures(id) ON DELETE CASCADE;
+
+
+--
+-- Name: comments comment_profile_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.comments
+    ADD CONSTRAINT comment_profile_id_fk FOREIGN KEY (profile_id) REFERENCES public.profiles(id) ON DELETE CASCADE;
+
+
+--
+-- Name: comments comment_parent_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.comments
+    ADD CONSTRAINT comment_parent_id_fk FOREIGN KEY (parent_id) REFERENCES public.comments(id) ON DELETE CASCADE;
+
+
+--
+-- Name: follows follow_follower_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.follows
+    ADD CONSTRAINT follow_follower_id_fk FOREIGN KEY (follower_id) REFERENCES public.profiles(id) ON DELETE CASCADE;
+
+
+--
+-- Name: follows follow_followed_id_fk; Type: FK CONSTRAINT; Schema: public; Owner: figure
+--
+
+ALTER TABLE ONLY public.follows
+    ADD CONSTRAINT follow_followed_id_fk FOREIGN KEY (followed_id) REFERENCES public.profiles(id) ON DELETE CASCADE;
+
 --
 -- PostgreSQL database dump complete
 --
@@ -1,19 +1,33 @@
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::{Arc};
-use axum::extract::State;
-use axum::http::{Request, StatusCode};
+use async_trait::async_trait;
+use axum::extract::{ConnectInfo, FromRequestParts, State};
+use axum::http::{header, Extensions, HeaderMap, Method, Request, StatusCode};
+use axum::http::request::Parts;
 use axum::middleware::Next;
-use axum::response::Response;
+use axum::response::{IntoResponse, Response};
 use tower_cookies::Cookies;
-use crate::context::{ContextTrait, RepositoryContextTrait};
+use crate::context::{ContextTrait, ServiceContextTrait};
 use crate::ServerState;
-use crate::entities::dtos::session_dtos::{SessionFromStore, SessionOption};
-use crate::repositories::traits::SessionRepositoryTrait;
+use crate::entities::access_token::Scope;
+use crate::entities::dtos::session_dtos::{ClientInfo, SessionFromStore, SessionOption};
+use crate::entities::user::Role;
+use crate::server_errors::ServerError;
+use crate::services::traits::{AccessTokenServiceTrait, UserServiceTrait};
+use crate::utilities::trusted_proxies::TrustedProxies;
 
 pub async fn authenticate<B, C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies, mut req: Request<B>, next: Next<B>) -> Result<Response, StatusCode> {
-    if let Some(cookie) = cookies.get("session_id") {
+    // An access token takes precedence over the cookie, so a request never mixes both
+    if let Some(token) = bearer_token(req.headers()) {
+        if let Ok(session) = server_state.context.service_context().access_token_service().find_session_by_token(token).await {
+            req.extensions_mut().insert(SessionOption::new(Some(session)));
+        }
+    } else if let Some(cookie) = cookies.get("session_id") {
         let session_id = cookie.value();
         // Get the user id associated with the session from the session store
-        if let Ok(session_value) = server_state.context.repository_context().session_repository().find_by_id(session_id, Some(86400)).await {
+        if let Ok(session_value) = server_state.context.service_context().user_service().find_session(session_id.to_string()).await {
             // Pass it to the extension so that handlers/extractors can access it
             req.extensions_mut().insert(
                 SessionOption::new(
@@ -25,4 +39,207 @@ pub async fn authenticate<B, C: ContextTrait>(State(server_state): State<Arc<Ser
     }
 
     Ok(next.run(req).await)
+}
+
+// Token of an `Authorization: Bearer <token>` header
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers.get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+// Header the CSRF token of the session has to be sent in
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+// Requests that can change state need the CSRF token of their session, the cookie alone isn't enough.
+// Runs after authenticate, requests without a session or with an access token have nothing to protect.
+pub async fn csrf_protect<B, C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, req: Request<B>, next: Next<B>) -> Response {
+    let method = req.method();
+    if method == Method::GET || method == Method::HEAD || method == Method::OPTIONS {
+        return next.run(req).await;
+    }
+
+    let session_id = req.extensions().get::<SessionOption>()
+        .and_then(|session| session.session_opt.as_ref())
+        .filter(|session| !session.is_access_token())
+        .map(|session| session.get_id());
+    if let Some(session_id) = session_id {
+        let token = req.headers().get(CSRF_HEADER).and_then(|value| value.to_str().ok());
+        let result = match token {
+            Some(token) => server_state.csrf_tokens.verify(&session_id, token),
+            None => Err(ServerError::InvalidCsrfToken)
+        };
+        if let Err(e) = result {
+            return e.into_response();
+        }
+    }
+    next.run(req).await
+}
+
+// Type level roles for RequireRole
+pub trait RequiredRole: Send + Sync {
+    const ROLE: Role;
+}
+
+pub struct Moderator;
+
+impl RequiredRole for Moderator {
+    const ROLE: Role = Role::Moderator;
+}
+
+pub struct Admin;
+
+impl RequiredRole for Admin {
+    const ROLE: Role = Role::Admin;
+}
+
+// Extracts the session of a user with at least the privileges of role R,
+// rejects with 401 without a session and with 403 if the role is insufficient.
+pub struct RequireRole<R: RequiredRole> {
+    pub session: SessionFromStore,
+    marker: PhantomData<R>,
+}
+
+#[async_trait]
+impl<S: Send + Sync, R: RequiredRole> FromRequestParts<S> for RequireRole<R> {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let session = parts.extensions.get::<SessionOption>()
+            .and_then(|session| session.session_opt.clone());
+        match session {
+            Some(session) if session.get_role().has_privileges_of(R::ROLE) => Ok(Self {
+                session,
+                marker: PhantomData,
+            }),
+            Some(_) => Err(ServerError::Forbidden.into_response()),
+            None => Err(StatusCode::UNAUTHORIZED.into_response())
+        }
+    }
+}
+
+// Type level scopes for RequireScope
+pub trait RequiredScope: Send + Sync {
+    const SCOPE: Scope;
+}
+
+pub struct FiguresWriteScope;
+
+impl RequiredScope for FiguresWriteScope {
+    const SCOPE: Scope = Scope::FiguresWrite;
+}
+
+pub struct ProfileWriteScope;
+
+impl RequiredScope for ProfileWriteScope {
+    const SCOPE: Scope = Scope::ProfileWrite;
+}
+
+pub struct ModerationScope;
+
+impl RequiredScope for ModerationScope {
+    const SCOPE: Scope = Scope::Moderation;
+}
+
+pub struct AdminScope;
+
+impl RequiredScope for AdminScope {
+    const SCOPE: Scope = Scope::Admin;
+}
+
+// Rejects with 403 requests of access tokens without scope S,
+// requests without a session are left to the route.
+pub struct RequireScope<S: RequiredScope> {
+    marker: PhantomData<S>,
+}
+
+#[async_trait]
+impl<St: Send + Sync, S: RequiredScope> FromRequestParts<St> for RequireScope<S> {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &St) -> Result<Self, Self::Rejection> {
+        let session = parts.extensions.get::<SessionOption>()
+            .and_then(|session| session.session_opt.as_ref());
+        match session {
+            Some(session) if !session.has_scope(S::SCOPE) => Err(ServerError::InsufficientScope.into_response()),
+            _ => Ok(Self {
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+// Rejects with 403 every request of an access token, for routes managing the account itself
+pub async fn reject_access_tokens<B>(req: Request<B>, next: Next<B>) -> Response {
+    let is_access_token = req.extensions().get::<SessionOption>()
+        .and_then(|session| session.session_opt.as_ref())
+        .is_some_and(|session| session.is_access_token());
+    match is_access_token {
+        true => ServerError::InsufficientScope.into_response(),
+        false => next.run(req).await
+    }
+}
+
+// Whether users have to verify their email before they can post content
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmailVerificationPolicy {
+    Optional,
+    RequiredToPost,
+}
+
+// Extracts the session of a user that is allowed to post content by the email verification policy,
+// rejects with 401 without a session and with 403 if the email has to be verified first.
+// Every route that creates, changes or removes what other users see takes it: figures, comments, likes,
+// follows and the profile. Account, session and security settings stay available without verifying.
+pub struct VerifiedSession {
+    pub session: SessionFromStore,
+}
+
+#[async_trait]
+impl<C: ContextTrait> FromRequestParts<Arc<ServerState<C>>> for VerifiedSession {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, server_state: &Arc<ServerState<C>>) -> Result<Self, Self::Rejection> {
+        let session = match parts.extensions.get::<SessionOption>().and_then(|session| session.session_opt.clone()) {
+            Some(session) => session,
+            None => return Err(StatusCode::UNAUTHORIZED.into_response())
+        };
+
+        if server_state.email_verification_policy == EmailVerificationPolicy::RequiredToPost {
+            // Checked against the user instead of the session, so verifying takes effect for existing sessions
+            match server_state.context.service_context().user_service().is_email_verified(session.get_user_id()).await {
+                Ok(true) => {}
+                Ok(false) => return Err(ServerError::EmailNotVerified.into_response()),
+                Err(e) => return Err(e.into_response())
+            }
+        }
+        Ok(Self {
+            session
+        })
+    }
+}
+
+// Extracts the client of a request
+#[async_trait]
+impl<C: ContextTrait> FromRequestParts<Arc<ServerState<C>>> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, server_state: &Arc<ServerState<C>>) -> Result<Self, Self::Rejection> {
+        let user_agent = parts.headers.get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        Ok(Self {
+            user_agent,
+            ip: client_ip(&parts.headers, &parts.extensions, &server_state.trusted_proxies).map(|ip| ip.to_string()),
+        })
+    }
+}
+
+// Ip of the client, X-Forwarded-For is only taken into account for requests from trusted proxies
+pub fn client_ip(headers: &HeaderMap, extensions: &Extensions, trusted_proxies: &TrustedProxies) -> Option<IpAddr> {
+    extensions.get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(address)| trusted_proxies.client_ip(address.ip(), headers))
 }
\ No newline at end of file
@@ -1,16 +1,102 @@
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use async_trait::async_trait;
 use aws_credential_types::provider::SharedCredentialsProvider;
 use aws_sdk_s3::{Client, Config, Credentials};
 use aws_sdk_s3::Region;
+use aws_sdk_s3::presigning::config::PresigningConfig;
 use aws_sdk_s3::types::ByteStream;
 use bytes::Bytes;
+use tracing::{error, warn};
+use crate::entities::object_deletion::ObjectDeletion;
+use crate::repositories::traits::{ObjectDeletionRepositoryTrait, TransactionTrait};
 use crate::server_errors::ServerError;
 
+// A failed deletion is first retried after a minute, every next retry waits twice as long up to a day
+const FIRST_RETRY_AFTER: i64 = 60;
+const MAX_RETRY_AFTER: i64 = 86400;
+const RETRY_BATCH_SIZE: i32 = 100;
+// Failing deletions are logged as errors from then on
+const RETRIES_BEFORE_ERROR: i32 = 5;
+
 #[async_trait]
-pub trait ContentStore: Send + Sync + Clone {
-    async fn upload_image(&self, name: &str, bytes: Bytes) -> Result<String, ServerError>;
+pub trait ContentStore: Send + Sync + Clone + 'static {
+    // Returns the url the object is served at
+    async fn upload_object(&self, name: &str, bytes: Bytes, content_type: &str) -> Result<String, ServerError>;
+    // Streams the file from disk instead of loading it in memory
+    async fn upload_file(&self, name: &str, path: &Path, content_type: &str) -> Result<(), ServerError>;
+    async fn get_object(&self, name: &str) -> Result<Bytes, ServerError>;
+    // Presigned url to download the object without making it public, errors if the object doesn't exist
+    async fn get_download_url(&self, name: &str, expires_in: Duration) -> Result<String, ServerError>;
+    async fn delete_object(&self, name: &str) -> Result<(), ServerError>;
     fn get_base_url(&self) -> String;
+
+    async fn upload_image(&self, name: &str, bytes: Bytes) -> Result<String, ServerError> {
+        self.upload_object(name, bytes, "image/jpeg").await
+    }
+
+    // Name of the object behind a url returned by upload_image
+    fn get_object_name(&self, url: &str) -> Option<String> {
+        url.strip_prefix(&self.get_base_url()).map(str::to_string)
+    }
+}
+
+// Deletions of objects are queued in the database, in the same transaction that stops referencing them,
+// so an object is never left behind when the store fails or the server restarts before it is removed.
+// Urls that aren't part of the store are skipped.
+pub async fn queue_object_deletions<S, T, D>(store: &S, deletion_repository: &D, transaction: &mut T, urls: Vec<String>) -> Result<Vec<ObjectDeletion>, ServerError>
+    where S: ContentStore, T: TransactionTrait, D: ObjectDeletionRepositoryTrait<T> {
+    let names = urls.into_iter()
+        .filter_map(|url| {
+            let name = store.get_object_name(&url);
+            if name.is_none() {
+                warn!("Url {} is not part of the content store, skipping deletion", url);
+            }
+            name
+        })
+        .collect::<Vec<String>>();
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    deletion_repository.create(Some(transaction), names, FIRST_RETRY_AFTER).await
+}
+
+// First attempt of queued deletions, made once their transaction committed.
+// Failed deletions stay queued and are retried by retry_object_deletions.
+pub async fn delete_queued_objects<S, T, D>(store: &S, deletion_repository: &D, deletions: Vec<ObjectDeletion>)
+    where S: ContentStore, T: TransactionTrait, D: ObjectDeletionRepositoryTrait<T> {
+    for deletion in deletions {
+        match store.delete_object(&deletion.name).await {
+            Ok(()) => {
+                // Deleting an object twice is harmless, a deletion that stays queued is only retried once more
+                if let Err(e) = deletion_repository.delete_by_id(None, deletion.id).await {
+                    warn!("Deleted object {} but failed to remove it from the queue: {}", deletion.name, e);
+                }
+            }
+            Err(e) => warn!("Failed to delete object {}, it stays queued to be retried: {}", deletion.name, e)
+        }
+    }
+}
+
+// Retries the queued deletions that are due, run periodically by the deletion worker
+pub async fn retry_object_deletions<S, T, D>(store: &S, deletion_repository: &D) -> Result<(), ServerError>
+    where S: ContentStore, T: TransactionTrait, D: ObjectDeletionRepositoryTrait<T> {
+    for deletion in deletion_repository.find_due(None, RETRY_BATCH_SIZE).await? {
+        match store.delete_object(&deletion.name).await {
+            Ok(()) => deletion_repository.delete_by_id(None, deletion.id).await?,
+            Err(e) => {
+                let attempts = deletion.attempts + 1;
+                match attempts > RETRIES_BEFORE_ERROR {
+                    true => error!("Retry {} of deleting object {} failed: {}", attempts, deletion.name, e),
+                    false => warn!("Retry {} of deleting object {} failed: {}", attempts, deletion.name, e)
+                }
+                let retry_after = FIRST_RETRY_AFTER.saturating_mul(1 << attempts.min(20)).min(MAX_RETRY_AFTER);
+                deletion_repository.postpone(None, deletion.id, retry_after).await?;
+            }
+        }
+    }
+    Ok(())
 }
 
 #[derive(Clone)]
@@ -22,17 +108,66 @@ pub struct S3Storage {
 
 #[async_trait]
 impl ContentStore for S3Storage {
-    async fn upload_image(&self, name: &str, bytes: Bytes) -> Result<String, ServerError> {
+    async fn upload_object(&self, name: &str, bytes: Bytes, content_type: &str) -> Result<String, ServerError> {
         self.client.put_object()
             .bucket(&self.bucket)
             .key(name)
-            .content_type("image/jpeg")
+            .content_type(content_type)
             .body(ByteStream::from(bytes))
             .send().await
             .map(|_| format!("{}{}", self.base_storage_url, name))
             .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
     }
 
+    async fn upload_file(&self, name: &str, path: &Path, content_type: &str) -> Result<(), ServerError> {
+        let body = ByteStream::from_path(path).await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        self.client.put_object()
+            .bucket(&self.bucket)
+            .key(name)
+            .content_type(content_type)
+            .body(body)
+            .send().await
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn get_object(&self, name: &str) -> Result<Bytes, ServerError> {
+        let object = self.client.get_object()
+            .bucket(&self.bucket)
+            .key(name)
+            .send().await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        object.body.collect().await
+            .map(|data| data.into_bytes())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn get_download_url(&self, name: &str, expires_in: Duration) -> Result<String, ServerError> {
+        self.client.head_object()
+            .bucket(&self.bucket)
+            .key(name)
+            .send().await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        let presigning_config = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        self.client.get_object()
+            .bucket(&self.bucket)
+            .key(name)
+            .presigned(presigning_config).await
+            .map(|request| request.uri().to_string())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn delete_object(&self, name: &str) -> Result<(), ServerError> {
+        self.client.delete_object()
+            .bucket(&self.bucket)
+            .key(name)
+            .send().await
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
     fn get_base_url(&self) -> String {
         self.base_storage_url.clone()
     }
@@ -57,4 +192,13 @@ impl S3Storage {
             base_storage_url
         }
     }
+
+    // Store for a bucket that isn't public, its objects are only handed out through presigned urls
+    pub fn private_store(&self, bucket: String) -> Self {
+        Self {
+            client: self.client.clone(),
+            bucket,
+            base_storage_url: String::new()
+        }
+    }
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 use crate::repositories::traits::{FigureRepositoryTrait, ProfileRepositoryTrait, SessionRepositoryTrait, TransactionCreatorTrait, TransactionTrait, UserRepositoryTrait};
-use crate::services::traits::{FigureServiceTrait, ProfileServiceTrait, UserServiceTrait};
+use crate::services::traits::{AccessTokenServiceTrait, AccountServiceTrait, CommentServiceTrait, DataExportServiceTrait, FigureServiceTrait, FollowServiceTrait, LikeServiceTrait, OidcServiceTrait, ProfileServiceTrait, RateLimitServiceTrait, SearchServiceTrait, UserServiceTrait};
 
 pub trait ContextTrait: Send + Sync {
     type ServiceContext: ServiceContextTrait;
@@ -38,33 +38,82 @@ pub trait ServiceContextTrait: Send + Sync {
     type UserService: UserServiceTrait;
     type ProfileService: ProfileServiceTrait;
     type FigureService: FigureServiceTrait;
+    type RateLimitService: RateLimitServiceTrait;
+    type AccessTokenService: AccessTokenServiceTrait;
+    type OidcService: OidcServiceTrait;
+    type AccountService: AccountServiceTrait;
+    type DataExportService: DataExportServiceTrait;
+    type SearchService: SearchServiceTrait;
+    type LikeService: LikeServiceTrait;
+    type CommentService: CommentServiceTrait;
+    type FollowService: FollowServiceTrait;
     fn user_service(&self) -> &Self::UserService;
     fn profile_service(&self) -> &Self::ProfileService;
     fn figure_service(&self) -> &Self::FigureService;
+    fn rate_limit_service(&self) -> &Self::RateLimitService;
+    fn access_token_service(&self) -> &Self::AccessTokenService;
+    fn oidc_service(&self) -> &Self::OidcService;
+    fn account_service(&self) -> &Self::AccountService;
+    fn data_export_service(&self) -> &Self::DataExportService;
+    fn search_service(&self) -> &Self::SearchService;
+    fn like_service(&self) -> &Self::LikeService;
+    fn comment_service(&self) -> &Self::CommentService;
+    fn follow_service(&self) -> &Self::FollowService;
 }
 
-pub struct ServiceContext<US, PS, FS> {
+pub struct ServiceContext<US, PS, FS, RS, AS, OS, ACS, DS, SS, LS, CS, FLS> {
     user_service: US,
     profile_service: PS,
     figure_service: FS,
+    rate_limit_service: RS,
+    access_token_service: AS,
+    oidc_service: OS,
+    account_service: ACS,
+    data_export_service: DS,
+    search_service: SS,
+    like_service: LS,
+    comment_service: CS,
+    follow_service: FLS,
 }
 
-impl<US, PS, FS> ServiceContext<US, PS, FS> {
-    pub fn new(user_service: US, profile_service: PS, figure_service: FS)
-               -> ServiceContext<US, PS, FS> {
+impl<US, PS, FS, RS, AS, OS, ACS, DS, SS, LS, CS, FLS> ServiceContext<US, PS, FS, RS, AS, OS, ACS, DS, SS, LS, CS, FLS> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(user_service: US, profile_service: PS, figure_service: FS, rate_limit_service: RS, access_token_service: AS, oidc_service: OS, account_service: ACS, data_export_service: DS, search_service: SS, like_service: LS, comment_service: CS, follow_service: FLS)
+               -> ServiceContext<US, PS, FS, RS, AS, OS, ACS, DS, SS, LS, CS, FLS> {
         ServiceContext {
             user_service,
             profile_service,
             figure_service,
+            rate_limit_service,
+            access_token_service,
+            oidc_service,
+            account_service,
+            data_export_service,
+            search_service,
+            like_service,
+            comment_service,
+            follow_service,
         }
     }
 }
 
-impl<US, PS, FS> ServiceContextTrait for ServiceContext<US, PS, FS>
-    where US: UserServiceTrait, PS: ProfileServiceTrait, FS: FigureServiceTrait {
+impl<US, PS, FS, RS, AS, OS, ACS, DS, SS, LS, CS, FLS> ServiceContextTrait for ServiceContext<US, PS, FS, RS, AS, OS, ACS, DS, SS, LS, CS, FLS>
+    where US: UserServiceTrait, PS: ProfileServiceTrait, FS: FigureServiceTrait, RS: RateLimitServiceTrait,
+          AS: AccessTokenServiceTrait, OS: OidcServiceTrait, ACS: AccountServiceTrait,
+          DS: DataExportServiceTrait, SS: SearchServiceTrait, LS: LikeServiceTrait,
+          CS: CommentServiceTrait, FLS: FollowServiceTrait {
     type UserService = US;
     type ProfileService = PS;
     type FigureService = FS;
+    type RateLimitService = RS;
+    type AccessTokenService = AS;
+    type OidcService = OS;
+    type AccountService = ACS;
+    type DataExportService = DS;
+    type SearchService = SS;
+    type LikeService = LS;
+    type CommentService = CS;
+    type FollowService = FLS;
 
     fn user_service(&self) -> &Self::UserService {
         &self.user_service
@@ -77,6 +126,42 @@ impl<US, PS, FS> ServiceContextTrait for ServiceContext<US, PS, FS>
     fn figure_service(&self) -> &Self::FigureService {
         &self.figure_service
     }
+
+    fn rate_limit_service(&self) -> &Self::RateLimitService {
+        &self.rate_limit_service
+    }
+
+    fn access_token_service(&self) -> &Self::AccessTokenService {
+        &self.access_token_service
+    }
+
+    fn oidc_service(&self) -> &Self::OidcService {
+        &self.oidc_service
+    }
+
+    fn account_service(&self) -> &Self::AccountService {
+        &self.account_service
+    }
+
+    fn data_export_service(&self) -> &Self::DataExportService {
+        &self.data_export_service
+    }
+
+    fn search_service(&self) -> &Self::SearchService {
+        &self.search_service
+    }
+
+    fn like_service(&self) -> &Self::LikeService {
+        &self.like_service
+    }
+
+    fn comment_service(&self) -> &Self::CommentService {
+        &self.comment_service
+    }
+
+    fn follow_service(&self) -> &Self::FollowService {
+        &self.follow_service
+    }
 }
 
 pub trait RepositoryContextTrait: Send + Sync {
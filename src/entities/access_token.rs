@@ -0,0 +1,123 @@
+use std::fmt::{Display, Formatter};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Error, FromRow, Row};
+use sqlx::postgres::PgRow;
+use crate::entities::types::IdType;
+
+// Personal access token of a user for API clients, only the hash of the token is stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessToken {
+    pub id: IdType,
+    pub user_id: IdType,
+    pub name: String,
+    pub token_hash: String,
+    pub scopes: Vec<Scope>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+// What an access token may be used for, sessions of a signed in browser aren't limited by scopes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    #[serde(rename = "figures:write")]
+    FiguresWrite,
+    #[serde(rename = "profile:write")]
+    ProfileWrite,
+    // Only grants anything to tokens of moderators and admins
+    #[serde(rename = "moderation")]
+    Moderation,
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Scope::FiguresWrite => "figures:write",
+            Scope::ProfileWrite => "profile:write",
+            Scope::Moderation => "moderation",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+impl TryFrom<String> for Scope {
+    type Error = anyhow::Error;
+
+    fn try_from(scope: String) -> Result<Self, Self::Error> {
+        match scope.as_str() {
+            "figures:write" => Ok(Scope::FiguresWrite),
+            "profile:write" => Ok(Scope::ProfileWrite),
+            "moderation" => Ok(Scope::Moderation),
+            "admin" => Ok(Scope::Admin),
+            _ => Err(anyhow!("Unknown scope: {}", scope))
+        }
+    }
+}
+
+pub enum AccessTokenDef {
+    Table,
+    Id,
+    UserId,
+    Name,
+    TokenHash,
+    Scopes,
+    CreatedAt,
+    LastUsedAt,
+}
+
+impl AccessTokenDef {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AccessTokenDef::Table => "access_token",
+            AccessTokenDef::Id => "id",
+            AccessTokenDef::UserId => "user_id",
+            AccessTokenDef::Name => "name",
+            AccessTokenDef::TokenHash => "token_hash",
+            AccessTokenDef::Scopes => "scopes",
+            AccessTokenDef::CreatedAt => "created_at",
+            AccessTokenDef::LastUsedAt => "last_used_at",
+        }
+    }
+
+    pub fn as_table_str(&self) -> &str {
+        match self {
+            AccessTokenDef::Table => "access_token",
+            AccessTokenDef::Id => "access_token.id",
+            AccessTokenDef::UserId => "access_token.user_id",
+            AccessTokenDef::Name => "access_token.name",
+            AccessTokenDef::TokenHash => "access_token.token_hash",
+            AccessTokenDef::Scopes => "access_token.scopes",
+            AccessTokenDef::CreatedAt => "access_token.created_at",
+            AccessTokenDef::LastUsedAt => "access_token.last_used_at",
+        }
+    }
+}
+
+impl Display for AccessTokenDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", &self.as_table_str())
+    }
+}
+
+impl FromRow<'_, PgRow> for AccessToken {
+    fn from_row(row: &PgRow) -> Result<Self, Error> {
+        let scopes: Vec<String> = row.try_get(AccessTokenDef::Scopes.as_str())?;
+        let scopes = scopes.into_iter()
+            .map(Scope::try_from)
+            .collect::<Result<Vec<Scope>, anyhow::Error>>()
+            .map_err(|e| Error::Decode(e.into()))?;
+
+        Ok(AccessToken {
+            id: row.try_get(AccessTokenDef::Id.as_str())?,
+            user_id: row.try_get(AccessTokenDef::UserId.as_str())?,
+            name: row.try_get(AccessTokenDef::Name.as_str())?,
+            token_hash: row.try_get(AccessTokenDef::TokenHash.as_str())?,
+            scopes,
+            created_at: row.try_get(AccessTokenDef::CreatedAt.as_str())?,
+            last_used_at: row.try_get(AccessTokenDef::LastUsedAt.as_str())?,
+        })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,56 @@
+use std::fmt::{Display, Formatter};
+
+// A comment on a figure, replies point to the comment that starts their thread
+pub enum CommentDef {
+    Table,
+    Id,
+    FigureId,
+    ProfileId,
+    // Only set on replies
+    ParentId,
+    Content,
+    CreatedAt,
+    // Only set once the comment was edited
+    EditedAt,
+}
+
+impl CommentDef {
+    pub fn as_str(&self) -> &str {
+        match self {
+            CommentDef::Table => "comment",
+            CommentDef::Id => "id",
+            CommentDef::FigureId => "figure_id",
+            CommentDef::ProfileId => "profile_id",
+            CommentDef::ParentId => "parent_id",
+            CommentDef::Content => "content",
+            CommentDef::CreatedAt => "created_at",
+            CommentDef::EditedAt => "edited_at",
+        }
+    }
+
+    pub fn as_table_str(&self) -> &str {
+        match self {
+            CommentDef::Table => "comment",
+            CommentDef::Id => "comment.id",
+            CommentDef::FigureId => "comment.figure_id",
+            CommentDef::ProfileId => "comment.profile_id",
+            CommentDef::ParentId => "comment.parent_id",
+            CommentDef::Content => "comment.content",
+            CommentDef::CreatedAt => "comment.created_at",
+            CommentDef::EditedAt => "comment.edited_at",
+        }
+    }
+
+    pub fn unique(&self) -> &str {
+        match self {
+            CommentDef::Id => "comment_id",
+            _ => self.as_table_str(),
+        }
+    }
+}
+
+impl Display for CommentDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", &self.as_table_str())
+    }
+}
\ No newline at end of file
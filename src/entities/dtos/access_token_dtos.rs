@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use crate::entities::access_token::{AccessToken, Scope};
+use crate::entities::types::IdType;
+
+// Access token as listed to its user, without its hash
+#[derive(Serialize, Debug)]
+pub struct AccessTokenDTO {
+    pub id: IdType,
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<AccessToken> for AccessTokenDTO {
+    fn from(access_token: AccessToken) -> Self {
+        Self {
+            id: access_token.id,
+            name: access_token.name,
+            scopes: access_token.scopes,
+            created_at: access_token.created_at,
+            last_used_at: access_token.last_used_at,
+        }
+    }
+}
+
+// Only returned when creating the token
+#[derive(Serialize, Debug)]
+pub struct CreatedAccessTokenDTO {
+    pub token: String,
+    pub access_token: AccessTokenDTO,
+}
\ No newline at end of file
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{Error, FromRow, Row};
+use sqlx::postgres::PgRow;
+use crate::entities::comment::CommentDef;
+use crate::entities::dtos::profile_dto::ProfileDTO;
+use crate::entities::profile::Profile;
+use crate::entities::types::IdType;
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct CommentDTO {
+    pub id: IdType,
+    pub figure_id: IdType,
+    pub parent_id: Option<IdType>,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub profile: ProfileDTO,
+    // Always 0 for replies
+    pub reply_count: IdType,
+}
+
+impl CommentDTO {
+    pub fn to_json_string(&self) -> String {
+        json!({
+            "comment": &self
+        }).to_string()
+    }
+}
+
+impl FromRow<'_, PgRow> for CommentDTO {
+    fn from_row(row: &PgRow) -> Result<Self, Error> {
+        let profile = Profile::from_row(row)?;
+
+        Ok(CommentDTO {
+            id: row.try_get(CommentDef::Id.unique())?,
+            figure_id: row.try_get(CommentDef::FigureId.as_str())?,
+            parent_id: row.try_get(CommentDef::ParentId.as_str())?,
+            content: row.try_get(CommentDef::Content.as_str())?,
+            created_at: row.try_get(CommentDef::CreatedAt.as_str())?,
+            edited_at: row.try_get(CommentDef::EditedAt.as_str())?,
+            profile: ProfileDTO::from(profile),
+            reply_count: row.try_get("reply_count")?,
+        })
+    }
+}
\ No newline at end of file
@@ -1,13 +1,13 @@
 use serde::Serialize;
 use serde_json::{json, Value};
-use sqlx::{Error, FromRow};
+use sqlx::{Error, FromRow, Row};
 use sqlx::postgres::PgRow;
 use crate::entities::dtos::profile_dto::ProfileDTO;
-use crate::entities::figure::Figure;
+use crate::entities::figure::{Figure, FigureDef};
 use crate::entities::profile::Profile;
 use crate::entities::types::IdType;
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, PartialEq)]
 pub struct FigureDTO {
     pub id: IdType,
     pub title: String,
@@ -15,7 +15,20 @@ pub struct FigureDTO {
     pub width: i32,
     pub height: i32,
     pub url: String,
-    pub profile: ProfileDTO
+    pub profile: ProfileDTO,
+    pub tags: Vec<String>,
+    pub like_count: IdType,
+    // Whether the profile of the request liked the figure, false without a session
+    pub liked_by_me: bool,
+}
+
+// A figure in the liked figures of a profile, paginated by the like instead of the figure
+#[derive(Serialize, Debug, PartialEq)]
+pub struct LikedFigureDTO {
+    #[serde(skip)]
+    pub like_id: IdType,
+    #[serde(flatten)]
+    pub figure: FigureDTO,
 }
 
 impl FigureDTO {
@@ -29,7 +42,7 @@ impl FigureDTO {
         })
     }
 
-    pub fn from(figure: Figure, profile_dto: ProfileDTO) -> Self {
+    pub fn from(figure: Figure, profile_dto: ProfileDTO, tags: Vec<String>, like_count: IdType) -> Self {
         Self {
             id: figure.id,
             title: figure.title,
@@ -38,6 +51,9 @@ impl FigureDTO {
             height: figure.height,
             url: figure.url,
             profile: profile_dto,
+            tags,
+            like_count,
+            liked_by_me: false,
         }
     }
 }
@@ -47,6 +63,8 @@ impl FromRow<'_, PgRow> for FigureDTO {
         let figure = Figure::from_row(row)?;
         let profile = Profile::from_row(row)?;
         let profile_dto = ProfileDTO::from(profile);
+        let tags: Vec<String> = row.try_get("tags")?;
+        let like_count: IdType = row.try_get(FigureDef::LikeCount.as_str())?;
 
         Ok(FigureDTO {
             id: figure.id,
@@ -56,6 +74,18 @@ impl FromRow<'_, PgRow> for FigureDTO {
             height: figure.height,
             url: figure.url,
             profile: profile_dto,
+            tags,
+            like_count,
+            liked_by_me: false,
+        })
+    }
+}
+
+impl FromRow<'_, PgRow> for LikedFigureDTO {
+    fn from_row(row: &PgRow) -> Result<Self, Error> {
+        Ok(LikedFigureDTO {
+            like_id: row.try_get("like_id")?,
+            figure: FigureDTO::from_row(row)?,
         })
     }
 }
\ No newline at end of file
@@ -1,4 +1,7 @@
 pub mod user_dto;
 pub mod profile_dto;
 pub mod figure_dto;
-pub mod session_dtos;
\ No newline at end of file
+pub mod session_dtos;
+pub mod two_factor_dtos;
+pub mod access_token_dtos;
+pub mod comment_dto;
\ No newline at end of file
@@ -1,5 +1,8 @@
 use serde::Serialize;
 use serde_json::json;
+use sqlx::{Error, FromRow, Row};
+use sqlx::postgres::PgRow;
+use crate::entities::follow::FollowDef;
 use crate::entities::profile::Profile;
 use crate::entities::types::IdType;
 
@@ -18,6 +21,17 @@ pub struct ProfileWithoutUserIdDTO {
     pub bio: Option<String>,
     pub banner: Option<String>,
     pub profile_picture: Option<String>,
+    pub follower_count: IdType,
+    pub following_count: IdType,
+}
+
+// A profile in the followers or following of a profile, paginated by the follow instead of the profile
+#[derive(Serialize, Debug)]
+pub struct FollowProfileDTO {
+    #[serde(skip)]
+    pub follow_id: IdType,
+    #[serde(flatten)]
+    pub profile: ProfileWithoutUserIdDTO,
 }
 
 impl ProfileDTO {
@@ -57,7 +71,17 @@ impl From<Profile> for ProfileWithoutUserIdDTO {
             bio: profile.bio,
             banner: profile.banner,
             profile_picture: profile.profile_picture,
-
+            follower_count: profile.follower_count,
+            following_count: profile.following_count,
         }
     }
+}
+
+impl FromRow<'_, PgRow> for FollowProfileDTO {
+    fn from_row(row: &PgRow) -> Result<Self, Error> {
+        Ok(FollowProfileDTO {
+            follow_id: row.try_get(FollowDef::Id.unique())?,
+            profile: ProfileWithoutUserIdDTO::from(Profile::from_row(row)?),
+        })
+    }
 }
\ No newline at end of file
@@ -1,23 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::entities::access_token::Scope;
+use crate::entities::dtos::profile_dto::ProfileDTO;
 use crate::entities::types::IdType;
+use crate::entities::user::Role;
+use crate::utilities::token::hash_token;
+
+// Result of checking the password of a user
+#[derive(Debug, PartialEq)]
+pub enum Authentication {
+    SignedIn(ProfileDTO, Session),
+    // Token to complete the sign in with once the second factor is checked
+    TwoFactorRequired(String),
+}
+
+// Client that created a session, shown to the user when listing their sessions
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Session {
     id: String,
     user_id: IdType,
     profile_id: IdType,
+    role: Role,
+    client: ClientInfo,
+    created_at: DateTime<Utc>,
+    last_seen_at: DateTime<Utc>,
+    // Created with remember me, the cookie outlives the browser session
+    persistent: bool,
+    // End of the lifetime of the session, regardless of activity
+    expires_at: Option<DateTime<Utc>>,
     time_until_expiration: Option<usize>,
 }
 
 impl Session {
-    pub fn new(id: String, user_id: IdType, profile_id: IdType, time_until_expiration: Option<usize>) -> Self {
+    pub fn new(id: String, user_id: IdType, profile_id: IdType, role: Role, client: ClientInfo, time_until_expiration: Option<usize>) -> Self {
+        let now = Utc::now();
         Self {
             id,
             user_id,
             profile_id,
+            role,
+            client,
+            created_at: now,
+            last_seen_at: now,
+            persistent: false,
+            expires_at: None,
             time_until_expiration,
         }
     }
 
+    pub fn with_expiry(mut self, persistent: bool, expires_at: Option<DateTime<Utc>>) -> Self {
+        self.persistent = persistent;
+        self.expires_at = expires_at;
+        self
+    }
+
+    // Used for sessions loaded from the store
+    pub fn with_timestamps(mut self, created_at: DateTime<Utc>, last_seen_at: DateTime<Utc>) -> Self {
+        self.created_at = created_at;
+        self.last_seen_at = last_seen_at;
+        self
+    }
+
     pub fn get_id(&self) -> String {
         self.id.clone()
     }
@@ -30,12 +79,95 @@ impl Session {
         self.profile_id
     }
 
-    //TODO config session expiration time
+    pub fn get_role(&self) -> Role {
+        self.role
+    }
+
+    pub fn get_client(&self) -> &ClientInfo {
+        &self.client
+    }
+
+    pub fn get_created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn get_last_seen_at(&self) -> DateTime<Utc> {
+        self.last_seen_at
+    }
+
+    pub fn set_last_seen_at(&mut self, last_seen_at: DateTime<Utc>) {
+        self.last_seen_at = last_seen_at;
+    }
+
+    // Identifies the session towards the user without giving away the session id itself,
+    // also the key of the session in the store
+    pub fn get_public_id(&self) -> String {
+        hash_token(&self.id)
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        self.persistent
+    }
+
+    pub fn get_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
     pub fn get_time_until_expiration(&self) -> Option<usize> {
         self.time_until_expiration
     }
 }
 
+// How long sessions last, in seconds. Every use of a session extends it by the idle timeout,
+// up to the max lifetime counted from its creation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SessionPolicy {
+    pub idle_timeout: usize,
+    pub max_lifetime: usize,
+}
+
+impl SessionPolicy {
+    // Seconds the session should be kept in the store for when used at `now`, None once it has expired
+    pub fn time_until_expiration(&self, expires_at: DateTime<Utc>, now: DateTime<Utc>) -> Option<usize> {
+        let remaining_lifetime = (expires_at - now).num_seconds();
+        match remaining_lifetime > 0 {
+            true => Some(self.idle_timeout.min(remaining_lifetime as usize)),
+            false => None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SessionPolicies {
+    pub default: SessionPolicy,
+    // Used for sign ins with remember me
+    pub remember_me: SessionPolicy,
+}
+
+impl SessionPolicies {
+    pub fn for_session(&self, persistent: bool) -> SessionPolicy {
+        match persistent {
+            true => self.remember_me,
+            false => self.default,
+        }
+    }
+}
+
+impl Default for SessionPolicies {
+    fn default() -> Self {
+        Self {
+            default: SessionPolicy {
+                idle_timeout: 86400,
+                max_lifetime: 86400 * 30,
+            },
+            remember_me: SessionPolicy {
+                idle_timeout: 86400 * 30,
+                max_lifetime: 86400 * 90,
+            },
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SessionOption {
     pub session_opt: Option<SessionFromStore>,
@@ -47,6 +179,11 @@ impl SessionOption {
             session_opt: session,
         }
     }
+
+    // Profile of the request, if signed in
+    pub fn profile_id(&self) -> Option<IdType> {
+        self.session_opt.as_ref().map(|session| session.get_profile_id())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -54,17 +191,32 @@ pub struct SessionFromStore {
     id: String,
     user_id: IdType,
     profile_id: IdType,
+    role: Role,
+    // Only set for requests authenticated with an access token, limited to these scopes
+    scopes: Option<Vec<Scope>>,
+    persistent: bool,
+    // When the browser signed in, not set for access tokens
+    signed_in_at: Option<DateTime<Utc>>,
 }
 
 impl SessionFromStore {
-    pub fn new(id: String, user_id: IdType, profile_id: IdType) -> Self {
+    pub fn new(id: String, user_id: IdType, profile_id: IdType, role: Role) -> Self {
         Self {
             id,
             user_id,
             profile_id,
+            role,
+            scopes: None,
+            persistent: false,
+            signed_in_at: None,
         }
     }
 
+    pub fn with_scopes(mut self, scopes: Vec<Scope>) -> Self {
+        self.scopes = Some(scopes);
+        self
+    }
+
     pub fn get_id(&self) -> String {
         self.id.clone()
     }
@@ -76,6 +228,31 @@ impl SessionFromStore {
     pub fn get_profile_id(&self) -> IdType {
         self.profile_id
     }
+
+    pub fn get_role(&self) -> Role {
+        self.role
+    }
+
+    pub fn is_access_token(&self) -> bool {
+        self.scopes.is_some()
+    }
+
+    // Created with remember me
+    pub fn is_persistent(&self) -> bool {
+        self.persistent
+    }
+
+    pub fn get_signed_in_at(&self) -> Option<DateTime<Utc>> {
+        self.signed_in_at
+    }
+
+    // Sessions of a signed in browser have every scope
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        match &self.scopes {
+            Some(scopes) => scopes.contains(&scope),
+            None => true
+        }
+    }
 }
 
 impl From<Session> for SessionFromStore {
@@ -84,6 +261,57 @@ impl From<Session> for SessionFromStore {
             id: value.id,
             user_id: value.user_id,
             profile_id: value.profile_id,
+            role: value.role,
+            scopes: None,
+            persistent: value.persistent,
+            signed_in_at: Some(value.created_at),
+        }
+    }
+}
+
+// Session found through the index of its user, the store only knows the public id of a session
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionSummary {
+    pub public_id: String,
+    pub user_id: IdType,
+    pub client: ClientInfo,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl From<&Session> for SessionSummary {
+    fn from(session: &Session) -> Self {
+        Self {
+            public_id: session.get_public_id(),
+            user_id: session.user_id,
+            client: session.client.clone(),
+            created_at: session.created_at,
+            last_seen_at: session.last_seen_at,
+        }
+    }
+}
+
+// Session as listed to its user
+#[derive(Serialize, Debug)]
+pub struct SessionDTO {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    // Session of the request listing the sessions
+    pub current: bool,
+}
+
+impl SessionDTO {
+    pub fn from_summary(session: SessionSummary, current_session_id: &str) -> Self {
+        Self {
+            current: session.public_id == hash_token(current_session_id),
+            id: session.public_id,
+            user_agent: session.client.user_agent,
+            ip: session.client.ip,
+            created_at: session.created_at,
+            last_seen_at: session.last_seen_at,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+// Shown once when enrolling, the secret is entered manually or scanned from the uri as a QR code
+#[derive(Serialize, Debug)]
+pub struct TotpEnrolmentDTO {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RecoveryCodesDTO {
+    pub recovery_codes: Vec<String>,
+}
\ No newline at end of file
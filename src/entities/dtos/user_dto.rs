@@ -1,15 +1,26 @@
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use serde_json::json;
+use crate::entities::dtos::profile_dto::ProfileWithoutUserIdDTO;
 use crate::entities::types::IdType;
-use crate::entities::user::User;
+use crate::entities::user::{Role, User, UserAndProfileFromQuery};
 
 #[derive(Serialize, Debug)]
 pub struct UserDTO {
     pub email: String,
-    pub role: String,
+    pub role: Role,
+    pub suspended: bool,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub two_factor_enabled: bool,
     pub id: IdType,
 }
 
+#[derive(Serialize, Debug)]
+pub struct UserWithProfileDTO {
+    pub user: UserDTO,
+    pub profile: ProfileWithoutUserIdDTO,
+}
+
 impl UserDTO {
     pub fn _to_json(&self) -> String {
         json!({
@@ -21,9 +32,27 @@ impl UserDTO {
 impl From<User> for UserDTO {
     fn from(user: User) -> Self {
         Self {
+            two_factor_enabled: user.is_two_factor_enabled(),
             email: user.email,
             role: user.role,
+            suspended: user.suspended,
+            verified_at: user.verified_at,
             id: user.id,
         }
     }
+}
+
+impl From<UserAndProfileFromQuery> for UserWithProfileDTO {
+    fn from(user_and_profile: UserAndProfileFromQuery) -> Self {
+        Self {
+            user: UserDTO::from(user_and_profile.user),
+            profile: ProfileWithoutUserIdDTO::from(user_and_profile.profile),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct DataExportDownloadDTO {
+    // Presigned url of the archive, only valid for a few minutes
+    pub url: String,
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+use std::fmt::{Display, Formatter};
+
+// Identity of a user at an OpenID Connect provider, taken from a validated ID token
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalIdentity {
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    // The provider checked that the email belongs to the user
+    pub email_verified: bool,
+    pub preferred_username: Option<String>,
+}
+
+// Links an identity at a provider to a user, identities are unique by issuer and subject
+pub enum ExternalIdentityDef {
+    Table,
+    UserId,
+    Issuer,
+    Subject,
+}
+
+impl ExternalIdentityDef {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ExternalIdentityDef::Table => "external_identity",
+            ExternalIdentityDef::UserId => "user_id",
+            ExternalIdentityDef::Issuer => "issuer",
+            ExternalIdentityDef::Subject => "subject",
+        }
+    }
+
+    pub fn as_table_str(&self) -> &str {
+        match self {
+            ExternalIdentityDef::Table => "external_identity",
+            ExternalIdentityDef::UserId => "external_identity.user_id",
+            ExternalIdentityDef::Issuer => "external_identity.issuer",
+            ExternalIdentityDef::Subject => "external_identity.subject",
+        }
+    }
+}
+
+impl Display for ExternalIdentityDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", &self.as_table_str())
+    }
+}
\ No newline at end of file
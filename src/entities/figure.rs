@@ -4,7 +4,7 @@ use sqlx::{Error, FromRow, Row};
 use sqlx::postgres::PgRow;
 use crate::entities::types::IdType;
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone, PartialEq)]
 pub struct Figure {
     pub id: IdType,
     pub title: String,
@@ -24,6 +24,10 @@ pub enum FigureDef {
     Height,
     Url,
     ProfileId,
+    // Kept up to date when figures are liked or unliked
+    LikeCount,
+    // Generated by the database, for full-text search
+    SearchVector,
 }
 
 impl FigureDef {
@@ -37,6 +41,8 @@ impl FigureDef {
             FigureDef::Height => "height",
             FigureDef::Url => "url",
             FigureDef::ProfileId => "profile_id",
+            FigureDef::LikeCount => "like_count",
+            FigureDef::SearchVector => "search_vector",
         }
     }
 
@@ -50,6 +56,8 @@ impl FigureDef {
             FigureDef::Height => "figure.height",
             FigureDef::Url => "figure.url",
             FigureDef::ProfileId => "figure.profile_id",
+            FigureDef::LikeCount => "figure.like_count",
+            FigureDef::SearchVector => "figure.search_vector",
         }
     }
 
@@ -0,0 +1,42 @@
+use std::fmt::{Display, Formatter};
+
+// A profile following another profile, the number of followers and followed profiles is kept on the profiles
+pub enum FollowDef {
+    Table,
+    Id,
+    FollowerId,
+    FollowedId,
+}
+
+impl FollowDef {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FollowDef::Table => "follow",
+            FollowDef::Id => "id",
+            FollowDef::FollowerId => "follower_id",
+            FollowDef::FollowedId => "followed_id",
+        }
+    }
+
+    pub fn as_table_str(&self) -> &str {
+        match self {
+            FollowDef::Table => "follow",
+            FollowDef::Id => "follow.id",
+            FollowDef::FollowerId => "follow.follower_id",
+            FollowDef::FollowedId => "follow.followed_id",
+        }
+    }
+
+    pub fn unique(&self) -> &str {
+        match self {
+            FollowDef::Id => "follow_id",
+            _ => self.as_table_str(),
+        }
+    }
+}
+
+impl Display for FollowDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", &self.as_table_str())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+use std::fmt::{Display, Formatter};
+
+// A profile liking a figure, the number of likes is kept on the figure
+pub enum FigureLikeDef {
+    Table,
+    Id,
+    FigureId,
+    ProfileId,
+}
+
+impl FigureLikeDef {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FigureLikeDef::Table => "figure_like",
+            FigureLikeDef::Id => "id",
+            FigureLikeDef::FigureId => "figure_id",
+            FigureLikeDef::ProfileId => "profile_id",
+        }
+    }
+
+    pub fn as_table_str(&self) -> &str {
+        match self {
+            FigureLikeDef::Table => "figure_like",
+            FigureLikeDef::Id => "figure_like.id",
+            FigureLikeDef::FigureId => "figure_like.figure_id",
+            FigureLikeDef::ProfileId => "figure_like.profile_id",
+        }
+    }
+}
+
+impl Display for FigureLikeDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", &self.as_table_str())
+    }
+}
\ No newline at end of file
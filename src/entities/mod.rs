@@ -2,4 +2,15 @@ pub mod figure;
 pub mod profile;
 pub mod user;
 pub mod types;
-pub mod dtos;
\ No newline at end of file
+pub mod dtos;
+pub mod one_time_token;
+pub mod rate_limit;
+pub mod recovery_code;
+pub mod access_token;
+pub mod external_identity;
+pub mod oidc_sign_in;
+pub mod tag;
+pub mod like;
+pub mod comment;
+pub mod follow;
+pub mod object_deletion;
\ No newline at end of file
@@ -0,0 +1,59 @@
+use std::fmt::{Display, Formatter};
+use sqlx::{Error, FromRow, Row};
+use sqlx::postgres::PgRow;
+use crate::entities::types::IdType;
+
+// Object that has to be removed from the content store, recorded in the same transaction
+// that stops referencing it and kept until the store confirmed the removal
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectDeletion {
+    pub id: IdType,
+    pub name: String,
+    pub attempts: i32,
+}
+
+pub enum ObjectDeletionDef {
+    Table,
+    Id,
+    Name,
+    Attempts,
+    NextAttemptAt,
+}
+
+impl ObjectDeletionDef {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ObjectDeletionDef::Table => "object_deletion",
+            ObjectDeletionDef::Id => "id",
+            ObjectDeletionDef::Name => "name",
+            ObjectDeletionDef::Attempts => "attempts",
+            ObjectDeletionDef::NextAttemptAt => "next_attempt_at",
+        }
+    }
+
+    pub fn as_table_str(&self) -> &str {
+        match self {
+            ObjectDeletionDef::Table => "object_deletion",
+            ObjectDeletionDef::Id => "object_deletion.id",
+            ObjectDeletionDef::Name => "object_deletion.name",
+            ObjectDeletionDef::Attempts => "object_deletion.attempts",
+            ObjectDeletionDef::NextAttemptAt => "object_deletion.next_attempt_at",
+        }
+    }
+}
+
+impl Display for ObjectDeletionDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", &self.as_table_str())
+    }
+}
+
+impl FromRow<'_, PgRow> for ObjectDeletion {
+    fn from_row(row: &PgRow) -> Result<Self, Error> {
+        Ok(ObjectDeletion {
+            id: row.try_get(ObjectDeletionDef::Id.as_str())?,
+            name: row.try_get(ObjectDeletionDef::Name.as_str())?,
+            attempts: row.try_get(ObjectDeletionDef::Attempts.as_str())?,
+        })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+// Sign in with an OpenID Connect provider that was started but not yet completed,
+// stored by the hash of the state parameter until the provider redirects back
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OidcSignIn {
+    pub nonce: String,
+    // PKCE verifier, only its challenge is sent along with the authorization request
+    pub code_verifier: String,
+    pub remember_me: bool,
+}
\ No newline at end of file
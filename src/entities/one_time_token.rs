@@ -0,0 +1,21 @@
+// Purpose of a single use token, tokens of one kind can't be used for another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OneTimeTokenKind {
+    PasswordReset,
+    EmailVerification,
+    // Given out for a correct password when the second factor still has to be checked
+    TwoFactorSignIn,
+    // Downloads the archive of a data export
+    DataExport,
+}
+
+impl OneTimeTokenKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            OneTimeTokenKind::PasswordReset => "password_reset",
+            OneTimeTokenKind::EmailVerification => "email_verification",
+            OneTimeTokenKind::TwoFactorSignIn => "two_factor_sign_in",
+            OneTimeTokenKind::DataExport => "data_export",
+        }
+    }
+}
\ No newline at end of file
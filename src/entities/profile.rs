@@ -13,6 +13,8 @@ pub struct Profile {
     pub banner: Option<String>,
     pub profile_picture: Option<String>,
     pub user_id: IdType,
+    pub follower_count: IdType,
+    pub following_count: IdType,
 }
 
 pub enum ProfileDef {
@@ -24,6 +26,11 @@ pub enum ProfileDef {
     Banner,
     ProfilePicture,
     UserId,
+    // Kept up to date when profiles are followed or unfollowed
+    FollowerCount,
+    FollowingCount,
+    // Generated by the database, for full-text search
+    SearchVector,
 }
 
 impl ProfileDef {
@@ -37,6 +44,9 @@ impl ProfileDef {
             ProfileDef::Banner => "banner",
             ProfileDef::ProfilePicture => "profile_picture",
             ProfileDef::UserId => "user_id",
+            ProfileDef::FollowerCount => "follower_count",
+            ProfileDef::FollowingCount => "following_count",
+            ProfileDef::SearchVector => "search_vector",
         }
     }
 
@@ -50,6 +60,9 @@ impl ProfileDef {
             ProfileDef::Banner => "profile.banner",
             ProfileDef::ProfilePicture => "profile.profile_picture",
             ProfileDef::UserId => "profile.user_id",
+            ProfileDef::FollowerCount => "profile.follower_count",
+            ProfileDef::FollowingCount => "profile.following_count",
+            ProfileDef::SearchVector => "profile.search_vector",
         }
     }
 
@@ -77,6 +90,8 @@ impl FromRow<'_, PgRow> for Profile {
         let profile_picture: Option<String> = row.try_get(ProfileDef::ProfilePicture.as_str())?;
         let bio: Option<String> = row.try_get(ProfileDef::Bio.as_str())?;
         let banner: Option<String> = row.try_get(ProfileDef::Banner.as_str())?;
+        let follower_count: IdType = row.try_get(ProfileDef::FollowerCount.as_str())?;
+        let following_count: IdType = row.try_get(ProfileDef::FollowingCount.as_str())?;
 
         Ok(Profile {
             id,
@@ -86,6 +101,8 @@ impl FromRow<'_, PgRow> for Profile {
             bio,
             user_id,
             banner,
+            follower_count,
+            following_count,
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+// At most max_requests within a window of seconds, counted from the first request of the window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub max_requests: u64,
+    pub window: usize,
+}
+
+impl RateLimit {
+    pub const fn new(max_requests: u64, window: usize) -> Self {
+        Self {
+            max_requests,
+            window,
+        }
+    }
+}
\ No newline at end of file
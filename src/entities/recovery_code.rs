@@ -0,0 +1,32 @@
+use std::fmt::{Display, Formatter};
+
+// Single use codes to sign in with when the authenticator of a user is lost, only their hash is stored
+pub enum RecoveryCodeDef {
+    Table,
+    UserId,
+    CodeHash,
+}
+
+impl RecoveryCodeDef {
+    pub fn as_str(&self) -> &str {
+        match self {
+            RecoveryCodeDef::Table => "recovery_code",
+            RecoveryCodeDef::UserId => "user_id",
+            RecoveryCodeDef::CodeHash => "code_hash",
+        }
+    }
+
+    pub fn as_table_str(&self) -> &str {
+        match self {
+            RecoveryCodeDef::Table => "recovery_code",
+            RecoveryCodeDef::UserId => "recovery_code.user_id",
+            RecoveryCodeDef::CodeHash => "recovery_code.code_hash",
+        }
+    }
+}
+
+impl Display for RecoveryCodeDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", &self.as_table_str())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,71 @@
+use std::fmt::{Display, Formatter};
+use serde::Serialize;
+use crate::entities::types::IdType;
+
+// A tag with the number of figures it is on
+#[derive(Serialize, Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct TagCount {
+    pub name: String,
+    pub figure_count: IdType,
+}
+
+pub enum TagDef {
+    Table,
+    Id,
+    Name,
+}
+
+impl TagDef {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TagDef::Table => "tag",
+            TagDef::Id => "id",
+            TagDef::Name => "name",
+        }
+    }
+
+    pub fn as_table_str(&self) -> &str {
+        match self {
+            TagDef::Table => "tag",
+            TagDef::Id => "tag.id",
+            TagDef::Name => "tag.name",
+        }
+    }
+}
+
+impl Display for TagDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", &self.as_table_str())
+    }
+}
+
+// Join table between figures and their tags
+pub enum FigureTagDef {
+    Table,
+    FigureId,
+    TagId,
+}
+
+impl FigureTagDef {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FigureTagDef::Table => "figure_tag",
+            FigureTagDef::FigureId => "figure_id",
+            FigureTagDef::TagId => "tag_id",
+        }
+    }
+
+    pub fn as_table_str(&self) -> &str {
+        match self {
+            FigureTagDef::Table => "figure_tag",
+            FigureTagDef::FigureId => "figure_tag.figure_id",
+            FigureTagDef::TagId => "figure_tag.tag_id",
+        }
+    }
+}
+
+impl Display for FigureTagDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", &self.as_table_str())
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt::{Display, Formatter};
-use serde::{Serialize};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use crate::entities::profile::Profile;
 use crate::entities::types::IdType;
 
@@ -9,7 +11,60 @@ pub struct User {
     pub id: IdType,
     pub email: String,
     pub password: String,
-    pub role: String,
+    #[sqlx(try_from = "String")]
+    pub role: Role,
+    pub suspended: bool,
+    // Not verified as long as this isn't set
+    pub verified_at: Option<DateTime<Utc>>,
+    // Base32 encoded, set from the start of the enrolment
+    pub totp_secret: Option<String>,
+    // Two factor authentication is only required once the enrolment is confirmed
+    pub totp_enabled_at: Option<DateTime<Utc>>,
+    // Time step of the last accepted code, a code can't be used again
+    pub totp_last_used_step: Option<i64>,
+}
+
+impl User {
+    pub fn is_two_factor_enabled(&self) -> bool {
+        self.totp_secret.is_some() && self.totp_enabled_at.is_some()
+    }
+}
+
+// Roles are ordered by privilege, every role has the privileges of the roles before it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    User,
+    Moderator,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Role::User => "user",
+            Role::Moderator => "moderator",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn has_privileges_of(&self, role: Role) -> bool {
+        *self >= role
+    }
+}
+
+impl TryFrom<String> for Role {
+    type Error = anyhow::Error;
+
+    fn try_from(role: String) -> Result<Self, Self::Error> {
+        match role.as_str() {
+            "user" => Ok(Role::User),
+            "moderator" => Ok(Role::Moderator),
+            "admin" => Ok(Role::Admin),
+            _ => Err(anyhow!("Unknown role: {}", role))
+        }
+    }
 }
 
 pub enum UserDef {
@@ -18,6 +73,11 @@ pub enum UserDef {
     Email,
     Password,
     Role,
+    Suspended,
+    VerifiedAt,
+    TotpSecret,
+    TotpEnabledAt,
+    TotpLastUsedStep,
 }
 
 impl UserDef {
@@ -28,6 +88,11 @@ impl UserDef {
             UserDef::Email => "email",
             UserDef::Password => "password",
             UserDef::Role => "role",
+            UserDef::Suspended => "suspended",
+            UserDef::VerifiedAt => "verified_at",
+            UserDef::TotpSecret => "totp_secret",
+            UserDef::TotpEnabledAt => "totp_enabled_at",
+            UserDef::TotpLastUsedStep => "totp_last_used_step",
         }
     }
 
@@ -38,6 +103,11 @@ impl UserDef {
             UserDef::Email => "\"user\".email",
             UserDef::Password => "\"user\".password",
             UserDef::Role => "\"user\".role",
+            UserDef::Suspended => "\"user\".suspended",
+            UserDef::VerifiedAt => "\"user\".verified_at",
+            UserDef::TotpSecret => "\"user\".totp_secret",
+            UserDef::TotpEnabledAt => "\"user\".totp_enabled_at",
+            UserDef::TotpLastUsedStep => "\"user\".totp_last_used_step",
         }
     }
 
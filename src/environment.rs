@@ -13,12 +13,49 @@ pub struct Environment {
     pub s3_endpoint: String,
     pub s3_base_storage_url: String,
     pub s3_bucket: String,
+    // Private bucket for personal data exports
+    pub s3_export_bucket: String,
 
     // CORS origin
     pub origin: String,
 
     pub server_port: u16,
 
+    // Key used to sign pagination cursors
+    pub cursor_secret: Option<String>,
+    // Key used to create CSRF tokens
+    pub csrf_secret: Option<String>,
+
+    // Proxies whose X-Forwarded-For header is used to find the ip of clients
+    pub trusted_proxies: String,
+
+    // Mail (smtp), mails are written to mail_directory when no smtp host is set
+    pub smtp_host: Option<String>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub mail_from: Option<String>,
+    pub mail_directory: String,
+
+    // Session lifetimes in seconds, idle timeouts are extended on every request
+    pub session_idle_timeout: usize,
+    pub session_max_lifetime: usize,
+    pub remember_me_idle_timeout: usize,
+    pub remember_me_max_lifetime: usize,
+
+    // Argon2id cost of password hashes, weaker hashes are upgraded on sign in
+    pub password_memory_cost: u32,
+    pub password_time_cost: u32,
+    pub password_parallelism: u32,
+
+    // Unverified users can't upload figures or update their profile
+    pub require_email_verification: bool,
+
+    // OpenID Connect sign in, disabled when no issuer is set
+    pub oidc_issuer: Option<String>,
+    pub oidc_client_id: Option<String>,
+    pub oidc_client_secret: Option<String>,
+    pub oidc_redirect_uri: Option<String>,
+
     // Loki logging server url & name of running figure-backend instance
     pub loki_host: Option<String>,
     pub loki_url: Option<String>,
@@ -36,15 +73,50 @@ impl Environment {
                 s3_endpoint: env::var("S3_ENDPOINT").expect("No S3_ENDPOINT env found"),
                 s3_base_storage_url: env::var("S3_BASE_STORAGE_URL").expect("No S3_BASE_STORAGE_URL env found"),
                 s3_bucket: env::var("S3_BUCKET").expect("No S3_BUCKET env found"),
+                s3_export_bucket: env::var("S3_EXPORT_BUCKET").expect("No S3_EXPORT_BUCKET env found"),
                 origin: env::var("ORIGIN").expect("No ORIGIN env found"),
                 server_port: env::var("SERVER_PORT").unwrap_or_else(|e| {
                     error!("{}", e);
                     warn!("env SERVER_PORT not found or invalid, defaulting to port 8000");
                     "8000".to_string()
                 }).parse::<u16>().expect("Invalid SERVER_PORT env"),
+                cursor_secret: env::var("CURSOR_SECRET").ok(),
+                csrf_secret: env::var("CSRF_SECRET").ok(),
+                trusted_proxies: env::var("TRUSTED_PROXIES").unwrap_or_default(),
+                smtp_host: env::var("SMTP_HOST").ok(),
+                smtp_username: env::var("SMTP_USERNAME").ok(),
+                smtp_password: env::var("SMTP_PASSWORD").ok(),
+                mail_from: env::var("MAIL_FROM").ok(),
+                mail_directory: env::var("MAIL_DIRECTORY").unwrap_or_else(|_| "mail".to_string()),
+                session_idle_timeout: parse_seconds("SESSION_IDLE_TIMEOUT", 86400),
+                session_max_lifetime: parse_seconds("SESSION_MAX_LIFETIME", 86400 * 30),
+                remember_me_idle_timeout: parse_seconds("REMEMBER_ME_IDLE_TIMEOUT", 86400 * 30),
+                remember_me_max_lifetime: parse_seconds("REMEMBER_ME_MAX_LIFETIME", 86400 * 90),
+                password_memory_cost: parse_number("PASSWORD_MEMORY_COST", 8192),
+                password_time_cost: parse_number("PASSWORD_TIME_COST", 5),
+                password_parallelism: parse_number("PASSWORD_PARALLELISM", 1),
+                require_email_verification: env::var("REQUIRE_EMAIL_VERIFICATION")
+                    .map(|value| value.parse::<bool>().expect("Invalid REQUIRE_EMAIL_VERIFICATION env"))
+                    .unwrap_or(false),
+                oidc_issuer: env::var("OIDC_ISSUER").ok(),
+                oidc_client_id: env::var("OIDC_CLIENT_ID").ok(),
+                oidc_client_secret: env::var("OIDC_CLIENT_SECRET").ok(),
+                oidc_redirect_uri: env::var("OIDC_REDIRECT_URI").ok(),
                 loki_host: env::var("LOKI_HOST").ok(),
                 loki_url: env::var("LOKI_URL").ok(),
             }
         )
     }
+}
+
+fn parse_seconds(name: &str, default: usize) -> usize {
+    env::var(name)
+        .map(|value| value.parse::<usize>().unwrap_or_else(|_| panic!("Invalid {} env", name)))
+        .unwrap_or(default)
+}
+
+fn parse_number(name: &str, default: u32) -> u32 {
+    env::var(name)
+        .map(|value| value.parse::<u32>().unwrap_or_else(|_| panic!("Invalid {} env", name)))
+        .unwrap_or(default)
 }
\ No newline at end of file
@@ -0,0 +1,96 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use async_trait::async_trait;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use uuid::Uuid;
+use crate::server_errors::ServerError;
+
+#[async_trait]
+pub trait Mailer: Send + Sync + Clone + 'static {
+    async fn send_mail(&self, to: &str, subject: &str, body: String) -> Result<(), ServerError>;
+}
+
+#[derive(Clone)]
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(host: &str, username: String, password: String, from: &str) -> Result<Self, anyhow::Error> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::relay(host)?
+            .credentials(Credentials::new(username, password))
+            .build();
+
+        Ok(Self {
+            transport,
+            from: from.parse()?,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send_mail(&self, to: &str, subject: &str, body: String) -> Result<(), ServerError> {
+        let to: Mailbox = to.parse()
+            .map_err(|e: lettre::address::AddressError| ServerError::InternalError(Arc::new(e.into())))?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body)
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        self.transport.send(message).await
+            .map(|_response| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+}
+
+// Writes every mail to a file in the given directory instead of sending it, for local development
+#[derive(Clone)]
+pub struct FileMailer {
+    directory: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(directory: PathBuf) -> Self {
+        Self {
+            directory
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send_mail(&self, to: &str, subject: &str, body: String) -> Result<(), ServerError> {
+        tokio::fs::create_dir_all(&self.directory).await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        let path = self.directory.join(format!("{}.eml", Uuid::new_v4()));
+        let contents = format!("To: {}\nSubject: {}\n\n{}\n", to, subject, body);
+        tokio::fs::write(path, contents).await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+}
+
+// Mailer chosen at startup, SMTP when it is configured and files otherwise
+#[derive(Clone)]
+pub enum ConfiguredMailer {
+    Smtp(SmtpMailer),
+    File(FileMailer),
+}
+
+#[async_trait]
+impl Mailer for ConfiguredMailer {
+    async fn send_mail(&self, to: &str, subject: &str, body: String) -> Result<(), ServerError> {
+        match self {
+            ConfiguredMailer::Smtp(mailer) => mailer.send_mail(to, subject, body).await,
+            ConfiguredMailer::File(mailer) => mailer.send_mail(to, subject, body).await,
+        }
+    }
+}
\ No newline at end of file
@@ -9,16 +9,21 @@ mod repositories;
 mod context;
 mod utilities;
 mod environment;
+mod mailer;
+mod rate_limit_layer;
+mod oidc;
 
 use std::env;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use axum::{Extension, middleware, Router};
 use axum::extract::DefaultBodyLimit;
 use axum::http::header::{ACCEPT, CONTENT_TYPE};
-use axum::http::Method;
+use axum::http::{HeaderName, Method};
+use axum::routing::delete;
 use axum::routing::get;
+use axum::routing::patch;
 use axum::routing::post;
 use redis::aio::ConnectionManager;
 use sqlx::{Pool, Postgres};
@@ -27,37 +32,88 @@ use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_cookies::CookieManagerLayer;
 use tower_http::limit::RequestBodyLimitLayer;
 use url::Url;
-use tracing::info;
-use crate::auth_layer::authenticate;
-use crate::content_store::S3Storage;
+use tracing::{info, warn};
+use rand_core::{OsRng, RngCore};
+use crate::auth_layer::{Admin, AdminScope, authenticate, csrf_protect, CSRF_HEADER, EmailVerificationPolicy, FiguresWriteScope, ModerationScope, ProfileWriteScope, reject_access_tokens, RequireRole, RequireScope};
+use crate::content_store::{retry_object_deletions, S3Storage};
 use crate::context::{Context, ContextTrait, RepositoryContext, ServiceContext};
-use crate::entities::dtos::session_dtos::SessionOption;
+use crate::entities::rate_limit::RateLimit;
+use crate::rate_limit_layer::RateLimitLayer;
+use crate::entities::dtos::session_dtos::{SessionOption, SessionPolicies, SessionPolicy};
 use crate::environment::Environment;
+use crate::mailer::{ConfiguredMailer, FileMailer, SmtpMailer};
+use crate::oidc::{OidcConfig, OidcProvider};
+use crate::repositories::access_token_repository::AccessTokenRepository;
 use crate::repositories::figure_repository::FigureRepository;
+use crate::repositories::comment_repository::CommentRepository;
+use crate::repositories::follow_repository::FollowRepository;
+use crate::repositories::like_repository::LikeRepository;
+use crate::repositories::object_deletion_repository::ObjectDeletionRepository;
+use crate::repositories::oidc_sign_in_repository::OidcSignInRepository;
+use crate::repositories::one_time_token_repository::OneTimeTokenRepository;
 use crate::repositories::profile_repository::ProfileRepository;
+use crate::repositories::rate_limit_repository::RateLimitRepository;
 use crate::repositories::session_repository::SessionRepository;
+use crate::repositories::tag_repository::TagRepository;
 use crate::repositories::transaction::PostgresTransactionCreator;
 use crate::repositories::user_repository::UserRepository;
-use crate::routes::authentication_routes::{load_session, signin_user, signout_user, signup_user};
-use crate::routes::figure_routes::{browse_figures, browse_figures_from_profile, browse_figures_from_profile_starting_from_figure_id, browse_figures_starting_from_figure_id, get_figure, get_total_figures_by_profile, get_total_figures_count, landing_page_figures, upload_figure};
+use crate::routes::access_token_routes::{create_access_token, get_access_tokens, revoke_access_token};
+use crate::routes::account_routes::{delete_account, download_data_export, request_data_export};
+use crate::routes::admin_routes::{admin_browse_user_figures, admin_browse_users, admin_delete_figure, admin_get_user, admin_invalidate_user_sessions, admin_suspend_user, admin_unsuspend_user};
+use crate::routes::authentication_routes::{change_password, complete_two_factor_sign_in, get_csrf_token, load_session, request_password_reset, reset_password, send_email_verification, signin_user, signout_user, signup_user, verify_email};
+use crate::routes::comment_routes::{browse_comments, browse_replies, create_comment, delete_comment, update_comment};
+use crate::routes::figure_routes::{browse_figures, browse_figures_from_profile, browse_figures_from_profile_starting_from_figure_id, browse_figures_starting_from_figure_id, delete_figure, get_figure, get_total_figures_by_profile, get_total_figures_count, landing_page_figures, update_figure, upload_figure};
+use crate::routes::follow_routes::{browse_followers, browse_following, feed, follow_profile, unfollow_profile};
+use crate::routes::like_routes::{browse_liked_figures, like_figure, unlike_figure};
 use crate::routes::misc_routes::healthcheck;
+use crate::routes::moderation_routes::{moderate_delete_comment, moderate_delete_figure};
+use crate::routes::oidc_routes::{complete_oidc_sign_in, start_oidc_sign_in};
+use crate::routes::search_routes::search;
+use crate::routes::session_routes::{get_sessions, revoke_other_sessions, revoke_session};
+use crate::routes::tag_routes::{browse_tag_figures, popular_tags};
 use crate::routes::profile_routes::{get_profile, get_total_profiles_count, update_profile};
+use crate::routes::two_factor_routes::{confirm_totp_enrolment, disable_totp, start_totp_enrolment};
+use crate::services::access_token_service::AccessTokenService;
+use crate::services::account_mailer::AccountMailer;
+use crate::services::account_service::AccountService;
+use crate::services::data_export_service::DataExportService;
 use crate::services::figure_service::FigureService;
+use crate::services::comment_service::CommentService;
+use crate::services::follow_service::FollowService;
+use crate::services::like_service::LikeService;
+use crate::services::oidc_service::OidcService;
 use crate::services::profile_service::ProfileService;
+use crate::services::rate_limit_service::RateLimitService;
+use crate::services::search_service::SearchService;
 use crate::services::user_service::UserService;
+use crate::utilities::csrf::CsrfTokens;
+use crate::utilities::cursor::CursorSigner;
 use crate::utilities::logging::init_logging;
+use crate::utilities::password::PasswordHashPolicy;
 use crate::utilities::secure_rand_generator::ChaCha20;
+use crate::utilities::trusted_proxies::TrustedProxies;
 
 pub struct ServerState<C: ContextTrait> {
     context: C,
     domain: String,
+    // Frontend the browser is sent back to after signing in with a provider
+    origin: String,
+    cursor_signer: CursorSigner,
+    email_verification_policy: EmailVerificationPolicy,
+    csrf_tokens: CsrfTokens,
+    trusted_proxies: TrustedProxies,
 }
 
 impl<C: ContextTrait> ServerState<C> {
-    pub fn new(context: C, domain: String) -> Self {
+    pub fn new(context: C, domain: String, origin: String, cursor_signer: CursorSigner, email_verification_policy: EmailVerificationPolicy, csrf_tokens: CsrfTokens, trusted_proxies: TrustedProxies) -> Self {
         Self {
             context,
             domain,
+            origin,
+            cursor_signer,
+            email_verification_policy,
+            csrf_tokens,
+            trusted_proxies,
         }
     }
 }
@@ -95,6 +151,7 @@ async fn main() -> anyhow::Result<(), anyhow::Error> {
         env.s3_app_id, env.s3_app_key, env.s3_region,
         env.s3_endpoint, env.s3_base_storage_url, env.s3_bucket,
     );
+    let export_store = content_store.private_store(env.s3_export_bucket);
 
     info!("Setting up CORS...");
     let cors = create_app_cors([env.origin.parse()?]);
@@ -106,12 +163,53 @@ async fn main() -> anyhow::Result<(), anyhow::Error> {
     let domain = Url::parse(&env.origin)?.host_str().unwrap().to_string();
     info!("Domain parsed from origin: {}", domain);
 
+    let cursor_signer = create_cursor_signer(env.cursor_secret);
+    let csrf_tokens = create_csrf_tokens(env.csrf_secret);
+
+    let trusted_proxies = TrustedProxies::parse(&env.trusted_proxies).expect("Invalid TRUSTED_PROXIES env");
+    info!("Trusted proxies: {:?}", trusted_proxies);
+
+    let mailer = create_mailer(env.smtp_host, env.smtp_username, env.smtp_password, env.mail_from, env.mail_directory)?;
+    let account_mailer = AccountMailer::new(mailer, env.origin.clone());
+
+    let email_verification_policy = match env.require_email_verification {
+        true => EmailVerificationPolicy::RequiredToPost,
+        false => EmailVerificationPolicy::Optional,
+    };
+    info!("Email verification policy: {:?}", email_verification_policy);
+
+    let session_policies = SessionPolicies {
+        default: SessionPolicy {
+            idle_timeout: env.session_idle_timeout,
+            max_lifetime: env.session_max_lifetime,
+        },
+        remember_me: SessionPolicy {
+            idle_timeout: env.remember_me_idle_timeout,
+            max_lifetime: env.remember_me_max_lifetime,
+        },
+    };
+    info!("Session policies: {:?}", session_policies);
+
+    let password_hash_policy = PasswordHashPolicy {
+        memory_cost: env.password_memory_cost,
+        time_cost: env.password_time_cost,
+        parallelism: env.password_parallelism,
+    };
+    password_hash_policy.params().expect("Invalid PASSWORD_MEMORY_COST, PASSWORD_TIME_COST or PASSWORD_PARALLELISM env");
+    info!("Password hash policy: {:?}", password_hash_policy);
+
+    let identity_provider = create_identity_provider(env.oidc_issuer, env.oidc_client_id, env.oidc_client_secret, env.oidc_redirect_uri);
+
     info!("Waiting for stores...");
     let db_pool = db_pool_future.await??;
     let session_store = session_store_connection_future.await??;
 
+    // Retries deletions the content store failed, including those queued before a restart
+    spawn_object_deletion_worker(content_store.clone(), ObjectDeletionRepository::new(db_pool.clone()));
+
     info!("Creating state...");
-    let server_state = create_state(db_pool, session_store, content_store, domain);
+    let context = create_context(db_pool, session_store, content_store, export_store, account_mailer, session_policies, password_hash_policy, identity_provider);
+    let server_state = Arc::new(ServerState::new(context, domain, env.origin, cursor_signer, email_verification_policy, csrf_tokens, trusted_proxies));
 
     info!("Setting up routes and layers...");
     let app = create_app(server_state, cors, authentication_extension);
@@ -121,7 +219,7 @@ async fn main() -> anyhow::Result<(), anyhow::Error> {
 
     info!("Starting Axum...");
     let axum_server = axum::Server::bind(&addr)
-        .serve(app.into_make_service());
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>());
 
     info!("Server is up at port {}", server_port);
     info!("Ready to serve in {}ms", time_to_start.elapsed().as_millis());
@@ -130,21 +228,43 @@ async fn main() -> anyhow::Result<(), anyhow::Error> {
     Ok(())
 }
 
+// Per ip, or per user when signed in
+const SIGN_IN_RATE_LIMIT: RateLimit = RateLimit::new(20, 60);
+const TWO_FACTOR_SIGN_IN_RATE_LIMIT: RateLimit = RateLimit::new(10, 60);
+const SIGN_UP_RATE_LIMIT: RateLimit = RateLimit::new(5, 3600);
+const PASSWORD_RESET_RATE_LIMIT: RateLimit = RateLimit::new(5, 3600);
+const UPLOAD_RATE_LIMIT: RateLimit = RateLimit::new(30, 3600);
+const DATA_EXPORT_RATE_LIMIT: RateLimit = RateLimit::new(3, 86400);
+const COMMENT_RATE_LIMIT: RateLimit = RateLimit::new(60, 3600);
+
+const OBJECT_DELETION_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
 fn create_app<C: ContextTrait + 'static>(server_state: Arc<ServerState<C>>, cors: CorsLayer, authentication_extension: SessionOption) -> Router {
     Router::new()
-        .route("/profile/update", post(update_profile))
-        .route("/figures/upload", post(upload_figure))
+        .route("/profile/update", post(update_profile).route_layer(middleware::from_extractor::<RequireScope<ProfileWriteScope>>()))
+        .route("/figures/upload", post(upload_figure)
+            .route_layer(RateLimitLayer::new(server_state.clone(), "upload", UPLOAD_RATE_LIMIT))
+            .route_layer(middleware::from_extractor::<RequireScope<FiguresWriteScope>>()))
+        .route("/figures/:id", patch(update_figure).route_layer(middleware::from_extractor::<RequireScope<FiguresWriteScope>>()))
         // Disable the default limit
         .layer(DefaultBodyLimit::disable())
         // Set a different limit
         .layer(RequestBodyLimitLayer::new(5 * 1000000))
 
         .route("/healthcheck", get(healthcheck))
-        .route("/users/signup", post(signup_user))
-        .route("/users/signin", post(signin_user))
+        .route("/users/signup", post(signup_user).route_layer(RateLimitLayer::new(server_state.clone(), "signup", SIGN_UP_RATE_LIMIT)))
+        .route("/users/signin", post(signin_user).route_layer(RateLimitLayer::new(server_state.clone(), "signin", SIGN_IN_RATE_LIMIT)))
+        .route("/users/signin/two-factor", post(complete_two_factor_sign_in).route_layer(RateLimitLayer::new(server_state.clone(), "signin-two-factor", TWO_FACTOR_SIGN_IN_RATE_LIMIT)))
+        .route("/users/oidc/login", get(start_oidc_sign_in).route_layer(RateLimitLayer::new(server_state.clone(), "signin-oidc", SIGN_IN_RATE_LIMIT)))
+        .route("/users/oidc/callback", get(complete_oidc_sign_in).route_layer(RateLimitLayer::new(server_state.clone(), "signin-oidc-callback", SIGN_IN_RATE_LIMIT)))
+        .route("/users/password-reset/request", post(request_password_reset).route_layer(RateLimitLayer::new(server_state.clone(), "password-reset", PASSWORD_RESET_RATE_LIMIT)))
+        .route("/users/password-reset/confirm", post(reset_password))
+        .route("/users/verify-email/confirm", post(verify_email))
+        .route("/users/export/download", post(download_data_export))
         .route("/session/invalidate", post(signout_user))
         .route("/session/load", get(load_session))
         .route("/figures/:id", get(get_figure))
+        .route("/figures/:id", delete(delete_figure).route_layer(middleware::from_extractor::<RequireScope<FiguresWriteScope>>()))
         .route("/figures/browse", get(browse_figures))
         .route("/figures/landing-page", get(landing_page_figures))
         .route("/figures/browse/:starting_from_figure_id", get(browse_figures_starting_from_figure_id))
@@ -154,7 +274,27 @@ fn create_app<C: ContextTrait + 'static>(server_state: Arc<ServerState<C>>, cors
         .route("/profiles/:id", get(get_profile))
         .route("/profiles/count", get(get_total_profiles_count))
         .route("/figures/count", get(get_total_figures_count))
+        .route("/tags/popular", get(popular_tags))
+        .route("/search", get(search))
+        .route("/tags/:tag/figures", get(browse_tag_figures))
+        .route("/figures/:id/like", post(like_figure).delete(unlike_figure).route_layer(middleware::from_extractor::<RequireScope<FiguresWriteScope>>()))
+        .route("/profile/:profile_id/likes", get(browse_liked_figures))
+        .route("/figures/:id/comments", get(browse_comments))
+        .route("/figures/:id/comments", post(create_comment)
+            .route_layer(RateLimitLayer::new(server_state.clone(), "comment", COMMENT_RATE_LIMIT))
+            .route_layer(middleware::from_extractor::<RequireScope<FiguresWriteScope>>()))
+        .route("/comments/:id", patch(update_comment).delete(delete_comment).route_layer(middleware::from_extractor::<RequireScope<FiguresWriteScope>>()))
+        .route("/comments/:id/replies", get(browse_replies))
+        .route("/profiles/:id/follow", post(follow_profile).delete(unfollow_profile).route_layer(middleware::from_extractor::<RequireScope<ProfileWriteScope>>()))
+        .route("/profiles/:id/followers", get(browse_followers))
+        .route("/profiles/:id/following", get(browse_following))
+        .route("/feed", get(feed))
+        .route("/moderation/figures/:id", delete(moderate_delete_figure).route_layer(middleware::from_extractor::<RequireScope<ModerationScope>>()))
+        .route("/moderation/comments/:id", delete(moderate_delete_comment).route_layer(middleware::from_extractor::<RequireScope<ModerationScope>>()))
+        .nest("/admin", create_admin_router())
+        .merge(create_account_router(server_state.clone()))
 
+        .layer(middleware::from_fn_with_state(server_state.clone(), csrf_protect))
         .layer(middleware::from_fn_with_state(server_state.clone(), authenticate))
         .layer(Extension(authentication_extension))
         .layer(CookieManagerLayer::new())
@@ -162,13 +302,55 @@ fn create_app<C: ContextTrait + 'static>(server_state: Arc<ServerState<C>>, cors
         .with_state(server_state)
 }
 
-fn create_state(db_pool: Pool<Postgres>, session_store: ConnectionManager, content_store: S3Storage, domain: String) -> Arc<ServerState<impl ContextTrait>> {
+// Routes managing the account itself, only for signed in browsers and never for access tokens
+fn create_account_router<C: ContextTrait + 'static>(server_state: Arc<ServerState<C>>) -> Router<Arc<ServerState<C>>> {
+    Router::new()
+        .route("/users/two-factor/totp", post(start_totp_enrolment))
+        .route("/users/two-factor/totp/confirm", post(confirm_totp_enrolment))
+        .route("/users/two-factor/totp/disable", post(disable_totp))
+        .route("/users/password", post(change_password))
+        .route("/users/account", delete(delete_account))
+        .route("/users/export", post(request_data_export).route_layer(RateLimitLayer::new(server_state, "data-export", DATA_EXPORT_RATE_LIMIT)))
+        .route("/users/verify-email/resend", post(send_email_verification))
+        .route("/users/tokens", get(get_access_tokens).post(create_access_token))
+        .route("/users/tokens/:id", delete(revoke_access_token))
+        .route("/session/csrf", get(get_csrf_token))
+        .route("/sessions", get(get_sessions).delete(revoke_other_sessions))
+        .route("/sessions/:id", delete(revoke_session))
+        .route_layer(middleware::from_fn(reject_access_tokens))
+}
+
+// Every admin route requires a session with the admin role
+fn create_admin_router<C: ContextTrait + 'static>() -> Router<Arc<ServerState<C>>> {
+    Router::new()
+        .route("/users", get(admin_browse_users))
+        .route("/users/:id", get(admin_get_user))
+        .route("/users/:id/figures", get(admin_browse_user_figures))
+        .route("/users/:id/suspend", post(admin_suspend_user))
+        .route("/users/:id/unsuspend", post(admin_unsuspend_user))
+        .route("/users/:id/sessions", delete(admin_invalidate_user_sessions))
+        .route("/figures/:id", delete(admin_delete_figure))
+        .route_layer(middleware::from_extractor::<RequireRole<Admin>>())
+        .route_layer(middleware::from_extractor::<RequireScope<AdminScope>>())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_context(db_pool: Pool<Postgres>, session_store: ConnectionManager, content_store: S3Storage, export_store: S3Storage, account_mailer: AccountMailer<ConfiguredMailer>, session_policies: SessionPolicies, password_hash_policy: PasswordHashPolicy, identity_provider: Option<OidcProvider>) -> impl ContextTrait {
     // Initialize repositories
     let transaction_starter = PostgresTransactionCreator::new(db_pool.clone());
     let user_repository = UserRepository::new(db_pool.clone());
     let profile_repository = ProfileRepository::new(db_pool.clone());
     let figure_repository = FigureRepository::new(db_pool.clone());
-    let session_repository = SessionRepository::new(session_store);
+    let tag_repository = TagRepository::new(db_pool.clone());
+    let like_repository = LikeRepository::new(db_pool.clone());
+    let comment_repository = CommentRepository::new(db_pool.clone());
+    let follow_repository = FollowRepository::new(db_pool.clone());
+    let object_deletion_repository = ObjectDeletionRepository::new(db_pool.clone());
+    let access_token_repository = AccessTokenRepository::new(db_pool.clone());
+    let session_repository = SessionRepository::new(session_store.clone());
+    let one_time_token_repository = OneTimeTokenRepository::new(session_store.clone());
+    let oidc_sign_in_repository = OidcSignInRepository::new(session_store.clone());
+    let rate_limit_repository = RateLimitRepository::new(session_store);
 
     // Initialize utilities
     let secure_random_generator = ChaCha20::new();
@@ -177,19 +359,107 @@ fn create_state(db_pool: Pool<Postgres>, session_store: ConnectionManager, conte
     let user_service = UserService::new(
         transaction_starter.clone(), user_repository.clone(),
         profile_repository.clone(), session_repository.clone(),
-        secure_random_generator);
+        one_time_token_repository.clone(), account_mailer.clone(), secure_random_generator)
+        .with_session_policies(session_policies)
+        .with_password_hash_policy(password_hash_policy);
     let profile_service = ProfileService::new(profile_repository.clone(), content_store.clone());
-    let figure_service = FigureService::new(figure_repository.clone(), content_store);
+    let figure_service = FigureService::new(transaction_starter.clone(), figure_repository.clone(), tag_repository, object_deletion_repository.clone(), content_store.clone());
+    let rate_limit_service = RateLimitService::new(rate_limit_repository);
+    let access_token_service = AccessTokenService::new(access_token_repository, user_repository.clone(), profile_repository.clone(), ChaCha20::new());
+    let oidc_service = OidcService::new(oidc_sign_in_repository, identity_provider, ChaCha20::new());
+    let account_service = AccountService::new(
+        transaction_starter.clone(), user_repository.clone(), profile_repository.clone(),
+        figure_repository.clone(), like_repository.clone(), follow_repository.clone(), object_deletion_repository,
+        session_repository.clone(), content_store.clone());
+    let like_service = LikeService::new(figure_repository.clone(), like_repository.clone());
+    let comment_service = CommentService::new(figure_repository.clone(), comment_repository);
+    let follow_service = FollowService::new(profile_repository.clone(), follow_repository);
+    let search_service = SearchService::new(figure_repository.clone(), profile_repository.clone());
+    let data_export_service = DataExportService::new(
+        user_repository.clone(), profile_repository.clone(), figure_repository.clone(),
+        one_time_token_repository, content_store, export_store, account_mailer, ChaCha20::new());
 
     // Create service and repository contexts
     let repository_context = RepositoryContext::new(user_repository, profile_repository, figure_repository, session_repository, transaction_starter);
-    let service_context = ServiceContext::new(user_service, profile_service, figure_service);
+    let service_context = ServiceContext::new(user_service, profile_service, figure_service, rate_limit_service, access_token_service, oidc_service, account_service, data_export_service, search_service, like_service, comment_service, follow_service);
 
     // Combine contexts
-    let context = Context::new(service_context, repository_context);
+    Context::new(service_context, repository_context)
+}
+
+fn spawn_object_deletion_worker(content_store: S3Storage, object_deletion_repository: ObjectDeletionRepository) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(OBJECT_DELETION_RETRY_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = retry_object_deletions(&content_store, &object_deletion_repository).await {
+                warn!("Failed to retry queued object deletions: {}", e);
+            }
+        }
+    });
+}
+
+fn create_cursor_signer(cursor_secret: Option<String>) -> CursorSigner {
+    match cursor_secret {
+        Some(secret) => CursorSigner::new(secret.as_bytes()),
+        None => {
+            warn!("env CURSOR_SECRET not found, pagination cursors won't survive a restart");
+            let mut key = [0u8; 32];
+            OsRng.fill_bytes(&mut key);
+            CursorSigner::new(&key)
+        }
+    }
+}
+
+fn create_csrf_tokens(csrf_secret: Option<String>) -> CsrfTokens {
+    match csrf_secret {
+        Some(secret) => CsrfTokens::new(secret.as_bytes()),
+        None => {
+            warn!("env CSRF_SECRET not found, CSRF tokens won't survive a restart");
+            let mut key = [0u8; 32];
+            OsRng.fill_bytes(&mut key);
+            CsrfTokens::new(&key)
+        }
+    }
+}
+
+fn create_mailer(smtp_host: Option<String>, smtp_username: Option<String>, smtp_password: Option<String>, mail_from: Option<String>, mail_directory: String) -> anyhow::Result<ConfiguredMailer> {
+    match smtp_host {
+        Some(smtp_host) => {
+            let mailer = SmtpMailer::new(
+                &smtp_host,
+                smtp_username.expect("No SMTP_USERNAME env found"),
+                smtp_password.expect("No SMTP_PASSWORD env found"),
+                &mail_from.expect("No MAIL_FROM env found"),
+            )?;
+            Ok(ConfiguredMailer::Smtp(mailer))
+        }
+        None => {
+            warn!("env SMTP_HOST not found, mails will be written to {} instead of being sent", mail_directory);
+            Ok(ConfiguredMailer::File(FileMailer::new(mail_directory.into())))
+        }
+    }
+}
+
+fn create_identity_provider(issuer: Option<String>, client_id: Option<String>, client_secret: Option<String>, redirect_uri: Option<String>) -> Option<OidcProvider> {
+    let issuer = match issuer {
+        Some(issuer) => issuer,
+        None => {
+            info!("env OIDC_ISSUER not found, signing in with an OpenID Connect provider is disabled");
+            return None;
+        }
+    };
+    if !issuer.starts_with("https://") {
+        warn!("OIDC_ISSUER doesn't use https, only use this for a provider running locally");
+    }
+    info!("OpenID Connect issuer: {}", issuer);
 
-    // Resulting state
-    Arc::new(ServerState::new(context, domain))
+    Some(OidcProvider::new(OidcConfig {
+        issuer,
+        client_id: client_id.expect("No OIDC_CLIENT_ID env found"),
+        client_secret,
+        redirect_uri: redirect_uri.expect("No OIDC_REDIRECT_URI env found"),
+    }))
 }
 
 fn create_authentication_extension() -> SessionOption {
@@ -201,7 +471,7 @@ fn create_authentication_extension() -> SessionOption {
 fn create_app_cors<T: Into<AllowOrigin>>(origins: T) -> CorsLayer {
     CorsLayer::new()
         .allow_credentials(true)
-        .allow_methods([Method::GET, Method::POST])
-        .allow_headers([ACCEPT, CONTENT_TYPE])
+        .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE])
+        .allow_headers([ACCEPT, CONTENT_TYPE, HeaderName::from_static(CSRF_HEADER)])
         .allow_origin(origins)
 }
\ No newline at end of file
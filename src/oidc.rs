@@ -0,0 +1,357 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use bytes::Bytes;
+use chrono::Utc;
+use hyper::{Body, Client, Method, Request, StatusCode};
+use hyper::client::HttpConnector;
+use hyper::header::{ACCEPT, CONTENT_TYPE};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use rsa::{BigUint, RsaPublicKey};
+use rsa::pkcs1v15::{Signature, VerifyingKey};
+use rsa::signature::Verifier;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use sha2::Sha256;
+use tracing::warn;
+use url::{form_urlencoded, Url};
+use crate::entities::external_identity::ExternalIdentity;
+use crate::server_errors::ServerError;
+
+const SCOPE: &str = "openid email profile";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// Seconds the clocks of the provider and this server may differ
+const CLOCK_SKEW_LEEWAY: i64 = 60;
+
+#[async_trait]
+pub trait IdentityProvider: Send + Sync + Clone + 'static {
+    // Sign in page of the provider the browser is redirected to
+    async fn authorization_url(&self, state: &str, nonce: &str, code_challenge: &str) -> Result<String, ServerError>;
+    // Redeems the code the provider redirected back with, the identity is taken from the validated ID token
+    async fn exchange_code(&self, code: &str, code_verifier: &str, nonce: &str) -> Result<ExternalIdentity, ServerError>;
+}
+
+#[derive(Clone, Debug)]
+pub struct OidcConfig {
+    // The discovery document is fetched from {issuer}/.well-known/openid-configuration
+    pub issuer: String,
+    pub client_id: String,
+    // Public clients only rely on PKCE
+    pub client_secret: Option<String>,
+    // Callback route of this server, as registered at the provider
+    pub redirect_uri: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+// Public key of the provider as published in its JWKS, only RSA keys are used
+#[derive(Deserialize, Clone, Debug)]
+pub struct Jwk {
+    pub kty: String,
+    #[serde(default)]
+    pub kid: Option<String>,
+    #[serde(default)]
+    pub alg: Option<String>,
+    #[serde(default, rename = "use")]
+    pub key_use: Option<String>,
+    #[serde(default)]
+    pub n: Option<String>,
+    #[serde(default)]
+    pub e: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+struct IdTokenHeader {
+    alg: String,
+    #[serde(default)]
+    kid: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    aud: Audience,
+    #[serde(default)]
+    azp: Option<String>,
+    exp: i64,
+    iat: i64,
+    #[serde(default)]
+    nonce: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+    // Some providers send a string instead of a boolean
+    #[serde(default)]
+    email_verified: Option<Value>,
+    #[serde(default)]
+    preferred_username: Option<String>,
+}
+
+// OpenID Connect provider using the authorization code flow with PKCE
+#[derive(Clone)]
+pub struct OidcProvider {
+    config: Arc<OidcConfig>,
+    client: Client<HttpsConnector<HttpConnector>>,
+    // Fetched on first use, the keys again when an ID token is signed with an unknown key
+    metadata: Arc<RwLock<Option<ProviderMetadata>>>,
+    keys: Arc<RwLock<Vec<Jwk>>>,
+}
+
+impl OidcProvider {
+    pub fn new(config: OidcConfig) -> Self {
+        // Plain http is only meant for a provider on the same machine, like the one of the tests
+        let connector = HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+
+        Self {
+            config: Arc::new(config),
+            client: Client::builder().build(connector),
+            metadata: Arc::new(RwLock::new(None)),
+            keys: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    async fn metadata(&self) -> Result<ProviderMetadata, ServerError> {
+        let cached = self.metadata.read().unwrap().clone();
+        if let Some(metadata) = cached {
+            return Ok(metadata);
+        }
+
+        let url = format!("{}/.well-known/openid-configuration", self.config.issuer.trim_end_matches('/'));
+        let request = Request::get(&url)
+            .header(ACCEPT, "application/json")
+            .body(Body::empty())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        let (status, body) = self.send(request).await?;
+        let metadata: ProviderMetadata = parse_json(&url, status, &body)?;
+
+        // See OpenID Connect Discovery 4.3, the document can't speak for another issuer
+        if metadata.issuer != self.config.issuer {
+            return Err(ServerError::InternalError(Arc::new(anyhow!(
+                "Issuer of the discovery document ({}) doesn't match the configured issuer ({})", metadata.issuer, self.config.issuer))));
+        }
+        *self.metadata.write().unwrap() = Some(metadata.clone());
+        Ok(metadata)
+    }
+
+    async fn signing_keys(&self, metadata: &ProviderMetadata, key_id: Option<&str>) -> Result<Vec<Jwk>, ServerError> {
+        let cached = self.keys.read().unwrap().clone();
+        let is_known = match key_id {
+            Some(key_id) => cached.iter().any(|key| key.kid.as_deref() == Some(key_id)),
+            None => !cached.is_empty()
+        };
+        if is_known {
+            return Ok(cached);
+        }
+
+        // Providers rotate their keys, an unknown key id means the published keys changed
+        let request = Request::get(&metadata.jwks_uri)
+            .header(ACCEPT, "application/json")
+            .body(Body::empty())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        let (status, body) = self.send(request).await?;
+        let jwk_set: JwkSet = parse_json(&metadata.jwks_uri, status, &body)?;
+        *self.keys.write().unwrap() = jwk_set.keys.clone();
+        Ok(jwk_set.keys)
+    }
+
+    async fn send(&self, request: Request<Body>) -> Result<(StatusCode, Bytes), ServerError> {
+        let uri = request.uri().to_string();
+        let response = tokio::time::timeout(REQUEST_TIMEOUT, async {
+            let response = self.client.request(request).await?;
+            let status = response.status();
+            hyper::body::to_bytes(response.into_body()).await
+                .map(|body| (status, body))
+        }).await;
+
+        match response {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(e)) => Err(ServerError::InternalError(Arc::new(anyhow::Error::from(e)
+                .context(format!("Request to {} failed", uri))))),
+            Err(_) => Err(ServerError::InternalError(Arc::new(anyhow!("Request to {} timed out", uri))))
+        }
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for OidcProvider {
+    async fn authorization_url(&self, state: &str, nonce: &str, code_challenge: &str) -> Result<String, ServerError> {
+        let metadata = self.metadata().await?;
+        Url::parse_with_params(&metadata.authorization_endpoint, &[
+            ("response_type", "code"),
+            ("client_id", &self.config.client_id),
+            ("redirect_uri", &self.config.redirect_uri),
+            ("scope", SCOPE),
+            ("state", state),
+            ("nonce", nonce),
+            ("code_challenge", code_challenge),
+            ("code_challenge_method", "S256"),
+        ])
+            .map(String::from)
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn exchange_code(&self, code: &str, code_verifier: &str, nonce: &str) -> Result<ExternalIdentity, ServerError> {
+        let metadata = self.metadata().await?;
+
+        let form = {
+            let mut form = form_urlencoded::Serializer::new(String::new());
+            form.append_pair("grant_type", "authorization_code")
+                .append_pair("code", code)
+                .append_pair("redirect_uri", &self.config.redirect_uri)
+                .append_pair("client_id", &self.config.client_id)
+                .append_pair("code_verifier", code_verifier);
+            // Sent in the body (client_secret_post)
+            if let Some(client_secret) = &self.config.client_secret {
+                form.append_pair("client_secret", client_secret);
+            }
+            form.finish()
+        };
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(&metadata.token_endpoint)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(ACCEPT, "application/json")
+            .body(Body::from(form))
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        let (status, body) = self.send(request).await?;
+        // An expired or already used code, or a verifier that doesn't match the challenge
+        if status.is_client_error() {
+            warn!("Token endpoint rejected the code ({}): {}", status, String::from_utf8_lossy(&body));
+            return Err(ServerError::InvalidOidcSignIn);
+        }
+        let token_response: TokenResponse = parse_json(&metadata.token_endpoint, status, &body)?;
+
+        let keys = self.signing_keys(&metadata, key_id(&token_response.id_token).as_deref()).await?;
+        validate_id_token(&token_response.id_token, &keys, &metadata.issuer, &self.config.client_id, nonce, Utc::now().timestamp())
+    }
+}
+
+// Checks the signature and the claims of an ID token, see OpenID Connect Core 3.1.3.7
+pub fn validate_id_token(id_token: &str, keys: &[Jwk], issuer: &str, client_id: &str, nonce: &str, now: i64) -> Result<ExternalIdentity, ServerError> {
+    let mut parts = id_token.split('.');
+    let (encoded_header, encoded_claims, encoded_signature) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(header), Some(claims), Some(signature), None) => (header, claims, signature),
+        _ => return Err(ServerError::InvalidOidcSignIn)
+    };
+
+    let header: IdTokenHeader = decode_part(encoded_header)?;
+    // RS256 is the algorithm every provider has to support, never accept "none" or a symmetric algorithm
+    if header.alg != "RS256" {
+        return Err(ServerError::InvalidOidcSignIn);
+    }
+    let signature = URL_SAFE_NO_PAD.decode(encoded_signature)
+        .map_err(|_e| ServerError::InvalidOidcSignIn)?;
+    let signing_input = &id_token[..encoded_header.len() + 1 + encoded_claims.len()];
+    let is_signed = keys.iter()
+        .filter(|key| is_signing_key(key, header.kid.as_deref()))
+        .any(|key| verify_signature(key, signing_input.as_bytes(), &signature));
+    if !is_signed {
+        return Err(ServerError::InvalidOidcSignIn);
+    }
+
+    let claims: IdTokenClaims = decode_part(encoded_claims)?;
+    let audiences = match &claims.aud {
+        Audience::Single(audience) => vec![audience.as_str()],
+        Audience::Multiple(audiences) => audiences.iter().map(String::as_str).collect(),
+    };
+    // A token issued to several clients has to name this client as the authorized party
+    let is_authorized_party = match (&claims.azp, audiences.len()) {
+        (Some(azp), _) => azp == client_id,
+        (None, count) => count == 1,
+    };
+    if claims.iss != issuer
+        || !audiences.contains(&client_id)
+        || !is_authorized_party
+        || claims.exp + CLOCK_SKEW_LEEWAY < now
+        || claims.iat - CLOCK_SKEW_LEEWAY > now
+        || claims.nonce.as_deref() != Some(nonce)
+        || claims.sub.is_empty() {
+        return Err(ServerError::InvalidOidcSignIn);
+    }
+
+    let email_verified = matches!(&claims.email_verified, Some(Value::Bool(true)))
+        || matches!(&claims.email_verified, Some(Value::String(verified)) if verified == "true");
+    Ok(ExternalIdentity {
+        issuer: claims.iss,
+        subject: claims.sub,
+        email: claims.email,
+        email_verified,
+        preferred_username: claims.preferred_username,
+    })
+}
+
+// Id of the key the token claims to be signed with, checked along with the rest of the token
+fn key_id(id_token: &str) -> Option<String> {
+    id_token.split('.').next()
+        .and_then(|header| decode_part::<IdTokenHeader>(header).ok())
+        .and_then(|header| header.kid)
+}
+
+fn decode_part<T: DeserializeOwned>(part: &str) -> Result<T, ServerError> {
+    URL_SAFE_NO_PAD.decode(part).ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .ok_or(ServerError::InvalidOidcSignIn)
+}
+
+fn is_signing_key(key: &Jwk, key_id: Option<&str>) -> bool {
+    key.kty == "RSA"
+        && key.key_use.as_deref().unwrap_or("sig") == "sig"
+        && key.alg.as_deref().unwrap_or("RS256") == "RS256"
+        && (key_id.is_none() || key.kid.as_deref() == key_id)
+}
+
+fn verify_signature(key: &Jwk, message: &[u8], signature: &[u8]) -> bool {
+    let (modulus, exponent) = match (&key.n, &key.e) {
+        (Some(modulus), Some(exponent)) => (modulus, exponent),
+        _ => return false
+    };
+    let public_key = match (URL_SAFE_NO_PAD.decode(modulus), URL_SAFE_NO_PAD.decode(exponent)) {
+        (Ok(modulus), Ok(exponent)) => RsaPublicKey::new(BigUint::from_bytes_be(&modulus), BigUint::from_bytes_be(&exponent)),
+        _ => return false
+    };
+    match (public_key, Signature::try_from(signature)) {
+        (Ok(public_key), Ok(signature)) => VerifyingKey::<Sha256>::new(public_key).verify(message, &signature).is_ok(),
+        _ => false
+    }
+}
+
+fn parse_json<T: DeserializeOwned>(url: &str, status: StatusCode, body: &[u8]) -> Result<T, ServerError> {
+    if !status.is_success() {
+        return Err(ServerError::InternalError(Arc::new(anyhow!("Request to {} failed with status {}", url, status))));
+    }
+    serde_json::from_slice(body)
+        .map_err(|e| ServerError::InternalError(Arc::new(anyhow::Error::from(e)
+            .context(format!("Unexpected response from {}", url)))))
+}
\ No newline at end of file
@@ -0,0 +1,111 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use axum::http::Request;
+use axum::response::{IntoResponse, Response};
+use tower::{Layer, Service};
+use crate::auth_layer::client_ip;
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::dtos::session_dtos::SessionOption;
+use crate::entities::rate_limit::RateLimit;
+use crate::server_errors::ServerError;
+use crate::ServerState;
+use crate::services::traits::RateLimitServiceTrait;
+
+// Rate limits the routes it's applied to, per user when signed in and per ip otherwise.
+// Has to run after the authentication layer to see the session.
+pub struct RateLimitLayer<C: ContextTrait> {
+    server_state: Arc<ServerState<C>>,
+    scope: &'static str,
+    limit: RateLimit,
+}
+
+impl<C: ContextTrait> RateLimitLayer<C> {
+    // Routes sharing a scope share their counters
+    pub fn new(server_state: Arc<ServerState<C>>, scope: &'static str, limit: RateLimit) -> Self {
+        Self {
+            server_state,
+            scope,
+            limit,
+        }
+    }
+}
+
+impl<C: ContextTrait> Clone for RateLimitLayer<C> {
+    fn clone(&self) -> Self {
+        Self {
+            server_state: self.server_state.clone(),
+            scope: self.scope,
+            limit: self.limit,
+        }
+    }
+}
+
+impl<C: ContextTrait, S> Layer<S> for RateLimitLayer<C> {
+    type Service = RateLimitMiddleware<C, S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitMiddleware {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+pub struct RateLimitMiddleware<C: ContextTrait, S> {
+    inner: S,
+    layer: RateLimitLayer<C>,
+}
+
+impl<C: ContextTrait, S: Clone> Clone for RateLimitMiddleware<C, S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            layer: self.layer.clone(),
+        }
+    }
+}
+
+impl<C, S, B> Service<Request<B>> for RateLimitMiddleware<C, S>
+    where C: ContextTrait + 'static,
+          S: Service<Request<B>, Response=Response> + Clone + Send + 'static,
+          S::Future: Send,
+          B: Send + 'static {
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        // Use the service that was polled ready, leave a clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let layer = self.layer.clone();
+
+        Box::pin(async move {
+            let user_id = request.extensions().get::<SessionOption>()
+                .and_then(|session| session.session_opt.as_ref())
+                .map(|session| session.get_user_id());
+            let key = match user_id {
+                Some(user_id) => format!("user:{}", user_id),
+                None => match client_ip(request.headers(), request.extensions(), &layer.server_state.trusted_proxies) {
+                    Some(ip) => format!("ip:{}", ip),
+                    // Only without connect info, counting these together would let one client use up the limit of all others
+                    None => {
+                        let error = anyhow::Error::msg("No client address to rate limit the request by, the server has to be served with connect info");
+                        return Ok(ServerError::InternalError(Arc::new(error)).into_response());
+                    }
+                }
+            };
+
+            match layer.server_state.context.service_context().rate_limit_service().hit(layer.scope, &key, layer.limit).await {
+                Ok(()) => inner.call(request).await,
+                Err(e) => Ok(e.into_response())
+            }
+        })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use crate::entities::access_token::{AccessToken, AccessTokenDef, Scope};
+use crate::entities::types::IdType;
+use crate::server_errors::ServerError;
+use interpol::format as iformat;
+use crate::repositories::traits::{AccessTokenRepositoryTrait, TransactionTrait};
+use crate::repositories::transaction::PostgresTransaction;
+
+#[derive(Clone)]
+pub struct AccessTokenRepository {
+    db: Pool<Postgres>,
+}
+
+impl AccessTokenRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self {
+            db: pool
+        }
+    }
+}
+
+#[async_trait]
+impl AccessTokenRepositoryTrait<PostgresTransaction> for AccessTokenRepository {
+    async fn create(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType, name: String, token_hash: String, scopes: Vec<Scope>) -> Result<AccessToken, ServerError> {
+        let query_string = iformat!(r#"
+            INSERT INTO {AccessTokenDef::Table}
+            ({AccessTokenDef::UserId.as_str()}, {AccessTokenDef::Name.as_str()}, {AccessTokenDef::TokenHash.as_str()}, {AccessTokenDef::Scopes.as_str()})
+            VALUES ($1, $2, $3, $4)
+            RETURNING {AccessTokenDef::Id.as_str()}, {AccessTokenDef::UserId.as_str()}, {AccessTokenDef::Name.as_str()}, {AccessTokenDef::TokenHash.as_str()},
+            {AccessTokenDef::Scopes.as_str()}, {AccessTokenDef::CreatedAt.as_str()}, {AccessTokenDef::LastUsedAt.as_str()}
+            "#);
+
+        let scopes: Vec<String> = scopes.iter().map(|scope| scope.as_str().to_string()).collect();
+        let query =
+            sqlx::query_as::<_, AccessToken>(&query_string)
+                .bind(user_id)
+                .bind(name)
+                .bind(token_hash)
+                .bind(scopes);
+
+        match transaction {
+            Some(transaction) => query.fetch_one(transaction.inner()).await,
+            None => query.fetch_one(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn find_by_token_hash(&self, transaction: Option<&mut PostgresTransaction>, token_hash: &str) -> Result<AccessToken, ServerError> {
+        let query_string = iformat!(r#"
+            SELECT {AccessTokenDef::Id.as_str()}, {AccessTokenDef::UserId.as_str()}, {AccessTokenDef::Name.as_str()}, {AccessTokenDef::TokenHash.as_str()},
+            {AccessTokenDef::Scopes.as_str()}, {AccessTokenDef::CreatedAt.as_str()}, {AccessTokenDef::LastUsedAt.as_str()}
+            FROM {AccessTokenDef::Table}
+            WHERE {AccessTokenDef::TokenHash} = $1
+            "#);
+
+        let query =
+            sqlx::query_as::<_, AccessToken>(&query_string)
+                .bind(token_hash);
+
+        match transaction {
+            Some(transaction) => query.fetch_one(transaction.inner()).await,
+            None => query.fetch_one(&self.db).await
+        }.map_err(|e| match e {
+            sqlx::Error::RowNotFound => ServerError::ResourceNotFound,
+            e => ServerError::InternalError(Arc::new(e.into()))
+        })
+    }
+
+    async fn find_all_by_user_id(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType) -> Result<Vec<AccessToken>, ServerError> {
+        let query_string = iformat!(r#"
+            SELECT {AccessTokenDef::Id.as_str()}, {AccessTokenDef::UserId.as_str()}, {AccessTokenDef::Name.as_str()}, {AccessTokenDef::TokenHash.as_str()},
+            {AccessTokenDef::Scopes.as_str()}, {AccessTokenDef::CreatedAt.as_str()}, {AccessTokenDef::LastUsedAt.as_str()}
+            FROM {AccessTokenDef::Table}
+            WHERE {AccessTokenDef::UserId} = $1
+            ORDER BY {AccessTokenDef::Id} DESC
+            "#);
+
+        let query =
+            sqlx::query_as::<_, AccessToken>(&query_string)
+                .bind(user_id);
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn update_last_used_at(&self, transaction: Option<&mut PostgresTransaction>, access_token_id: IdType) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            UPDATE {AccessTokenDef::Table}
+            SET {AccessTokenDef::LastUsedAt.as_str()} = now()
+            WHERE {AccessTokenDef::Id} = $1
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(access_token_id);
+
+        let result = match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        match result.rows_affected() {
+            0 => Err(ServerError::ResourceNotFound),
+            _ => Ok(())
+        }
+    }
+
+    async fn delete(&self, transaction: Option<&mut PostgresTransaction>, access_token_id: IdType, user_id: IdType) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            DELETE FROM {AccessTokenDef::Table}
+            WHERE {AccessTokenDef::Id} = $1 AND {AccessTokenDef::UserId} = $2
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(access_token_id)
+                .bind(user_id);
+
+        let result = match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        match result.rows_affected() {
+            0 => Err(ServerError::ResourceNotFound),
+            _ => Ok(())
+        }
+    }
+}
\ No newline at end of file
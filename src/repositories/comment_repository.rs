@@ -0,0 +1,154 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use sqlx::{Error, Pool, Postgres, Row};
+use interpol::format as iformat;
+use crate::entities::comment::CommentDef;
+use crate::entities::dtos::comment_dto::CommentDTO;
+use crate::entities::profile::ProfileDef;
+use crate::entities::types::IdType;
+use crate::repositories::query_builder::{Comparison, FilteredQuery, Order};
+use crate::repositories::traits::{CommentRepositoryTrait, TransactionTrait};
+use crate::repositories::transaction::PostgresTransaction;
+use crate::server_errors::ServerError;
+
+#[derive(Clone)]
+pub struct CommentRepository {
+    db: Pool<Postgres>,
+}
+
+impl CommentRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        CommentRepository {
+            db: pool
+        }
+    }
+
+    // Base query of a listing, comments with their profile and number of replies.
+    // The profile id of the comment is read from the joined profile.
+    fn select_comments_query() -> String {
+        iformat!(r#"
+            SELECT {CommentDef::Id} AS {CommentDef::Id.unique()}, {CommentDef::FigureId}, {CommentDef::ParentId}, {CommentDef::Content}, {CommentDef::CreatedAt}, {CommentDef::EditedAt},
+            {ProfileDef::Id} AS {ProfileDef::Id.unique()}, {ProfileDef::Username}, {ProfileDef::DisplayName}, {ProfileDef::Bio}, {ProfileDef::Banner}, {ProfileDef::ProfilePicture}, {ProfileDef::UserId}, {ProfileDef::FollowerCount}, {ProfileDef::FollowingCount},
+            (
+                SELECT COUNT(*) FROM {CommentDef::Table} AS reply
+                WHERE reply.{CommentDef::ParentId.as_str()} = {CommentDef::Id}
+            ) AS reply_count
+            FROM {CommentDef::Table}
+            INNER JOIN {ProfileDef::Table}
+            ON {CommentDef::ProfileId} = {ProfileDef::Id}
+            "#)
+    }
+}
+
+#[async_trait]
+impl CommentRepositoryTrait<PostgresTransaction> for CommentRepository {
+    async fn create(&self, transaction: Option<&mut PostgresTransaction>, figure_id: IdType, profile_id: IdType, parent_id: Option<IdType>, content: String) -> Result<IdType, ServerError> {
+        let query_string = iformat!(r#"
+            INSERT INTO {CommentDef::Table}
+            ({CommentDef::FigureId.as_str()}, {CommentDef::ProfileId.as_str()}, {CommentDef::ParentId.as_str()}, {CommentDef::Content.as_str()})
+            VALUES ($1, $2, $3, $4)
+            RETURNING {CommentDef::Id.as_str()};
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(figure_id)
+                .bind(profile_id)
+                .bind(parent_id)
+                .bind(content);
+
+        match transaction {
+            Some(transaction) => query.fetch_one(transaction.inner()).await,
+            None => query.fetch_one(&self.db).await
+        }
+            .and_then(|row| row.try_get(0))
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn find_by_id(&self, transaction: Option<&mut PostgresTransaction>, comment_id: IdType) -> Result<CommentDTO, ServerError> {
+        let query_string = iformat!(r#"
+            {Self::select_comments_query()}
+            WHERE {CommentDef::Id} = $1
+            "#);
+
+        let query = sqlx::query_as(&query_string).bind(comment_id);
+
+        match transaction {
+            Some(transaction) => query.fetch_one(transaction.inner()).await,
+            None => query.fetch_one(&self.db).await
+        }.map_err(|e| match e {
+            Error::RowNotFound => ServerError::ResourceNotFound,
+            e => ServerError::InternalError(Arc::new(e.into()))
+        })
+    }
+
+    async fn find_starting_from_id_with_figure_id(&self, transaction: Option<&mut PostgresTransaction>, figure_id: IdType, comment_id: Option<IdType>, limit: i32) -> Result<Vec<CommentDTO>, ServerError> {
+        let mut query_builder = FilteredQuery::new(Self::select_comments_query())
+            .filter(CommentDef::FigureId, Comparison::Equal, figure_id)
+            .filter_null(CommentDef::ParentId)
+            .filter_if_some(CommentDef::Id, Comparison::LessThan, comment_id)
+            .order_by(CommentDef::Id, Order::Descending)
+            .limit(limit as i64);
+
+        let query = query_builder.build_query_as::<CommentDTO>();
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn find_replies_starting_from_id(&self, transaction: Option<&mut PostgresTransaction>, parent_id: IdType, comment_id: Option<IdType>, limit: i32) -> Result<Vec<CommentDTO>, ServerError> {
+        let mut query_builder = FilteredQuery::new(Self::select_comments_query())
+            .filter(CommentDef::ParentId, Comparison::Equal, parent_id)
+            .filter_if_some(CommentDef::Id, Comparison::GreaterThan, comment_id)
+            .order_by(CommentDef::Id, Order::Ascending)
+            .limit(limit as i64);
+
+        let query = query_builder.build_query_as::<CommentDTO>();
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn update_content(&self, transaction: Option<&mut PostgresTransaction>, comment_id: IdType, content: String) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            UPDATE {CommentDef::Table}
+            SET {CommentDef::Content.as_str()} = $1, {CommentDef::EditedAt.as_str()} = now()
+            WHERE {CommentDef::Id} = $2
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(content)
+                .bind(comment_id);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn delete_by_id(&self, transaction: Option<&mut PostgresTransaction>, comment_id: IdType) -> Result<(), ServerError> {
+        // Replies are removed by the foreign key on the parent
+        let query_string = iformat!(r#"
+            DELETE FROM {CommentDef::Table}
+            WHERE {CommentDef::Id} = $1
+            "#);
+
+        let query = sqlx::query(&query_string).bind(comment_id);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+}
\ No newline at end of file
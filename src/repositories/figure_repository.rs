@@ -2,11 +2,14 @@ use std::sync::Arc;
 use sqlx::{Error, Pool, Postgres, Row};
 use crate::server_errors::ServerError;
 use async_trait::async_trait;
-use crate::entities::dtos::figure_dto::FigureDTO;
+use crate::entities::dtos::figure_dto::{FigureDTO, LikedFigureDTO};
 use crate::entities::figure::{Figure, FigureDef};
+use crate::entities::like::FigureLikeDef;
 use crate::entities::profile::ProfileDef;
+use crate::entities::tag::{FigureTagDef, TagDef};
 use crate::entities::types::IdType;
 use interpol::format as iformat;
+use crate::repositories::query_builder::{Comparison, FilteredQuery, Order};
 use crate::repositories::traits::{FigureRepositoryTrait, TransactionTrait};
 use crate::repositories::transaction::PostgresTransaction;
 
@@ -21,6 +24,38 @@ impl FigureRepository {
             db: pool
         }
     }
+
+    // Base query of a listing, figures with their profile and tags
+    fn select_figures_query() -> String {
+        iformat!(r#"
+            SELECT {figure_columns()}
+            FROM {FigureDef::Table}
+            INNER JOIN {ProfileDef::Table}
+            ON {FigureDef::ProfileId} = {ProfileDef::Id}
+            "#)
+    }
+}
+
+// Every column of a FigureDTO, figures have to be joined with their profile
+pub fn figure_columns() -> String {
+    iformat!(r#"
+        {FigureDef::Id} AS {FigureDef::Id.unique()}, {FigureDef::Title}, {FigureDef::Description}, {FigureDef::Url}, {FigureDef::Width}, {FigureDef::Height}, {FigureDef::LikeCount},
+        {ProfileDef::Id} AS {ProfileDef::Id.unique()}, {ProfileDef::Username}, {ProfileDef::DisplayName}, {ProfileDef::Bio}, {ProfileDef::Banner}, {ProfileDef::ProfilePicture}, {ProfileDef::UserId}, {ProfileDef::FollowerCount}, {ProfileDef::FollowingCount},
+        {tags_column()}
+        "#)
+}
+
+// The tag names of the figure of the row, sorted
+fn tags_column() -> String {
+    iformat!(r#"
+        ARRAY(
+            SELECT {TagDef::Name} FROM {FigureTagDef::Table}
+            INNER JOIN {TagDef::Table}
+            ON {TagDef::Id} = {FigureTagDef::TagId}
+            WHERE {FigureTagDef::FigureId} = {FigureDef::Id}
+            ORDER BY {TagDef::Name}
+        ) AS tags
+        "#)
 }
 
 #[async_trait]
@@ -58,16 +93,7 @@ impl FigureRepositoryTrait<PostgresTransaction> for FigureRepository {
 
     async fn find_by_id(&self, transaction: Option<&mut PostgresTransaction>, figure_id: IdType) -> Result<FigureDTO, ServerError> {
         let query_string = iformat!(r#"
-            SELECT
-            {FigureDef::Id} AS {FigureDef::Id.unique()}, {FigureDef::Title}, {FigureDef::Description},
-            {FigureDef::Url}, {FigureDef::Width}, {FigureDef::Height},
-
-            {ProfileDef::Id} AS {ProfileDef::Id.unique()}, {ProfileDef::Username}, {ProfileDef::DisplayName},
-            {ProfileDef::Bio}, {ProfileDef::Banner}, {ProfileDef::ProfilePicture}, {ProfileDef::UserId}
-
-            FROM {FigureDef::Table}
-            INNER JOIN {ProfileDef::Table}
-            ON {FigureDef::ProfileId} = {ProfileDef::Id}
+            {Self::select_figures_query()}
             WHERE {FigureDef::Id} = $1
             "#);
 
@@ -76,62 +102,110 @@ impl FigureRepositoryTrait<PostgresTransaction> for FigureRepository {
         match transaction {
             Some(transaction) => query.fetch_one(transaction.inner()).await,
             None => query.fetch_one(&self.db).await
-        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+        }.map_err(|e| match e {
+            Error::RowNotFound => ServerError::ResourceNotFound,
+            e => ServerError::InternalError(Arc::new(e.into()))
+        })
     }
 
     async fn find_starting_from_id_with_profile_id(&self, transaction: Option<&mut PostgresTransaction>, figure_id: Option<IdType>, profile_id: Option<IdType>, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
-        let mut query_string = iformat!(r#"
-            SELECT {FigureDef::Id} AS {FigureDef::Id.unique()}, {FigureDef::Title}, {FigureDef::Description}, {FigureDef::Url}, {FigureDef::Width}, {FigureDef::Height},
-            {ProfileDef::Id} AS {ProfileDef::Id.unique()}, {ProfileDef::Username}, {ProfileDef::DisplayName}, {ProfileDef::Bio}, {ProfileDef::Banner}, {ProfileDef::ProfilePicture}, {ProfileDef::UserId}
-            FROM {FigureDef::Table}
-            INNER JOIN {ProfileDef::Table}
-            ON {FigureDef::ProfileId} = {ProfileDef::Id}
-            "#);
+        let mut query_builder = FilteredQuery::new(Self::select_figures_query())
+            // Filter figures by starting from figure id.
+            .filter_if_some(FigureDef::Id, Comparison::LessThan, figure_id)
+            // Filter by profile
+            .filter_if_some(FigureDef::ProfileId, Comparison::Equal, profile_id)
+            .order_by(FigureDef::Id, Order::Descending)
+            .limit(limit as i64);
 
-        // Filter figures by starting from figure id.
-        if let Some(starting_from_id) = figure_id {
-            query_string = iformat!(r#"
-            {query_string}
-            WHERE {FigureDef::Id} < {starting_from_id}
+        let query = query_builder.build_query_as::<FigureDTO>();
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }.map_err(|e| match e {
+            Error::RowNotFound => ServerError::ResourceNotFound,
+            e => ServerError::InternalError(Arc::new(e.into()))
+        })
+    }
+
+    async fn find_starting_from_id_with_tag(&self, transaction: Option<&mut PostgresTransaction>, figure_id: Option<IdType>, tag: String, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
+        // A figure has a tag at most once, so joining on the tags doesn't duplicate figures
+        let query_string = iformat!(r#"
+            {Self::select_figures_query()}
+            INNER JOIN {FigureTagDef::Table}
+            ON {FigureTagDef::FigureId} = {FigureDef::Id}
+            INNER JOIN {TagDef::Table}
+            ON {TagDef::Id} = {FigureTagDef::TagId}
             "#);
+
+        let mut query_builder = FilteredQuery::new(query_string)
+            .filter_if_some(FigureDef::Id, Comparison::LessThan, figure_id)
+            .filter(TagDef::Name, Comparison::Equal, tag)
+            .order_by(FigureDef::Id, Order::Descending)
+            .limit(limit as i64);
+
+        let query = query_builder.build_query_as::<FigureDTO>();
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
         }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
 
-        // Filter by profile
-        if let Some(from_profile) = profile_id {
-            let mut filter = iformat!("{FigureDef::ProfileId} = {from_profile}");
-            // Check if where clause already exists in query (only figure_id will add where clause)
-            if figure_id.is_some() {
-                filter = iformat!("AND {filter}");
-            } else {
-                filter = iformat!("WHERE {filter}");
-            }
-            query_string = iformat!(r#"
-            {query_string}
-            {filter}
+    async fn search(&self, transaction: Option<&mut PostgresTransaction>, query: String, offset: i64, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
+        // Parsed with the configuration the search vector is generated with, see scripts/seed.sql
+        let query_string = iformat!(r#"
+            {Self::select_figures_query()}
+            CROSS JOIN websearch_to_tsquery('english', $1) AS search_query
+            WHERE {FigureDef::SearchVector} @@ search_query
+            ORDER BY ts_rank({FigureDef::SearchVector}, search_query) DESC, {FigureDef::Id} DESC
+            LIMIT $2 OFFSET $3
             "#);
+
+        let query =
+            sqlx::query_as::<_, FigureDTO>(&query_string)
+                .bind(query)
+                .bind(limit as i64)
+                .bind(offset);
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
         }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
 
-        query_string = iformat!(r#"
-        {query_string}
-        ORDER BY {FigureDef::Id} DESC
-        LIMIT {limit}
-        "#);
+    async fn find_liked_starting_from_id(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType, like_id: Option<IdType>, limit: i32) -> Result<Vec<LikedFigureDTO>, ServerError> {
+        let query_string = iformat!(r#"
+            SELECT {FigureLikeDef::Id} AS like_id, {figure_columns()}
+            FROM {FigureLikeDef::Table}
+            INNER JOIN {FigureDef::Table}
+            ON {FigureDef::Id} = {FigureLikeDef::FigureId}
+            INNER JOIN {ProfileDef::Table}
+            ON {FigureDef::ProfileId} = {ProfileDef::Id}
+            "#);
+
+        // Most recently liked first
+        let mut query_builder = FilteredQuery::new(query_string)
+            .filter_if_some(FigureLikeDef::Id, Comparison::LessThan, like_id)
+            .filter(FigureLikeDef::ProfileId, Comparison::Equal, profile_id)
+            .order_by(FigureLikeDef::Id, Order::Descending)
+            .limit(limit as i64);
 
-        let query = sqlx::query_as::<_, FigureDTO>(&query_string);
+        let query = query_builder.build_query_as::<LikedFigureDTO>();
 
         match transaction {
             Some(transaction) => query.fetch_all(transaction.inner()).await,
             None => query.fetch_all(&self.db).await
-        }.map_err(|e| match e {
-            Error::RowNotFound => ServerError::ResourceNotFound,
-            e => ServerError::InternalError(Arc::new(e.into()))
-        })
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
     }
 
     async fn update_figure(&self, transaction: Option<&mut PostgresTransaction>, figure: Figure) -> Result<(), ServerError> {
         let query_string = iformat!(r#"
             UPDATE {FigureDef::Table}
-            SET {FigureDef::Title} = $2, {FigureDef::Description} = $3, {FigureDef::Url} = $4, {FigureDef::Width} = $5, {FigureDef::Height} = $6
+            SET {FigureDef::Title.as_str()} = $2, {FigureDef::Description.as_str()} = $3, {FigureDef::Url.as_str()} = $4, {FigureDef::Width.as_str()} = $5, {FigureDef::Height.as_str()} = $6
             WHERE {FigureDef::Id} = $1
             "#);
 
@@ -168,14 +242,26 @@ impl FigureRepositoryTrait<PostgresTransaction> for FigureRepository {
             .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
     }
 
-    async fn count_by_profile_id(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType) -> Result<IdType, ServerError> {
+    async fn delete_by_profile_id(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType) -> Result<Vec<String>, ServerError> {
         let query_string = iformat!(r#"
-        SELECT count(*) FROM {FigureDef::Table}
-        where {FigureDef::ProfileId} = $1
-        "#);
+            DELETE FROM {FigureDef::Table}
+            WHERE {FigureDef::ProfileId} = $1
+            RETURNING {FigureDef::Url.as_str()}
+            "#);
         let query =
-            sqlx::query(&query_string)
+            sqlx::query_scalar::<_, String>(&query_string)
                 .bind(profile_id);
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn count_by_profile_id(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType) -> Result<IdType, ServerError> {
+        let mut query_builder = FilteredQuery::new(iformat!("SELECT count(*) FROM {FigureDef::Table}"))
+            .filter(FigureDef::ProfileId, Comparison::Equal, profile_id);
+        let query = query_builder.build();
         match transaction {
             Some(transaction) => query.fetch_one(transaction.inner()).await,
             None => query.fetch_one(&self.db).await
@@ -185,11 +271,8 @@ impl FigureRepositoryTrait<PostgresTransaction> for FigureRepository {
     }
 
     async fn get_total_figures_count(&self, transaction: Option<&mut PostgresTransaction>) -> Result<IdType, ServerError> {
-        let query_string = iformat!(r#"
-        SELECT count(*) FROM {FigureDef::Table}
-        "#);
-        let query =
-            sqlx::query(&query_string);
+        let mut query_builder = FilteredQuery::new(iformat!("SELECT count(*) FROM {FigureDef::Table}"));
+        let query = query_builder.build();
         match transaction {
             Some(transaction) => query.fetch_one(transaction.inner()).await,
             None => query.fetch_one(&self.db).await
@@ -0,0 +1,201 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use interpol::format as iformat;
+use crate::entities::dtos::figure_dto::FigureDTO;
+use crate::entities::dtos::profile_dto::FollowProfileDTO;
+use crate::entities::figure::FigureDef;
+use crate::entities::follow::FollowDef;
+use crate::entities::profile::ProfileDef;
+use crate::entities::types::IdType;
+use crate::repositories::figure_repository::figure_columns;
+use crate::repositories::query_builder::{Comparison, FilteredQuery, Order};
+use crate::repositories::traits::{FollowRepositoryTrait, TransactionTrait};
+use crate::repositories::transaction::PostgresTransaction;
+use crate::server_errors::ServerError;
+
+// The follower and following counts of the profiles are updated in the same statement as the follows,
+// so profiles can be shown without counting their follows
+#[derive(Clone)]
+pub struct FollowRepository {
+    db: Pool<Postgres>,
+}
+
+impl FollowRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        FollowRepository {
+            db: pool
+        }
+    }
+
+    // Base query of a follow listing, the profiles on the given side of the follows
+    fn select_follow_profiles_query(profile_column: FollowDef) -> String {
+        iformat!(r#"
+            SELECT {FollowDef::Id} AS {FollowDef::Id.unique()}, {ProfileDef::Table}.*
+            FROM {FollowDef::Table}
+            INNER JOIN {ProfileDef::Table}
+            ON {ProfileDef::Id} = {profile_column}
+            "#)
+    }
+
+    async fn find_follow_profiles(&self, transaction: Option<&mut PostgresTransaction>, profile_column: FollowDef, filter_column: FollowDef, profile_id: IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError> {
+        let mut query_builder = FilteredQuery::new(Self::select_follow_profiles_query(profile_column))
+            .filter(filter_column, Comparison::Equal, profile_id)
+            .filter_if_some(FollowDef::Id, Comparison::LessThan, follow_id)
+            .order_by(FollowDef::Id, Order::Descending)
+            .limit(limit as i64);
+
+        let query = query_builder.build_query_as::<FollowProfileDTO>();
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+}
+
+#[async_trait]
+impl FollowRepositoryTrait<PostgresTransaction> for FollowRepository {
+    async fn follow(&self, transaction: Option<&mut PostgresTransaction>, follower_id: IdType, followed_id: IdType) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            WITH inserted AS (
+                INSERT INTO {FollowDef::Table} ({FollowDef::FollowerId.as_str()}, {FollowDef::FollowedId.as_str()})
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+                RETURNING {FollowDef::FollowerId.as_str()}, {FollowDef::FollowedId.as_str()}
+            ), followed AS (
+                UPDATE {ProfileDef::Table}
+                SET {ProfileDef::FollowerCount.as_str()} = {ProfileDef::FollowerCount} + 1
+                WHERE {ProfileDef::Id} IN (SELECT {FollowDef::FollowedId.as_str()} FROM inserted)
+            )
+            UPDATE {ProfileDef::Table}
+            SET {ProfileDef::FollowingCount.as_str()} = {ProfileDef::FollowingCount} + 1
+            WHERE {ProfileDef::Id} IN (SELECT {FollowDef::FollowerId.as_str()} FROM inserted)
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(follower_id)
+                .bind(followed_id);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn unfollow(&self, transaction: Option<&mut PostgresTransaction>, follower_id: IdType, followed_id: IdType) -> Result<bool, ServerError> {
+        let query_string = iformat!(r#"
+            WITH removed AS (
+                DELETE FROM {FollowDef::Table}
+                WHERE {FollowDef::FollowerId} = $1 AND {FollowDef::FollowedId} = $2
+                RETURNING {FollowDef::FollowerId.as_str()}, {FollowDef::FollowedId.as_str()}
+            ), followed AS (
+                UPDATE {ProfileDef::Table}
+                SET {ProfileDef::FollowerCount.as_str()} = {ProfileDef::FollowerCount} - 1
+                WHERE {ProfileDef::Id} IN (SELECT {FollowDef::FollowedId.as_str()} FROM removed)
+            )
+            UPDATE {ProfileDef::Table}
+            SET {ProfileDef::FollowingCount.as_str()} = {ProfileDef::FollowingCount} - 1
+            WHERE {ProfileDef::Id} IN (SELECT {FollowDef::FollowerId.as_str()} FROM removed)
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(follower_id)
+                .bind(followed_id);
+
+        // The profile of the follower is only updated when a follow was removed
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|result| result.rows_affected() > 0)
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn find_followers_starting_from_id(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError> {
+        self.find_follow_profiles(transaction, FollowDef::FollowerId, FollowDef::FollowedId, profile_id, follow_id, limit).await
+    }
+
+    async fn find_following_starting_from_id(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError> {
+        self.find_follow_profiles(transaction, FollowDef::FollowedId, FollowDef::FollowerId, profile_id, follow_id, limit).await
+    }
+
+    async fn find_feed_starting_from_id(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType, figure_id: Option<IdType>, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
+        // Takes the newest figures of every followed profile from the (profile_id, id) index and merges them,
+        // so a page costs one short index scan per followed profile instead of a scan over all figures.
+        // The condition is left out without a cursor so the statement can use the index either way.
+        let cursor_condition = match figure_id {
+            Some(_) => iformat!("AND {FigureDef::Id} < $3"),
+            None => String::new()
+        };
+        let query_string = iformat!(r#"
+            SELECT {figure_columns()}
+            FROM (
+                SELECT feed_figure.{FigureDef::Id.as_str()}
+                FROM {FollowDef::Table}
+                CROSS JOIN LATERAL (
+                    SELECT {FigureDef::Id} FROM {FigureDef::Table}
+                    WHERE {FigureDef::ProfileId} = {FollowDef::FollowedId} {cursor_condition}
+                    ORDER BY {FigureDef::Id} DESC
+                    LIMIT $2
+                ) AS feed_figure
+                WHERE {FollowDef::FollowerId} = $1
+                ORDER BY feed_figure.{FigureDef::Id.as_str()} DESC
+                LIMIT $2
+            ) AS feed
+            INNER JOIN {FigureDef::Table}
+            ON {FigureDef::Id} = feed.{FigureDef::Id.as_str()}
+            INNER JOIN {ProfileDef::Table}
+            ON {FigureDef::ProfileId} = {ProfileDef::Id}
+            ORDER BY {FigureDef::Id} DESC
+            "#);
+
+        let mut query =
+            sqlx::query_as::<_, FigureDTO>(&query_string)
+                .bind(profile_id)
+                .bind(limit as i64);
+        if let Some(figure_id) = figure_id {
+            query = query.bind(figure_id);
+        }
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn delete_by_profile_id(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType) -> Result<(), ServerError> {
+        // Two profiles follow each other at most once in each direction, so every other profile loses at most one of each
+        let query_string = iformat!(r#"
+            WITH removed AS (
+                DELETE FROM {FollowDef::Table}
+                WHERE {FollowDef::FollowerId} = $1 OR {FollowDef::FollowedId} = $1
+                RETURNING {FollowDef::FollowerId.as_str()}, {FollowDef::FollowedId.as_str()}
+            ), followed AS (
+                UPDATE {ProfileDef::Table}
+                SET {ProfileDef::FollowerCount.as_str()} = {ProfileDef::FollowerCount} - 1
+                WHERE {ProfileDef::Id} IN (SELECT {FollowDef::FollowedId.as_str()} FROM removed WHERE {FollowDef::FollowerId.as_str()} = $1)
+            )
+            UPDATE {ProfileDef::Table}
+            SET {ProfileDef::FollowingCount.as_str()} = {ProfileDef::FollowingCount} - 1
+            WHERE {ProfileDef::Id} IN (SELECT {FollowDef::FollowerId.as_str()} FROM removed WHERE {FollowDef::FollowedId.as_str()} = $1)
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(profile_id);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+}
\ No newline at end of file
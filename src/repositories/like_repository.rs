@@ -0,0 +1,122 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use interpol::format as iformat;
+use crate::entities::figure::FigureDef;
+use crate::entities::like::FigureLikeDef;
+use crate::entities::types::IdType;
+use crate::repositories::traits::{LikeRepositoryTrait, TransactionTrait};
+use crate::repositories::transaction::PostgresTransaction;
+use crate::server_errors::ServerError;
+
+// The like count of a figure is updated in the same statement as its likes,
+// so listings can read it from the figure instead of counting the likes
+#[derive(Clone)]
+pub struct LikeRepository {
+    db: Pool<Postgres>,
+}
+
+impl LikeRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        LikeRepository {
+            db: pool
+        }
+    }
+}
+
+#[async_trait]
+impl LikeRepositoryTrait<PostgresTransaction> for LikeRepository {
+    async fn like(&self, transaction: Option<&mut PostgresTransaction>, figure_id: IdType, profile_id: IdType) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            WITH inserted AS (
+                INSERT INTO {FigureLikeDef::Table} ({FigureLikeDef::FigureId.as_str()}, {FigureLikeDef::ProfileId.as_str()})
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+                RETURNING {FigureLikeDef::FigureId.as_str()}
+            )
+            UPDATE {FigureDef::Table}
+            SET {FigureDef::LikeCount.as_str()} = {FigureDef::LikeCount} + 1
+            WHERE {FigureDef::Id} IN (SELECT {FigureLikeDef::FigureId.as_str()} FROM inserted)
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(figure_id)
+                .bind(profile_id);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn unlike(&self, transaction: Option<&mut PostgresTransaction>, figure_id: IdType, profile_id: IdType) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            WITH removed AS (
+                DELETE FROM {FigureLikeDef::Table}
+                WHERE {FigureLikeDef::FigureId} = $1 AND {FigureLikeDef::ProfileId} = $2
+                RETURNING {FigureLikeDef::FigureId.as_str()}
+            )
+            UPDATE {FigureDef::Table}
+            SET {FigureDef::LikeCount.as_str()} = {FigureDef::LikeCount} - 1
+            WHERE {FigureDef::Id} IN (SELECT {FigureLikeDef::FigureId.as_str()} FROM removed)
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(figure_id)
+                .bind(profile_id);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn find_liked_figure_ids(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType, figure_ids: Vec<IdType>) -> Result<Vec<IdType>, ServerError> {
+        let query_string = iformat!(r#"
+            SELECT {FigureLikeDef::FigureId} FROM {FigureLikeDef::Table}
+            WHERE {FigureLikeDef::ProfileId} = $1 AND {FigureLikeDef::FigureId} = ANY($2)
+            "#);
+
+        let query =
+            sqlx::query_scalar::<_, IdType>(&query_string)
+                .bind(profile_id)
+                .bind(figure_ids);
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn delete_by_profile_id(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType) -> Result<(), ServerError> {
+        // A profile likes a figure at most once, so every figure loses one like
+        let query_string = iformat!(r#"
+            WITH removed AS (
+                DELETE FROM {FigureLikeDef::Table}
+                WHERE {FigureLikeDef::ProfileId} = $1
+                RETURNING {FigureLikeDef::FigureId.as_str()}
+            )
+            UPDATE {FigureDef::Table}
+            SET {FigureDef::LikeCount.as_str()} = {FigureDef::LikeCount} - 1
+            WHERE {FigureDef::Id} IN (SELECT {FigureLikeDef::FigureId.as_str()} FROM removed)
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(profile_id);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+}
@@ -3,4 +3,14 @@ pub mod profile_repository;
 pub mod session_repository;
 pub mod figure_repository;
 pub mod transaction;
-pub mod traits;
\ No newline at end of file
+pub mod traits;
+pub mod query_builder;
+pub mod one_time_token_repository;
+pub mod rate_limit_repository;
+pub mod access_token_repository;
+pub mod oidc_sign_in_repository;
+pub mod tag_repository;
+pub mod like_repository;
+pub mod comment_repository;
+pub mod follow_repository;
+pub mod object_deletion_repository;
\ No newline at end of file
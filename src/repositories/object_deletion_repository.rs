@@ -0,0 +1,103 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use interpol::format as iformat;
+use crate::entities::object_deletion::{ObjectDeletion, ObjectDeletionDef};
+use crate::entities::types::IdType;
+use crate::repositories::traits::{ObjectDeletionRepositoryTrait, TransactionTrait};
+use crate::repositories::transaction::PostgresTransaction;
+use crate::server_errors::ServerError;
+
+#[derive(Clone)]
+pub struct ObjectDeletionRepository {
+    db: Pool<Postgres>,
+}
+
+impl ObjectDeletionRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        ObjectDeletionRepository {
+            db: pool
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectDeletionRepositoryTrait<PostgresTransaction> for ObjectDeletionRepository {
+    async fn create(&self, transaction: Option<&mut PostgresTransaction>, names: Vec<String>, first_retry_after: i64) -> Result<Vec<ObjectDeletion>, ServerError> {
+        let query_string = iformat!(r#"
+            INSERT INTO {ObjectDeletionDef::Table} ({ObjectDeletionDef::Name.as_str()}, {ObjectDeletionDef::NextAttemptAt.as_str()})
+            SELECT unnest($1::text[]), now() + $2 * INTERVAL '1 second'
+            RETURNING {ObjectDeletionDef::Id.as_str()}, {ObjectDeletionDef::Name.as_str()}, {ObjectDeletionDef::Attempts.as_str()}
+            "#);
+
+        let query =
+            sqlx::query_as::<_, ObjectDeletion>(&query_string)
+                .bind(names)
+                .bind(first_retry_after);
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn find_due(&self, transaction: Option<&mut PostgresTransaction>, limit: i32) -> Result<Vec<ObjectDeletion>, ServerError> {
+        let query_string = iformat!(r#"
+            SELECT {ObjectDeletionDef::Id.as_str()}, {ObjectDeletionDef::Name.as_str()}, {ObjectDeletionDef::Attempts.as_str()}
+            FROM {ObjectDeletionDef::Table}
+            WHERE {ObjectDeletionDef::NextAttemptAt} <= now()
+            ORDER BY {ObjectDeletionDef::NextAttemptAt}
+            LIMIT $1
+            "#);
+
+        let query =
+            sqlx::query_as::<_, ObjectDeletion>(&query_string)
+                .bind(limit);
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn postpone(&self, transaction: Option<&mut PostgresTransaction>, id: IdType, retry_after: i64) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            UPDATE {ObjectDeletionDef::Table}
+            SET {ObjectDeletionDef::Attempts.as_str()} = {ObjectDeletionDef::Attempts} + 1,
+                {ObjectDeletionDef::NextAttemptAt.as_str()} = now() + $2 * INTERVAL '1 second'
+            WHERE {ObjectDeletionDef::Id} = $1
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(id)
+                .bind(retry_after);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn delete_by_id(&self, transaction: Option<&mut PostgresTransaction>, id: IdType) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            DELETE FROM {ObjectDeletionDef::Table}
+            WHERE {ObjectDeletionDef::Id} = $1
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(id);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+}
\ No newline at end of file
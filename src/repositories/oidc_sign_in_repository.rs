@@ -0,0 +1,55 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::{AsyncCommands, RedisResult};
+use crate::entities::oidc_sign_in::OidcSignIn;
+use crate::repositories::traits::OidcSignInRepositoryTrait;
+use crate::server_errors::ServerError;
+
+#[derive(Clone)]
+pub struct OidcSignInRepository {
+    connection: ConnectionManager,
+}
+
+impl OidcSignInRepository {
+    pub fn new(connection: ConnectionManager) -> Self {
+        OidcSignInRepository {
+            connection
+        }
+    }
+}
+
+#[async_trait]
+impl OidcSignInRepositoryTrait for OidcSignInRepository {
+    async fn create(&self, state_hash: &str, sign_in: &OidcSignIn, time_until_expiration: usize) -> Result<(), ServerError> {
+        let value = serde_json::to_string(sign_in)
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        self.connection
+            .clone()
+            .set_ex(sign_in_key(state_hash), value, time_until_expiration)
+            .await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn take(&self, state_hash: &str) -> Result<OidcSignIn, ServerError> {
+        let key = sign_in_key(state_hash);
+        // Read and removed atomically, so that a callback can't be completed twice
+        let result: RedisResult<(Option<String>,)> = redis::pipe()
+            .atomic()
+            .get(&key)
+            .del(&key).ignore()
+            .query_async(&mut self.connection.clone())
+            .await;
+
+        match result {
+            Ok((Some(value),)) => serde_json::from_str(&value)
+                .map_err(|e| ServerError::InternalError(Arc::new(e.into()))),
+            Ok((None,)) => Err(ServerError::InvalidOidcSignIn),
+            Err(e) => Err(ServerError::InternalError(Arc::new(e.into())))
+        }
+    }
+}
+
+fn sign_in_key(state_hash: &str) -> String {
+    format!("oidc_sign_in:{}", state_hash)
+}
\ No newline at end of file
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::{AsyncCommands, RedisResult};
+use crate::entities::one_time_token::OneTimeTokenKind;
+use crate::entities::types::IdType;
+use crate::repositories::traits::OneTimeTokenRepositoryTrait;
+use crate::server_errors::ServerError;
+
+#[derive(Clone)]
+pub struct OneTimeTokenRepository {
+    connection: ConnectionManager,
+}
+
+impl OneTimeTokenRepository {
+    pub fn new(connection: ConnectionManager) -> Self {
+        OneTimeTokenRepository {
+            connection
+        }
+    }
+}
+
+#[async_trait]
+impl OneTimeTokenRepositoryTrait for OneTimeTokenRepository {
+    async fn create(&self, kind: OneTimeTokenKind, token_hash: &str, user_id: IdType, time_until_expiration: usize) -> Result<(), ServerError> {
+        self.connection
+            .clone()
+            .set_ex(token_key(kind, token_hash), user_id, time_until_expiration)
+            .await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn find(&self, kind: OneTimeTokenKind, token_hash: &str) -> Result<IdType, ServerError> {
+        let result: RedisResult<Option<IdType>> = self.connection
+            .clone()
+            .get(token_key(kind, token_hash))
+            .await;
+
+        match result {
+            Ok(Some(user_id)) => Ok(user_id),
+            Ok(None) => Err(ServerError::InvalidToken),
+            Err(e) => Err(ServerError::InternalError(Arc::new(e.into())))
+        }
+    }
+
+    async fn take(&self, kind: OneTimeTokenKind, token_hash: &str) -> Result<IdType, ServerError> {
+        let key = token_key(kind, token_hash);
+        // Reading and removing happens atomically so that a token can't be used twice by concurrent requests
+        let result: RedisResult<(Option<IdType>,)> = redis::pipe()
+            .atomic()
+            .get(&key)
+            .del(&key).ignore()
+            .query_async(&mut self.connection.clone())
+            .await;
+
+        match result {
+            Ok((Some(user_id),)) => Ok(user_id),
+            Ok((None,)) => Err(ServerError::InvalidToken),
+            Err(e) => Err(ServerError::InternalError(Arc::new(e.into())))
+        }
+    }
+}
+
+fn token_key(kind: OneTimeTokenKind, token_hash: &str) -> String {
+    format!("{}:{}", kind.as_str(), token_hash)
+}
\ No newline at end of file
@@ -44,6 +44,8 @@ impl ProfileRepositoryTrait<PostgresTransaction> for ProfileRepository {
                 banner: None,
                 profile_picture: None,
                 user_id,
+                follower_count: 0,
+                following_count: 0,
             })
             .map_err(|e| {
                 match e {
@@ -91,6 +93,22 @@ impl ProfileRepositoryTrait<PostgresTransaction> for ProfileRepository {
         }
     }
 
+    async fn find_by_username(&self, transaction: Option<&mut PostgresTransaction>, username: &str) -> Result<Profile, ServerError> {
+        let query_string = iformat!("SELECT * FROM {ProfileDef::Table} WHERE {ProfileDef::Username.as_str()} = $1");
+        let query =
+            sqlx::query_as::<_, Profile>(&query_string)
+                .bind(username);
+        let query_result = match transaction {
+            Some(transaction) => query.fetch_one(transaction.inner()).await,
+            None => query.fetch_one(&self.db).await
+        };
+        match query_result {
+            Ok(profile) => Ok(profile),
+            Err(sqlx::Error::RowNotFound) => Err(ServerError::ResourceNotFound),
+            Err(e) => Err(ServerError::InternalError(Arc::new(e.into())))
+        }
+    }
+
     async fn update_profile_by_id(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType, display_name: Option<String>, bio: Option<String>, banner: Option<String>, profile_picture: Option<String>) -> Result<(), ServerError> {
         let query_string = iformat!(r#"
             UPDATE {ProfileDef::Table}
@@ -125,4 +143,47 @@ impl ProfileRepositoryTrait<PostgresTransaction> for ProfileRepository {
             .and_then(|row| row.try_get(0))
             .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
     }
+
+    async fn search(&self, transaction: Option<&mut PostgresTransaction>, query: String, offset: i64, limit: i32) -> Result<Vec<Profile>, ServerError> {
+        // Parsed with the configuration the search vector is generated with, see scripts/seed.sql
+        let query_string = iformat!(r#"
+            SELECT {ProfileDef::Table}.*
+            FROM {ProfileDef::Table}, websearch_to_tsquery('english', $1) AS search_query
+            WHERE {ProfileDef::SearchVector} @@ search_query
+            ORDER BY ts_rank({ProfileDef::SearchVector}, search_query) DESC, {ProfileDef::Id} DESC
+            LIMIT $2 OFFSET $3
+            "#);
+
+        let query =
+            sqlx::query_as::<_, Profile>(&query_string)
+                .bind(query)
+                .bind(limit as i64)
+                .bind(offset);
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn delete_by_id(&self, transaction: Option<&mut PostgresTransaction>, profile_id: IdType) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            DELETE FROM {ProfileDef::Table}
+            WHERE {ProfileDef::Id} = $1
+            "#);
+        let query =
+            sqlx::query(&query_string)
+                .bind(profile_id);
+
+        let result = match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        match result.rows_affected() {
+            0 => Err(ServerError::ResourceNotFound),
+            _ => Ok(())
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,225 @@
+use sqlx::{Encode, FromRow, Postgres, QueryBuilder, Type};
+use sqlx::postgres::{PgArguments, PgRow};
+use sqlx::query::{Query, QueryAs};
+use crate::entities::comment::CommentDef;
+use crate::entities::figure::FigureDef;
+use crate::entities::follow::FollowDef;
+use crate::entities::like::FigureLikeDef;
+use crate::entities::profile::ProfileDef;
+use crate::entities::tag::TagDef;
+use crate::entities::user::UserDef;
+
+// A column that can be referenced in a filtered query, implemented by the entity definitions
+pub trait Column: Sync {
+    fn qualified_name(&self) -> &str;
+}
+
+impl Column for FigureDef {
+    fn qualified_name(&self) -> &str {
+        self.as_table_str()
+    }
+}
+
+impl Column for ProfileDef {
+    fn qualified_name(&self) -> &str {
+        self.as_table_str()
+    }
+}
+
+impl Column for UserDef {
+    fn qualified_name(&self) -> &str {
+        self.as_table_str()
+    }
+}
+
+impl Column for FigureLikeDef {
+    fn qualified_name(&self) -> &str {
+        self.as_table_str()
+    }
+}
+
+impl Column for CommentDef {
+    fn qualified_name(&self) -> &str {
+        self.as_table_str()
+    }
+}
+
+impl Column for FollowDef {
+    fn qualified_name(&self) -> &str {
+        self.as_table_str()
+    }
+}
+
+impl Column for TagDef {
+    fn qualified_name(&self) -> &str {
+        self.as_table_str()
+    }
+}
+
+pub enum Comparison {
+    Equal,
+    LessThan,
+    GreaterThan,
+    ILike,
+}
+
+impl Comparison {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Comparison::Equal => "=",
+            Comparison::LessThan => "<",
+            Comparison::GreaterThan => ">",
+            Comparison::ILike => "ILIKE",
+        }
+    }
+}
+
+pub enum Order {
+    Ascending,
+    Descending,
+}
+
+impl Order {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Order::Ascending => "ASC",
+            Order::Descending => "DESC",
+        }
+    }
+}
+
+// Adds WHERE, ORDER BY and LIMIT clauses to a base query (SELECT ... FROM ... JOIN ...).
+// Every value is bound as an argument, so the statement text only depends on which filters are used
+// and the prepared statement can be reused between calls.
+pub struct FilteredQuery<'args> {
+    builder: QueryBuilder<'args, Postgres>,
+    has_condition: bool,
+    order_by: Option<(String, Order)>,
+    limit: Option<i64>,
+    finished: bool,
+}
+
+impl<'args> FilteredQuery<'args> {
+    pub fn new(base_query: impl Into<String>) -> Self {
+        Self {
+            builder: QueryBuilder::new(base_query),
+            has_condition: false,
+            order_by: None,
+            limit: None,
+            finished: false,
+        }
+    }
+
+    pub fn filter<C, V>(mut self, column: C, comparison: Comparison, value: V) -> Self
+        where C: Column, V: 'args + Encode<'args, Postgres> + Send + Type<Postgres> {
+        self.push_condition();
+        self.builder
+            .push(column.qualified_name())
+            .push(" ")
+            .push(comparison.as_str())
+            .push(" ")
+            .push_bind(value);
+        self
+    }
+
+    // Only filters when a value is given
+    pub fn filter_if_some<C, V>(self, column: C, comparison: Comparison, value: Option<V>) -> Self
+        where C: Column, V: 'args + Encode<'args, Postgres> + Send + Type<Postgres> {
+        match value {
+            Some(value) => self.filter(column, comparison, value),
+            None => self
+        }
+    }
+
+    pub fn filter_null<C: Column>(mut self, column: C) -> Self {
+        self.push_condition();
+        self.builder
+            .push(column.qualified_name())
+            .push(" IS NULL");
+        self
+    }
+
+    // Matches if any of the columns matches the value, the value is bound once per column
+    pub fn filter_any<V>(mut self, columns: &[&dyn Column], comparison: Comparison, value: V) -> Self
+        where V: 'args + Encode<'args, Postgres> + Send + Type<Postgres> + Clone {
+        self.push_condition();
+        self.builder.push("(");
+        for (index, column) in columns.iter().enumerate() {
+            if index > 0 {
+                self.builder.push(" OR ");
+            }
+            self.builder
+                .push(column.qualified_name())
+                .push(" ")
+                .push(comparison.as_str())
+                .push(" ")
+                .push_bind(value.clone());
+        }
+        self.builder.push(")");
+        self
+    }
+
+    // Only filters when a value is given
+    pub fn filter_any_if_some<V>(self, columns: &[&dyn Column], comparison: Comparison, value: Option<V>) -> Self
+        where V: 'args + Encode<'args, Postgres> + Send + Type<Postgres> + Clone {
+        match value {
+            Some(value) => self.filter_any(columns, comparison, value),
+            None => self
+        }
+    }
+
+    pub fn order_by<C: Column>(mut self, column: C, order: Order) -> Self {
+        self.order_by = Some((column.qualified_name().to_string(), order));
+        self
+    }
+
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    #[cfg(test)]
+    pub fn sql(&mut self) -> &str {
+        self.finish();
+        self.builder.sql()
+    }
+
+    pub fn build(&mut self) -> Query<'_, Postgres, PgArguments> {
+        self.finish();
+        self.builder.build()
+    }
+
+    pub fn build_query_as<'q, T: FromRow<'q, PgRow>>(&'q mut self) -> QueryAs<'q, Postgres, T, PgArguments> {
+        self.finish();
+        self.builder.build_query_as()
+    }
+
+    fn push_condition(&mut self) {
+        if self.has_condition {
+            self.builder.push(" AND ");
+        } else {
+            self.builder.push(" WHERE ");
+            self.has_condition = true;
+        }
+    }
+
+    // ORDER BY and LIMIT are added last so that filters can be added in any order
+    fn finish(&mut self) {
+        if self.finished {
+            return;
+        }
+        if let Some((column, order)) = &self.order_by {
+            self.builder
+                .push(" ORDER BY ")
+                .push(column)
+                .push(" ")
+                .push(order.as_str());
+        }
+        if let Some(limit) = self.limit {
+            self.builder
+                .push(" LIMIT ")
+                .push_bind(limit);
+        }
+        self.finished = true;
+    }
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::{AsyncCommands, RedisResult, Script};
+use crate::repositories::traits::RateLimitRepositoryTrait;
+use crate::server_errors::ServerError;
+
+// Only the first increment of a window sets the expiration, done in a script so that
+// a counter can't be left without one
+const INCREMENT_SCRIPT: &str = r#"
+local count = redis.call('INCR', KEYS[1])
+if count == 1 then
+    redis.call('EXPIRE', KEYS[1], ARGV[1])
+end
+return {count, redis.call('TTL', KEYS[1])}
+"#;
+
+#[derive(Clone)]
+pub struct RateLimitRepository {
+    connection: ConnectionManager,
+    increment_script: Arc<Script>,
+}
+
+impl RateLimitRepository {
+    pub fn new(connection: ConnectionManager) -> Self {
+        RateLimitRepository {
+            connection,
+            increment_script: Arc::new(Script::new(INCREMENT_SCRIPT)),
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimitRepositoryTrait for RateLimitRepository {
+    async fn increment(&self, key: &str, window: usize) -> Result<(u64, usize), ServerError> {
+        let result: RedisResult<(u64, i64)> = self.increment_script
+            .key(rate_limit_key(key))
+            .arg(window)
+            .invoke_async(&mut self.connection.clone())
+            .await;
+
+        result
+            .map(|(count, time_to_live)| (count, time_to_live.max(0) as usize))
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn time_to_live(&self, key: &str) -> Result<Option<usize>, ServerError> {
+        let time_to_live: i64 = self.connection.clone().ttl(rate_limit_key(key)).await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        // Negative when the key doesn't exist or has no expiration, which rate limit keys always have
+        match time_to_live > 0 {
+            true => Ok(Some(time_to_live as usize)),
+            false => Ok(None)
+        }
+    }
+
+    async fn set(&self, key: &str, time_until_expiration: usize) -> Result<(), ServerError> {
+        self.connection
+            .clone()
+            .set_ex(rate_limit_key(key), 1, time_until_expiration)
+            .await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), ServerError> {
+        self.connection
+            .clone()
+            .del(rate_limit_key(key))
+            .await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+}
+
+fn rate_limit_key(key: &str) -> String {
+    format!("rate_limit:{}", key)
+}
\ No newline at end of file
@@ -1,22 +1,42 @@
 use std::sync::Arc;
 use async_trait::async_trait;
 use redis::aio::ConnectionManager;
-use redis::{AsyncCommands, Expiry, RedisResult};
+use redis::{AsyncCommands, RedisResult, Script};
 use crate::entities::types::IdType;
+use crate::entities::user::Role;
 use serde::{Serialize, Deserialize};
-use crate::entities::dtos::session_dtos::Session;
+use chrono::{DateTime, Utc};
+use crate::entities::dtos::session_dtos::{ClientInfo, Session, SessionPolicies, SessionSummary};
 use crate::repositories::traits::SessionRepositoryTrait;
 use crate::server_errors::ServerError;
+use crate::utilities::token::hash_token;
+
+// The idle timeout depends on the policy of the session, so the session is read and extended in a script
+// instead of a GETEX. Sessions stored before they could be persistent don't have the field and use the default
+const FIND_AND_EXTEND_SCRIPT: &str = r#"
+local value = redis.call('GET', KEYS[1])
+if not value then
+    return false
+end
+local idle_timeout = ARGV[1]
+if cjson.decode(value).persistent == true then
+    idle_timeout = ARGV[2]
+end
+redis.call('EXPIRE', KEYS[1], idle_timeout)
+return value
+"#;
 
 #[derive(Clone)]
 pub struct SessionRepository {
     connection: ConnectionManager,
+    find_and_extend_script: Arc<Script>,
 }
 
 impl SessionRepository {
     pub fn new(connection: ConnectionManager) -> Self {
         SessionRepository {
-            connection
+            connection,
+            find_and_extend_script: Arc::new(Script::new(FIND_AND_EXTEND_SCRIPT)),
         }
     }
 }
@@ -25,69 +45,210 @@ impl SessionRepository {
 pub struct SessionValueInStore {
     pub user_id: IdType,
     pub profile_id: IdType,
+    // Sessions created before roles existed don't have one
+    #[serde(default)]
+    pub role: Role,
+    // Sessions created before the following were stored don't have them
+    #[serde(default)]
+    pub client: ClientInfo,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub last_seen_at: DateTime<Utc>,
+    #[serde(default)]
+    pub persistent: bool,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl SessionValueInStore {
+    fn from_session(session: &Session) -> Self {
+        Self {
+            user_id: session.get_user_id(),
+            profile_id: session.get_profile_id(),
+            role: session.get_role(),
+            client: session.get_client().clone(),
+            created_at: session.get_created_at(),
+            last_seen_at: session.get_last_seen_at(),
+            persistent: session.is_persistent(),
+            expires_at: session.get_expires_at(),
+        }
+    }
+
+    fn into_session(self, session_id: String) -> Session {
+        Session::new(session_id, self.user_id, self.profile_id, self.role, self.client, None)
+            .with_timestamps(self.created_at, self.last_seen_at)
+            .with_expiry(self.persistent, self.expires_at)
+    }
+
+    fn into_summary(self, public_id: String) -> SessionSummary {
+        SessionSummary {
+            public_id,
+            user_id: self.user_id,
+            client: self.client,
+            created_at: self.created_at,
+            last_seen_at: self.last_seen_at,
+        }
+    }
 }
 
 #[async_trait]
 impl SessionRepositoryTrait for SessionRepository {
     async fn create(&self, session: Session) -> Result<Session, ServerError> {
-        let session_in_store = SessionValueInStore {
-            user_id: session.get_user_id(),
-            profile_id: session.get_profile_id(),
-        };
-        let session_value_json = match serde_json::to_string(&session_in_store) {
+        let session_value_json = match serde_json::to_string(&SessionValueInStore::from_session(&session)) {
             Ok(json) => json,
             Err(e) => {
                 return Err(ServerError::InternalError(Arc::new(e.into())));
             }
         };
 
-        let mut connection = self.connection.clone();
-        let result = match session.get_time_until_expiration() {
-            Some(time) => connection.set_ex(
-                session.get_id(),
+        // The session is also added to the index of its user, so that all sessions of a user can be removed
+        let public_id = session.get_public_id();
+        let mut pipeline = redis::pipe();
+        pipeline.atomic();
+        match session.get_time_until_expiration() {
+            Some(time) => pipeline.set_ex(
+                session_key(&public_id),
                 session_value_json,
                 time),
-            None => {
-                connection.set(
-                    session.get_id(),
-                    session_value_json,
-                )
-            }
-        }.await;
+            None => pipeline.set(
+                session_key(&public_id),
+                session_value_json,
+            )
+        }.ignore();
+        pipeline.sadd(user_sessions_key(session.get_user_id()), public_id).ignore();
 
+        let result: RedisResult<()> = pipeline.query_async(&mut self.connection.clone()).await;
         match result {
             Ok(()) => Ok(session),
             Err(e) => Err(ServerError::InternalError(Arc::new(e.into())))
         }
     }
 
-    async fn find_by_id(&self, session_id: &str, time_until_expiration: Option<usize>) -> Result<Session, ServerError> {
-        let mut connection = self.connection.clone();
-        let result: RedisResult<String> = match time_until_expiration {
-            Some(time) => connection.get_ex(session_id, Expiry::EX(time)),
-            None => connection.get(session_id)
-        }.await;
+    async fn find_and_extend(&self, session_id: &str, policies: SessionPolicies) -> Result<Session, ServerError> {
+        let result: RedisResult<Option<String>> = self.find_and_extend_script
+            .key(session_key(&hash_token(session_id)))
+            .arg(policies.default.idle_timeout)
+            .arg(policies.remember_me.idle_timeout)
+            .invoke_async(&mut self.connection.clone())
+            .await;
 
         match result {
-            Ok(session_string) => {
+            Ok(Some(session_string)) => {
                 serde_json::from_str::<SessionValueInStore>(&session_string)
-                    .map(|value| Session::new(
-                        session_id.to_string(),
-                        value.user_id,
-                        value.profile_id,
-                        None,
-                    ))
+                    .map(|value| value.into_session(session_id.to_string()))
                     .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
             }
-            Err(_) => Err(ServerError::ResourceNotFound)
+            Ok(None) => Err(ServerError::ResourceNotFound),
+            Err(e) => Err(ServerError::InternalError(Arc::new(e.into())))
+        }
+    }
+
+    async fn find_all_by_user_id(&self, user_id: IdType) -> Result<Vec<SessionSummary>, ServerError> {
+        let mut connection = self.connection.clone();
+        let key = user_sessions_key(user_id);
+        let public_ids: Vec<String> = connection.smembers(&key).await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        if public_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Explicit MGET, `get` would send a GET if the user only has one session
+        let session_keys: Vec<String> = public_ids.iter().map(|public_id| session_key(public_id)).collect();
+        let values: Vec<Option<String>> = redis::cmd("MGET").arg(&session_keys).query_async(&mut connection).await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        let mut sessions = Vec::new();
+        let mut expired_public_ids = Vec::new();
+        for (public_id, value) in public_ids.into_iter().zip(values) {
+            match value.and_then(|value| serde_json::from_str::<SessionValueInStore>(&value).ok()) {
+                Some(value) => sessions.push(value.into_summary(public_id)),
+                None => expired_public_ids.push(public_id)
+            }
         }
+
+        // Sessions expire without being removed from the index, clean those up
+        if !expired_public_ids.is_empty() {
+            let _: usize = connection.srem(&key, expired_public_ids).await
+                .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        }
+        Ok(sessions)
+    }
+
+    async fn update_expiration(&self, session_id: &str, time_until_expiration: usize) -> Result<(), ServerError> {
+        let updated: bool = self.connection.clone().expire(session_key(&hash_token(session_id)), time_until_expiration).await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        match updated {
+            true => Ok(()),
+            false => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn update_last_seen(&self, session: &Session) -> Result<(), ServerError> {
+        let session_value_json = serde_json::to_string(&SessionValueInStore::from_session(session))
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        // XX so that a session removed in the meantime isn't recreated, KEEPTTL leaves its expiration as is
+        let result: RedisResult<Option<String>> = redis::cmd("SET")
+            .arg(session_key(&session.get_public_id()))
+            .arg(session_value_json)
+            .arg("XX")
+            .arg("KEEPTTL")
+            .query_async(&mut self.connection.clone())
+            .await;
+
+        result
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
     }
 
     async fn remove_by_id(&self, session_id: &str) -> Result<(), ServerError> {
-        self.connection
-            .clone()
-            .del(session_id)
+        self.remove_by_public_id(&hash_token(session_id)).await
+    }
+
+    async fn remove_by_public_id(&self, public_id: &str) -> Result<(), ServerError> {
+        let mut connection = self.connection.clone();
+        let key = session_key(public_id);
+        let session_string: Option<String> = connection.get(&key).await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        let mut pipeline = redis::pipe();
+        pipeline.atomic().del(key).ignore();
+        if let Some(value) = session_string.and_then(|session_string| serde_json::from_str::<SessionValueInStore>(&session_string).ok()) {
+            pipeline.srem(user_sessions_key(value.user_id), public_id).ignore();
+        }
+
+        pipeline.query_async(&mut connection)
+            .await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn remove_all_by_user_id(&self, user_id: IdType) -> Result<(), ServerError> {
+        let mut connection = self.connection.clone();
+        let key = user_sessions_key(user_id);
+        // The index can contain sessions that already expired, deleting those is a no-op
+        let public_ids: Vec<String> = connection.smembers(&key).await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        let mut pipeline = redis::pipe();
+        pipeline.atomic();
+        for public_id in public_ids {
+            pipeline.del(session_key(&public_id)).ignore();
+        }
+        pipeline.del(key).ignore();
+
+        pipeline.query_async(&mut connection)
             .await
             .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
     }
+}
+
+// Sessions are stored under the hash of their id, so that a dump of the store can't be used as cookies
+fn session_key(public_id: &str) -> String {
+    format!("session:{}", public_id)
+}
+
+// Key of the set containing the public ids of the sessions of a user
+fn user_sessions_key(user_id: IdType) -> String {
+    format!("user_sessions:{}", user_id)
 }
\ No newline at end of file
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use interpol::format as iformat;
+use crate::entities::tag::{FigureTagDef, TagCount, TagDef};
+use crate::entities::types::IdType;
+use crate::repositories::traits::{TagRepositoryTrait, TransactionTrait};
+use crate::repositories::transaction::PostgresTransaction;
+use crate::server_errors::ServerError;
+
+#[derive(Clone)]
+pub struct TagRepository {
+    db: Pool<Postgres>,
+}
+
+impl TagRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        TagRepository {
+            db: pool
+        }
+    }
+}
+
+#[async_trait]
+impl TagRepositoryTrait<PostgresTransaction> for TagRepository {
+    async fn replace_figure_tags(&self, transaction: Option<&mut PostgresTransaction>, figure_id: IdType, tags: Vec<String>) -> Result<(), ServerError> {
+        // A single statement, tags the figure keeps are left alone so no row is both deleted and inserted.
+        // The upsert returns the id of existing tags as well, even those created by a concurrent transaction.
+        let query_string = iformat!(r#"
+            WITH removed AS (
+                DELETE FROM {FigureTagDef::Table}
+                WHERE {FigureTagDef::FigureId} = $1
+                AND {FigureTagDef::TagId} NOT IN (SELECT {TagDef::Id} FROM {TagDef::Table} WHERE {TagDef::Name} = ANY($2))
+            ), upserted AS (
+                INSERT INTO {TagDef::Table} ({TagDef::Name.as_str()})
+                SELECT unnest($2::text[])
+                ON CONFLICT ({TagDef::Name.as_str()}) DO UPDATE SET {TagDef::Name.as_str()} = EXCLUDED.{TagDef::Name.as_str()}
+                RETURNING {TagDef::Id.as_str()}
+            )
+            INSERT INTO {FigureTagDef::Table} ({FigureTagDef::FigureId.as_str()}, {FigureTagDef::TagId.as_str()})
+            SELECT $1, upserted.{TagDef::Id.as_str()} FROM upserted
+            ON CONFLICT DO NOTHING
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(figure_id)
+                .bind(tags);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn find_popular(&self, transaction: Option<&mut PostgresTransaction>, limit: i32) -> Result<Vec<TagCount>, ServerError> {
+        let query_string = iformat!(r#"
+            SELECT {TagDef::Name} AS name, count(*) AS figure_count
+            FROM {TagDef::Table}
+            INNER JOIN {FigureTagDef::Table}
+            ON {FigureTagDef::TagId} = {TagDef::Id}
+            GROUP BY {TagDef::Name}
+            ORDER BY figure_count DESC, {TagDef::Name}
+            LIMIT $1
+            "#);
+
+        let query =
+            sqlx::query_as::<_, TagCount>(&query_string)
+                .bind(limit as i64);
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+}
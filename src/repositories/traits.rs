@@ -1,10 +1,17 @@
 use async_trait::async_trait;
-use crate::entities::dtos::figure_dto::FigureDTO;
-use crate::entities::dtos::session_dtos::Session;
+use crate::entities::access_token::{AccessToken, Scope};
+use crate::entities::dtos::comment_dto::CommentDTO;
+use crate::entities::dtos::figure_dto::{FigureDTO, LikedFigureDTO};
+use crate::entities::dtos::profile_dto::FollowProfileDTO;
+use crate::entities::dtos::session_dtos::{Session, SessionPolicies, SessionSummary};
 use crate::entities::figure::Figure;
+use crate::entities::object_deletion::ObjectDeletion;
+use crate::entities::oidc_sign_in::OidcSignIn;
+use crate::entities::one_time_token::OneTimeTokenKind;
 use crate::entities::profile::Profile;
+use crate::entities::tag::TagCount;
 use crate::entities::types::IdType;
-use crate::entities::user::User;
+use crate::entities::user::{User, UserAndProfileFromQuery};
 use crate::server_errors::ServerError;
 
 #[async_trait]
@@ -24,6 +31,27 @@ pub trait UserRepositoryTrait<T: TransactionTrait>: Send + Sync + Clone {
     async fn create(&self, transaction: Option<&mut T>, email: String, password_hash: String) -> Result<User, ServerError>;
     async fn find_one_by_email(&self, transaction: Option<&mut T>, email: String) -> Result<User, ServerError>;
     async fn find_one_by_id(&self, transaction: Option<&mut T>, id: IdType) -> Result<User, ServerError>;
+    // Search matches a part of the email or username, case insensitive
+    async fn find_starting_from_id_with_search(&self, transaction: Option<&mut T>, user_id: Option<IdType>, search: Option<String>, limit: i32) -> Result<Vec<UserAndProfileFromQuery>, ServerError>;
+    async fn update_password(&self, transaction: Option<&mut T>, user_id: IdType, password_hash: String) -> Result<(), ServerError>;
+    // Marks the email of the user as verified
+    async fn update_verified_at(&self, transaction: Option<&mut T>, user_id: IdType) -> Result<(), ServerError>;
+    async fn update_suspended(&self, transaction: Option<&mut T>, user_id: IdType, suspended: bool) -> Result<(), ServerError>;
+    // Setting a new secret disables two factor authentication until the secret is confirmed
+    async fn update_totp_secret(&self, transaction: Option<&mut T>, user_id: IdType, secret: Option<String>) -> Result<(), ServerError>;
+    async fn enable_totp(&self, transaction: Option<&mut T>, user_id: IdType, step: i64) -> Result<(), ServerError>;
+    // Errors with ResourceNotFound if the step isn't after the last used step, so that codes can't be replayed
+    async fn update_totp_last_used_step(&self, transaction: Option<&mut T>, user_id: IdType, step: i64) -> Result<(), ServerError>;
+    // Removes the previous recovery codes of the user
+    async fn replace_recovery_codes(&self, transaction: Option<&mut T>, user_id: IdType, code_hashes: Vec<String>) -> Result<(), ServerError>;
+    // Errors with ResourceNotFound if the user has no such code
+    async fn remove_recovery_code(&self, transaction: Option<&mut T>, user_id: IdType, code_hash: &str) -> Result<(), ServerError>;
+    // User the identity at an OpenID Connect provider is linked to
+    async fn find_one_by_external_identity(&self, transaction: Option<&mut T>, issuer: &str, subject: &str) -> Result<User, ServerError>;
+    async fn create_external_identity(&self, transaction: Option<&mut T>, user_id: IdType, issuer: String, subject: String) -> Result<(), ServerError>;
+    async fn has_external_identity(&self, transaction: Option<&mut T>, user_id: IdType) -> Result<bool, ServerError>;
+    // Access tokens, recovery codes and external identities of the user are removed along with it
+    async fn delete_by_id(&self, transaction: Option<&mut T>, user_id: IdType) -> Result<(), ServerError>;
 }
 
 #[async_trait]
@@ -31,8 +59,12 @@ pub trait ProfileRepositoryTrait<T: TransactionTrait>: Send + Sync + Clone {
     async fn create(&self, transaction: Option<&mut T>, username: String, user_id: IdType) -> Result<Profile, ServerError>;
     async fn find_by_id(&self, transaction: Option<&mut T>, profile_id: IdType) -> Result<Profile, ServerError>;
     async fn find_by_user_id(&self, transaction: Option<&mut T>, user_id: IdType) -> Result<Profile, ServerError>;
+    async fn find_by_username(&self, transaction: Option<&mut T>, username: &str) -> Result<Profile, ServerError>;
     async fn update_profile_by_id(&self, transaction: Option<&mut T>, profile_id: IdType, display_name: Option<String>, bio: Option<String>, banner: Option<String>, profile_picture: Option<String>) -> Result<(), ServerError>;
     async fn get_total_profiles_count(&self, transaction: Option<&mut T>) -> Result<IdType, ServerError>;
+    // Full-text search over the username, display name and bio, most relevant first
+    async fn search(&self, transaction: Option<&mut T>, query: String, offset: i64, limit: i32) -> Result<Vec<Profile>, ServerError>;
+    async fn delete_by_id(&self, transaction: Option<&mut T>, profile_id: IdType) -> Result<(), ServerError>;
 }
 
 #[async_trait]
@@ -40,15 +72,127 @@ pub trait FigureRepositoryTrait<T: TransactionTrait>: Send + Sync + Clone {
     async fn create(&self, transaction: Option<&mut T>, figure: Figure) -> Result<Figure, ServerError>;
     async fn find_by_id(&self, transaction: Option<&mut T>, figure_id: IdType) -> Result<FigureDTO, ServerError>;
     async fn find_starting_from_id_with_profile_id(&self, transaction: Option<&mut T>, figure_id: Option<IdType>, profile_id: Option<IdType>, limit: i32) -> Result<Vec<FigureDTO>, ServerError>;
+    async fn find_starting_from_id_with_tag(&self, transaction: Option<&mut T>, figure_id: Option<IdType>, tag: String, limit: i32) -> Result<Vec<FigureDTO>, ServerError>;
+    // Full-text search over the title and description, most relevant first
+    async fn search(&self, transaction: Option<&mut T>, query: String, offset: i64, limit: i32) -> Result<Vec<FigureDTO>, ServerError>;
+    // Figures liked by the profile, paginated by the id of the like
+    async fn find_liked_starting_from_id(&self, transaction: Option<&mut T>, profile_id: IdType, like_id: Option<IdType>, limit: i32) -> Result<Vec<LikedFigureDTO>, ServerError>;
     async fn update_figure(&self, transaction: Option<&mut T>, figure: Figure) -> Result<(), ServerError>;
     async fn delete_figure_by_id(&self, transaction: Option<&mut T>, figure_id: IdType) -> Result<(), ServerError>;
+    // Returns the urls of the deleted figures
+    async fn delete_by_profile_id(&self, transaction: Option<&mut T>, profile_id: IdType) -> Result<Vec<String>, ServerError>;
     async fn count_by_profile_id(&self, transaction: Option<&mut T>, profile_id: IdType) -> Result<IdType, ServerError>;
     async fn get_total_figures_count(&self, transaction: Option<&mut T>) -> Result<IdType, ServerError>;
 }
 
+#[async_trait]
+pub trait TagRepositoryTrait<T: TransactionTrait>: Send + Sync + Clone {
+    // Tags that don't exist yet are created, an empty list removes every tag of the figure
+    async fn replace_figure_tags(&self, transaction: Option<&mut T>, figure_id: IdType, tags: Vec<String>) -> Result<(), ServerError>;
+    // Tags on the most figures first
+    async fn find_popular(&self, transaction: Option<&mut T>, limit: i32) -> Result<Vec<TagCount>, ServerError>;
+}
+
+#[async_trait]
+pub trait LikeRepositoryTrait<T: TransactionTrait>: Send + Sync + Clone {
+    // Liking a figure twice or unliking a figure that wasn't liked changes nothing
+    async fn like(&self, transaction: Option<&mut T>, figure_id: IdType, profile_id: IdType) -> Result<(), ServerError>;
+    async fn unlike(&self, transaction: Option<&mut T>, figure_id: IdType, profile_id: IdType) -> Result<(), ServerError>;
+    // Returns which of the figures are liked by the profile
+    async fn find_liked_figure_ids(&self, transaction: Option<&mut T>, profile_id: IdType, figure_ids: Vec<IdType>) -> Result<Vec<IdType>, ServerError>;
+    // Removes every like of the profile, the like counts of the figures are lowered accordingly
+    async fn delete_by_profile_id(&self, transaction: Option<&mut T>, profile_id: IdType) -> Result<(), ServerError>;
+}
+
+#[async_trait]
+pub trait FollowRepositoryTrait<T: TransactionTrait>: Send + Sync + Clone {
+    // Following a profile twice or unfollowing a profile that wasn't followed changes nothing
+    async fn follow(&self, transaction: Option<&mut T>, follower_id: IdType, followed_id: IdType) -> Result<(), ServerError>;
+    // Whether the profile was followed, and so lost a follower
+    async fn unfollow(&self, transaction: Option<&mut T>, follower_id: IdType, followed_id: IdType) -> Result<bool, ServerError>;
+    // Both most recently followed first
+    async fn find_followers_starting_from_id(&self, transaction: Option<&mut T>, profile_id: IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError>;
+    async fn find_following_starting_from_id(&self, transaction: Option<&mut T>, profile_id: IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError>;
+    // Figures of the profiles the profile follows, newest first
+    async fn find_feed_starting_from_id(&self, transaction: Option<&mut T>, profile_id: IdType, figure_id: Option<IdType>, limit: i32) -> Result<Vec<FigureDTO>, ServerError>;
+    // Removes the follows from and of the profile, the counts of the other profiles are lowered accordingly
+    async fn delete_by_profile_id(&self, transaction: Option<&mut T>, profile_id: IdType) -> Result<(), ServerError>;
+}
+
+#[async_trait]
+pub trait ObjectDeletionRepositoryTrait<T: TransactionTrait>: Send + Sync + Clone {
+    // Queue removals of objects, they are first retried after the given number of seconds
+    async fn create(&self, transaction: Option<&mut T>, names: Vec<String>, first_retry_after: i64) -> Result<Vec<ObjectDeletion>, ServerError>;
+    // Deletions whose next attempt is due, longest waiting first
+    async fn find_due(&self, transaction: Option<&mut T>, limit: i32) -> Result<Vec<ObjectDeletion>, ServerError>;
+    // Counts a failed attempt and retries after the given number of seconds
+    async fn postpone(&self, transaction: Option<&mut T>, id: IdType, retry_after: i64) -> Result<(), ServerError>;
+    async fn delete_by_id(&self, transaction: Option<&mut T>, id: IdType) -> Result<(), ServerError>;
+}
+
+#[async_trait]
+pub trait CommentRepositoryTrait<T: TransactionTrait>: Send + Sync + Clone {
+    // Returns the id of the new comment
+    async fn create(&self, transaction: Option<&mut T>, figure_id: IdType, profile_id: IdType, parent_id: Option<IdType>, content: String) -> Result<IdType, ServerError>;
+    async fn find_by_id(&self, transaction: Option<&mut T>, comment_id: IdType) -> Result<CommentDTO, ServerError>;
+    // Comments on the figure that aren't replies, newest first
+    async fn find_starting_from_id_with_figure_id(&self, transaction: Option<&mut T>, figure_id: IdType, comment_id: Option<IdType>, limit: i32) -> Result<Vec<CommentDTO>, ServerError>;
+    // Replies to the comment, oldest first so a thread reads top to bottom
+    async fn find_replies_starting_from_id(&self, transaction: Option<&mut T>, parent_id: IdType, comment_id: Option<IdType>, limit: i32) -> Result<Vec<CommentDTO>, ServerError>;
+    async fn update_content(&self, transaction: Option<&mut T>, comment_id: IdType, content: String) -> Result<(), ServerError>;
+    // Replies to the comment are deleted with it
+    async fn delete_by_id(&self, transaction: Option<&mut T>, comment_id: IdType) -> Result<(), ServerError>;
+}
+
 #[async_trait]
 pub trait SessionRepositoryTrait: Send + Sync + Clone {
     async fn create(&self, session: Session) -> Result<Session, ServerError>;
-    async fn find_by_id(&self, session_id: &str, time_until_expiration: Option<usize>) -> Result<Session, ServerError>;
+    // Extends the session by the idle timeout of its policy in the same round trip
+    async fn find_and_extend(&self, session_id: &str, policies: SessionPolicies) -> Result<Session, ServerError>;
+    async fn find_all_by_user_id(&self, user_id: IdType) -> Result<Vec<SessionSummary>, ServerError>;
+    async fn update_expiration(&self, session_id: &str, time_until_expiration: usize) -> Result<(), ServerError>;
+    // Stores the last seen time of the session, without changing its expiration
+    async fn update_last_seen(&self, session: &Session) -> Result<(), ServerError>;
     async fn remove_by_id(&self, session_id: &str) -> Result<(), ServerError>;
+    async fn remove_by_public_id(&self, public_id: &str) -> Result<(), ServerError>;
+    async fn remove_all_by_user_id(&self, user_id: IdType) -> Result<(), ServerError>;
+}
+
+#[async_trait]
+pub trait OneTimeTokenRepositoryTrait: Send + Sync + Clone {
+    async fn create(&self, kind: OneTimeTokenKind, token_hash: &str, user_id: IdType, time_until_expiration: usize) -> Result<(), ServerError>;
+    // Returns the id of the user the token was created for without using it up
+    async fn find(&self, kind: OneTimeTokenKind, token_hash: &str) -> Result<IdType, ServerError>;
+    // Removes the token and returns the id of the user it was created for
+    async fn take(&self, kind: OneTimeTokenKind, token_hash: &str) -> Result<IdType, ServerError>;
+}
+
+#[async_trait]
+pub trait OidcSignInRepositoryTrait: Send + Sync + Clone {
+    async fn create(&self, state_hash: &str, sign_in: &OidcSignIn, time_until_expiration: usize) -> Result<(), ServerError>;
+    // Removes the sign in, errors with InvalidOidcSignIn if it doesn't exist or expired
+    async fn take(&self, state_hash: &str) -> Result<OidcSignIn, ServerError>;
+}
+
+// Counters that expire, keys are namespaced by the repository
+#[async_trait]
+pub trait RateLimitRepositoryTrait: Send + Sync + Clone {
+    // Increments the counter, a new counter expires after window seconds.
+    // Returns the count and the seconds until the counter expires.
+    async fn increment(&self, key: &str, window: usize) -> Result<(u64, usize), ServerError>;
+    // Seconds until the key expires, None if it doesn't exist
+    async fn time_to_live(&self, key: &str) -> Result<Option<usize>, ServerError>;
+    async fn set(&self, key: &str, time_until_expiration: usize) -> Result<(), ServerError>;
+    async fn remove(&self, key: &str) -> Result<(), ServerError>;
+}
+
+#[async_trait]
+pub trait AccessTokenRepositoryTrait<T: TransactionTrait>: Send + Sync + Clone {
+    async fn create(&self, transaction: Option<&mut T>, user_id: IdType, name: String, token_hash: String, scopes: Vec<Scope>) -> Result<AccessToken, ServerError>;
+    async fn find_by_token_hash(&self, transaction: Option<&mut T>, token_hash: &str) -> Result<AccessToken, ServerError>;
+    // Most recently created tokens first
+    async fn find_all_by_user_id(&self, transaction: Option<&mut T>, user_id: IdType) -> Result<Vec<AccessToken>, ServerError>;
+    async fn update_last_used_at(&self, transaction: Option<&mut T>, access_token_id: IdType) -> Result<(), ServerError>;
+    // Only deletes tokens of the given user, errors with ResourceNotFound otherwise
+    async fn delete(&self, transaction: Option<&mut T>, access_token_id: IdType, user_id: IdType) -> Result<(), ServerError>;
 }
\ No newline at end of file
@@ -1,10 +1,14 @@
 use std::sync::Arc;
 use async_trait::async_trait;
 use sqlx::{Pool, Postgres, Row};
-use crate::entities::user::{User, UserDef};
+use crate::entities::external_identity::ExternalIdentityDef;
+use crate::entities::profile::ProfileDef;
+use crate::entities::recovery_code::RecoveryCodeDef;
+use crate::entities::user::{Role, User, UserAndProfileFromQuery, UserDef};
 use crate::server_errors::ServerError;
 use interpol::format as iformat;
 use crate::entities::types::IdType;
+use crate::repositories::query_builder::{Column, Comparison, FilteredQuery, Order};
 use crate::repositories::traits::{TransactionTrait, UserRepositoryTrait};
 use crate::repositories::transaction::PostgresTransaction;
 
@@ -26,11 +30,12 @@ impl UserRepositoryTrait<PostgresTransaction> for UserRepository {
     async fn create(&self, transaction: Option<&mut PostgresTransaction>, email: String, password_hash: String) -> Result<User, ServerError> {
         let query_string = iformat!(r#"
             INSERT INTO {UserDef::Table} ({UserDef::Email.as_str()}, {UserDef::Password.as_str()}, {UserDef::Role.as_str()})
-            VALUES ($1, $2, 'user')
+            VALUES ($1, $2, $3)
             RETURNING {UserDef::Id.as_str()}"#);
         let query = sqlx::query(&query_string)
             .bind(email.to_lowercase())
-            .bind(&password_hash);
+            .bind(&password_hash)
+            .bind(Role::User.as_str());
         match transaction {
             Some(transaction) => query.fetch_one(transaction.inner()).await,
             None => query.fetch_one(&self.db).await
@@ -40,7 +45,12 @@ impl UserRepositoryTrait<PostgresTransaction> for UserRepository {
                  User {
                      email,
                      password: password_hash,
-                     role: "user".to_string(),
+                     role: Role::User,
+                     suspended: false,
+                     verified_at: None,
+                     totp_secret: None,
+                     totp_enabled_at: None,
+                     totp_last_used_step: None,
                      id: user_id,
                  })
             .map_err(|e| {
@@ -59,7 +69,7 @@ impl UserRepositoryTrait<PostgresTransaction> for UserRepository {
 
     async fn find_one_by_email(&self, transaction: Option<&mut PostgresTransaction>, email: String) -> Result<User, ServerError> {
         let query_string = iformat!(r#"
-        SELECT {UserDef::Id} AS {UserDef::Id.unique()}, {UserDef::Email}, {UserDef::Password}, {UserDef::Role}
+        SELECT {UserDef::Id} AS {UserDef::Id.unique()}, {UserDef::Email}, {UserDef::Password}, {UserDef::Role}, {UserDef::Suspended}, {UserDef::VerifiedAt}, {UserDef::TotpSecret}, {UserDef::TotpEnabledAt}, {UserDef::TotpLastUsedStep}
         FROM {UserDef::Table}
         WHERE {UserDef::Email.as_str()} = $1
         "#);
@@ -77,7 +87,7 @@ impl UserRepositoryTrait<PostgresTransaction> for UserRepository {
 
     async fn find_one_by_id(&self, transaction: Option<&mut PostgresTransaction>, id: IdType) -> Result<User, ServerError> {
         let query_string = iformat!(r#"
-        SELECT {UserDef::Id} AS {UserDef::Id.unique()}, {UserDef::Email}, {UserDef::Password}, {UserDef::Role}
+        SELECT {UserDef::Id} AS {UserDef::Id.unique()}, {UserDef::Email}, {UserDef::Password}, {UserDef::Role}, {UserDef::Suspended}, {UserDef::VerifiedAt}, {UserDef::TotpSecret}, {UserDef::TotpEnabledAt}, {UserDef::TotpLastUsedStep}
         FROM {UserDef::Table}
         WHERE {UserDef::Id.as_str()} = $1
         "#);
@@ -92,4 +102,300 @@ impl UserRepositoryTrait<PostgresTransaction> for UserRepository {
             ServerError::ResourceNotFound
         })
     }
+
+    async fn find_starting_from_id_with_search(&self, transaction: Option<&mut PostgresTransaction>, user_id: Option<IdType>, search: Option<String>, limit: i32) -> Result<Vec<UserAndProfileFromQuery>, ServerError> {
+        let query_string = iformat!(r#"
+            SELECT {UserDef::Id} AS {UserDef::Id.unique()}, {UserDef::Email}, {UserDef::Password}, {UserDef::Role}, {UserDef::Suspended}, {UserDef::VerifiedAt}, {UserDef::TotpSecret}, {UserDef::TotpEnabledAt}, {UserDef::TotpLastUsedStep},
+            {ProfileDef::Id} AS {ProfileDef::Id.unique()}, {ProfileDef::Username}, {ProfileDef::DisplayName}, {ProfileDef::Bio}, {ProfileDef::Banner}, {ProfileDef::ProfilePicture}, {ProfileDef::FollowerCount}, {ProfileDef::FollowingCount}
+            FROM {UserDef::Table}
+            INNER JOIN {ProfileDef::Table}
+            ON {ProfileDef::UserId} = {UserDef::Id}
+            "#);
+        let search_columns: [&dyn Column; 2] = [&UserDef::Email, &ProfileDef::Username];
+
+        let mut query_builder = FilteredQuery::new(query_string)
+            .filter_if_some(UserDef::Id, Comparison::LessThan, user_id)
+            .filter_any_if_some(&search_columns, Comparison::ILike, search.map(|search| format!("%{}%", escape_like_pattern(&search))))
+            .order_by(UserDef::Id, Order::Descending)
+            .limit(limit as i64);
+
+        let query = query_builder.build_query_as::<UserAndProfileFromQuery>();
+
+        match transaction {
+            Some(transaction) => query.fetch_all(transaction.inner()).await,
+            None => query.fetch_all(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn update_password(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType, password_hash: String) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            UPDATE {UserDef::Table}
+            SET {UserDef::Password.as_str()} = $2
+            WHERE {UserDef::Id} = $1
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(user_id)
+                .bind(password_hash);
+
+        let result = match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        match result.rows_affected() {
+            0 => Err(ServerError::ResourceNotFound),
+            _ => Ok(())
+        }
+    }
+
+    async fn update_verified_at(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType) -> Result<(), ServerError> {
+        // Verifying again keeps the original date
+        let query_string = iformat!(r#"
+            UPDATE {UserDef::Table}
+            SET {UserDef::VerifiedAt.as_str()} = COALESCE({UserDef::VerifiedAt}, now())
+            WHERE {UserDef::Id} = $1
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(user_id);
+
+        let result = match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        match result.rows_affected() {
+            0 => Err(ServerError::ResourceNotFound),
+            _ => Ok(())
+        }
+    }
+
+    async fn update_suspended(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType, suspended: bool) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            UPDATE {UserDef::Table}
+            SET {UserDef::Suspended.as_str()} = $2
+            WHERE {UserDef::Id} = $1
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(user_id)
+                .bind(suspended);
+
+        let result = match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        match result.rows_affected() {
+            0 => Err(ServerError::ResourceNotFound),
+            _ => Ok(())
+        }
+    }
+
+    async fn update_totp_secret(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType, secret: Option<String>) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            UPDATE {UserDef::Table}
+            SET {UserDef::TotpSecret.as_str()} = $2, {UserDef::TotpEnabledAt.as_str()} = NULL, {UserDef::TotpLastUsedStep.as_str()} = NULL
+            WHERE {UserDef::Id} = $1
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(user_id)
+                .bind(secret);
+
+        let result = match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        match result.rows_affected() {
+            0 => Err(ServerError::ResourceNotFound),
+            _ => Ok(())
+        }
+    }
+
+    async fn enable_totp(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType, step: i64) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            UPDATE {UserDef::Table}
+            SET {UserDef::TotpEnabledAt.as_str()} = now(), {UserDef::TotpLastUsedStep.as_str()} = $2
+            WHERE {UserDef::Id} = $1 AND {UserDef::TotpSecret} IS NOT NULL
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(user_id)
+                .bind(step);
+
+        let result = match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        match result.rows_affected() {
+            0 => Err(ServerError::ResourceNotFound),
+            _ => Ok(())
+        }
+    }
+
+    async fn update_totp_last_used_step(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType, step: i64) -> Result<(), ServerError> {
+        // Checked in the update so that two requests can't both use the same code
+        let query_string = iformat!(r#"
+            UPDATE {UserDef::Table}
+            SET {UserDef::TotpLastUsedStep.as_str()} = $2
+            WHERE {UserDef::Id} = $1 AND ({UserDef::TotpLastUsedStep} IS NULL OR {UserDef::TotpLastUsedStep} < $2)
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(user_id)
+                .bind(step);
+
+        let result = match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        match result.rows_affected() {
+            0 => Err(ServerError::ResourceNotFound),
+            _ => Ok(())
+        }
+    }
+
+    async fn replace_recovery_codes(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType, code_hashes: Vec<String>) -> Result<(), ServerError> {
+        // A single statement so that the user is never left without codes
+        let query_string = iformat!(r#"
+            WITH removed AS (
+                DELETE FROM {RecoveryCodeDef::Table}
+                WHERE {RecoveryCodeDef::UserId} = $1
+            )
+            INSERT INTO {RecoveryCodeDef::Table} ({RecoveryCodeDef::UserId.as_str()}, {RecoveryCodeDef::CodeHash.as_str()})
+            SELECT $1, unnest($2::text[])
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(user_id)
+                .bind(code_hashes);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn remove_recovery_code(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType, code_hash: &str) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            DELETE FROM {RecoveryCodeDef::Table}
+            WHERE {RecoveryCodeDef::UserId} = $1 AND {RecoveryCodeDef::CodeHash} = $2
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(user_id)
+                .bind(code_hash);
+
+        let result = match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        match result.rows_affected() {
+            0 => Err(ServerError::ResourceNotFound),
+            _ => Ok(())
+        }
+    }
+
+    async fn find_one_by_external_identity(&self, transaction: Option<&mut PostgresTransaction>, issuer: &str, subject: &str) -> Result<User, ServerError> {
+        let query_string = iformat!(r#"
+        SELECT {UserDef::Id} AS {UserDef::Id.unique()}, {UserDef::Email}, {UserDef::Password}, {UserDef::Role}, {UserDef::Suspended}, {UserDef::VerifiedAt}, {UserDef::TotpSecret}, {UserDef::TotpEnabledAt}, {UserDef::TotpLastUsedStep}
+        FROM {UserDef::Table}
+        INNER JOIN {ExternalIdentityDef::Table}
+        ON {ExternalIdentityDef::UserId} = {UserDef::Id}
+        WHERE {ExternalIdentityDef::Issuer} = $1 AND {ExternalIdentityDef::Subject} = $2
+        "#);
+        let query =
+            sqlx::query_as::<_, User>(&query_string)
+                .bind(issuer)
+                .bind(subject);
+        let query_result = match transaction {
+            Some(transaction) => query.fetch_one(transaction.inner()).await,
+            None => query.fetch_one(&self.db).await
+        };
+        match query_result {
+            Ok(user) => Ok(user),
+            Err(sqlx::Error::RowNotFound) => Err(ServerError::ResourceNotFound),
+            Err(e) => Err(ServerError::InternalError(Arc::new(e.into())))
+        }
+    }
+
+    async fn create_external_identity(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType, issuer: String, subject: String) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            INSERT INTO {ExternalIdentityDef::Table} ({ExternalIdentityDef::UserId.as_str()}, {ExternalIdentityDef::Issuer.as_str()}, {ExternalIdentityDef::Subject.as_str()})
+            VALUES ($1, $2, $3)
+            "#);
+
+        let query =
+            sqlx::query(&query_string)
+                .bind(user_id)
+                .bind(issuer)
+                .bind(subject);
+
+        match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }
+            .map(|_| ())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn has_external_identity(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType) -> Result<bool, ServerError> {
+        let query_string = iformat!(r#"
+            SELECT EXISTS (SELECT 1 FROM {ExternalIdentityDef::Table} WHERE {ExternalIdentityDef::UserId} = $1)
+            "#);
+
+        let query =
+            sqlx::query_scalar::<_, bool>(&query_string)
+                .bind(user_id);
+
+        match transaction {
+            Some(transaction) => query.fetch_one(transaction.inner()).await,
+            None => query.fetch_one(&self.db).await
+        }
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    async fn delete_by_id(&self, transaction: Option<&mut PostgresTransaction>, user_id: IdType) -> Result<(), ServerError> {
+        let query_string = iformat!(r#"
+            DELETE FROM {UserDef::Table}
+            WHERE {UserDef::Id} = $1
+            "#);
+        let query =
+            sqlx::query(&query_string)
+                .bind(user_id);
+
+        let result = match transaction {
+            Some(transaction) => query.execute(transaction.inner()).await,
+            None => query.execute(&self.db).await
+        }.map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        match result.rows_affected() {
+            0 => Err(ServerError::ResourceNotFound),
+            _ => Ok(())
+        }
+    }
+}
+
+// Escapes the wildcards of a LIKE pattern so that user input is matched literally
+fn escape_like_pattern(pattern: &str) -> String {
+    pattern
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
\ No newline at end of file
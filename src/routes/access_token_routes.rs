@@ -0,0 +1,62 @@
+use std::sync::Arc;
+use axum::{Extension, Json};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::access_token::Scope;
+use crate::entities::dtos::access_token_dtos::{AccessTokenDTO, CreatedAccessTokenDTO};
+use crate::entities::dtos::session_dtos::SessionOption;
+use crate::entities::types::IdType;
+use crate::ServerState;
+use crate::services::traits::AccessTokenServiceTrait;
+
+#[derive(Deserialize)]
+pub struct CreateAccessTokenForm {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+}
+
+pub async fn get_access_tokens<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().access_token_service().find_access_tokens(session.get_user_id()).await {
+        Ok(access_tokens) => Json(access_tokens.into_iter()
+            .map(AccessTokenDTO::from)
+            .collect::<Vec<AccessTokenDTO>>())
+            .into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+// The token is only part of this response, the user has to copy it right away
+pub async fn create_access_token<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Json(form): Json<CreateAccessTokenForm>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().access_token_service().create_access_token(session.get_user_id(), form.name, form.scopes).await {
+        Ok((token, access_token)) => Json(CreatedAccessTokenDTO {
+            token,
+            access_token: AccessTokenDTO::from(access_token),
+        }).into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn revoke_access_token<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().access_token_service().revoke_access_token(session.get_user_id(), id).await {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => e.into_response()
+    }
+}
\ No newline at end of file
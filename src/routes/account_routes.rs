@@ -0,0 +1,72 @@
+use std::sync::Arc;
+use axum::{Extension, Json};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use cookie::{Cookie, SameSite};
+use serde::Deserialize;
+use tower_cookies::Cookies;
+use tracing::error;
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::dtos::session_dtos::SessionOption;
+use crate::entities::dtos::user_dto::DataExportDownloadDTO;
+use crate::ServerState;
+use crate::services::traits::{AccountServiceTrait, DataExportServiceTrait};
+
+#[derive(Deserialize)]
+pub struct DeleteAccountForm {
+    // Can be left out by users with a linked identity who signed in moments ago
+    pub password: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct DownloadDataExportForm {
+    pub token: String,
+}
+
+// Deletion can't be undone, so the password is asked for again even though the browser is signed in,
+// users without a password sign in with their identity provider again instead
+pub async fn delete_account<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies, Json(form): Json<DeleteAccountForm>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().account_service().delete_account(session.get_user_id(), form.password, session.get_signed_in_at()).await {
+        Ok(_) => {
+            let mut cookie = Cookie::new("session_id", "");
+            cookie.set_http_only(true);
+            cookie.set_secure(true);
+            cookie.set_same_site(SameSite::Lax);
+            cookie.set_domain(server_state.domain.to_string());
+            cookie.set_path("/");
+            cookie.make_removal();
+            cookies.add(cookie);
+            StatusCode::OK.into_response()
+        }
+        Err(e) => e.into_response()
+    }
+}
+
+// The archive is built in the background, a link to download it is mailed once it is ready
+pub async fn request_data_export<C: ContextTrait + 'static>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    let user_id = session.get_user_id();
+    tokio::spawn(async move {
+        if let Err(e) = server_state.context.service_context().data_export_service().export_data(user_id).await {
+            error!("Failed to export the data of user (id: {}): {}", user_id, e);
+        }
+    });
+    StatusCode::ACCEPTED.into_response()
+}
+
+pub async fn download_data_export<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Json(form): Json<DownloadDataExportForm>) -> Response {
+    match server_state.context.service_context().data_export_service().take_data_export(form.token).await {
+        Ok(url) => Json(DataExportDownloadDTO { url }).into_response(),
+        Err(e) => e.into_response()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+use axum::Extension;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+use crate::auth_layer::{Admin, RequireRole};
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::dtos::session_dtos::SessionOption;
+use crate::entities::types::IdType;
+use crate::routes::figure_routes::{get_figures_page, page_response, PageQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::ServerState;
+use crate::services::traits::{FigureServiceTrait, UserServiceTrait};
+use crate::utilities::cursor::CursorKind;
+
+// Used when no limit is given
+const USERS_PAGE_SIZE: u32 = 20;
+
+#[derive(Deserialize)]
+pub struct UserSearchQuery {
+    pub search: Option<String>,
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+}
+
+pub async fn admin_browse_users<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Query(query): Query<UserSearchQuery>) -> Response {
+    let starting_from_user_id = match query.cursor.map(|cursor| server_state.cursor_signer.decode(CursorKind::Users, &cursor)).transpose() {
+        Ok(user_id) => user_id,
+        Err(e) => return e.into_response()
+    };
+    let limit = query.limit.unwrap_or(USERS_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let search = query.search.filter(|search| !search.is_empty());
+
+    // Fetch one user more than requested to find out if there is a next page
+    match server_state.context.service_context().user_service().find_users_starting_from_id(starting_from_user_id, search, limit as i32 + 1).await {
+        Ok(users) => page_response("users", users, limit, CursorKind::Users, |user| user.user.id, &server_state.cursor_signer),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn admin_get_user<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Path(user_id): Path<IdType>) -> Response {
+    match server_state.context.service_context().user_service().find_user_by_id(user_id).await {
+        Ok(user) => json!(user).to_string().into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn admin_browse_user_figures<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Path(user_id): Path<IdType>, Query(page): Query<PageQuery>) -> Response {
+    let user = match server_state.context.service_context().user_service().find_user_by_id(user_id).await {
+        Ok(user) => user,
+        Err(e) => return e.into_response()
+    };
+    get_figures_page(&server_state, &session, page, DEFAULT_PAGE_SIZE, Some(user.profile.id)).await
+}
+
+pub async fn admin_suspend_user<C: ContextTrait>(admin: RequireRole<Admin>, State(server_state): State<Arc<ServerState<C>>>, Path(user_id): Path<IdType>) -> Response {
+    set_user_suspended(admin, &server_state, user_id, true).await
+}
+
+pub async fn admin_unsuspend_user<C: ContextTrait>(admin: RequireRole<Admin>, State(server_state): State<Arc<ServerState<C>>>, Path(user_id): Path<IdType>) -> Response {
+    set_user_suspended(admin, &server_state, user_id, false).await
+}
+
+async fn set_user_suspended<C: ContextTrait>(admin: RequireRole<Admin>, server_state: &ServerState<C>, user_id: IdType, suspended: bool) -> Response {
+    match server_state.context.service_context().user_service().set_user_suspended(user_id, suspended).await {
+        Ok(_) => {
+            info!("User (id: {}) suspended: {}, by admin (user id: {})", user_id, suspended, admin.session.get_user_id());
+            StatusCode::OK.into_response()
+        }
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn admin_invalidate_user_sessions<C: ContextTrait>(admin: RequireRole<Admin>, State(server_state): State<Arc<ServerState<C>>>, Path(user_id): Path<IdType>) -> Response {
+    match server_state.context.service_context().user_service().invalidate_user_sessions(user_id).await {
+        Ok(_) => {
+            info!("Sessions of user (id: {}) invalidated by admin (user id: {})", user_id, admin.session.get_user_id());
+            StatusCode::OK.into_response()
+        }
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn admin_delete_figure<C: ContextTrait>(admin: RequireRole<Admin>, State(server_state): State<Arc<ServerState<C>>>, Path(figure_id): Path<IdType>) -> Response {
+    match server_state.context.service_context().figure_service().delete_figure_as_moderator(figure_id).await {
+        Ok(_) => {
+            info!("Figure (id: {}) deleted by admin (user id: {})", figure_id, admin.session.get_user_id());
+            StatusCode::OK.into_response()
+        }
+        Err(e) => e.into_response()
+    }
+}
\ No newline at end of file
@@ -3,18 +3,25 @@ use axum::{Extension, Json};
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
+use chrono::Utc;
 use cookie::{Cookie, SameSite};
+use cookie::time::Duration;
 use serde::Serialize;
 use serde::Deserialize;
+use serde_json::json;
 use tower_cookies::Cookies;
+use tracing::error;
 use crate::context::{ContextTrait, RepositoryContextTrait, ServiceContextTrait};
 use crate::ServerState;
 use crate::entities::dtos::profile_dto::ProfileDTO;
-use crate::entities::dtos::session_dtos::{Session, SessionOption};
+use crate::entities::dtos::session_dtos::{Authentication, ClientInfo, Session, SessionOption};
+use crate::entities::rate_limit::RateLimit;
 use crate::entities::types::IdType;
 use crate::repositories::traits::SessionRepositoryTrait;
 use crate::server_errors::ServerError;
-use crate::services::traits::{ProfileServiceTrait, UserServiceTrait};
+use crate::services::traits::{ProfileServiceTrait, RateLimitServiceTrait, UserServiceTrait};
+
+const SIGN_IN_EMAIL_RATE_LIMIT: RateLimit = RateLimit::new(10, 900);
 
 #[derive(Deserialize)]
 pub struct SignUpForm {
@@ -27,6 +34,40 @@ pub struct SignUpForm {
 pub struct SignInForm {
     pub email: String,
     pub password: String,
+    // Keeps the user signed in after closing the browser, using the longer remember me session policy
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+#[derive(Deserialize)]
+pub struct TwoFactorSignInForm {
+    pub token: String,
+    // TOTP code or one of the recovery codes
+    pub code: String,
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ChangePasswordForm {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Deserialize)]
+pub struct PasswordResetRequestForm {
+    pub email: String,
+}
+
+#[derive(Deserialize)]
+pub struct PasswordResetForm {
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Deserialize)]
+pub struct VerifyEmailForm {
+    pub token: String,
 }
 
 #[derive(Serialize)]
@@ -42,45 +83,123 @@ impl From<Session> for SignInResponse {
     }
 }
 
-pub async fn signin_user<C: ContextTrait>(Extension(_session_option): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies, Json(signin): Json<SignInForm>) -> Response {
-    return match server_state.context.service_context().user_service().authenticate_user(signin.email, signin.password).await {
-        Ok((profile, session)) => {
-            let mut cookie = Cookie::new("session_id", session.get_id());
-            cookie.set_http_only(true);
-            cookie.set_secure(true);
-            cookie.set_same_site(SameSite::Strict);
-            cookie.set_domain(server_state.domain.to_string());
-            cookie.set_path("/");
-            cookies.add(cookie);
+pub async fn signin_user<C: ContextTrait>(Extension(_session_option): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies, client: ClientInfo, Json(signin): Json<SignInForm>) -> Response {
+    // Guesses are limited per account as well, attackers can spread them over many ips
+    let rate_limit_service = server_state.context.service_context().rate_limit_service();
+    let email = signin.email.to_lowercase();
+    let ip = client.ip.clone().unwrap_or_default();
+    if let Err(e) = rate_limit_service.hit("signin-email", &email, SIGN_IN_EMAIL_RATE_LIMIT).await {
+        return e.into_response();
+    }
+    if let Err(e) = rate_limit_service.check_sign_in_lockout(&email, &ip).await {
+        return e.into_response();
+    }
+
+    return match server_state.context.service_context().user_service().authenticate_user(signin.email, signin.password, client, signin.remember_me).await {
+        Ok(Authentication::SignedIn(profile, session)) => {
+            if let Err(e) = rate_limit_service.reset_failed_sign_ins(&email, &ip).await {
+                error!("Failed to reset failed sign ins: {}", e);
+            }
+            cookies.add(create_session_cookie(&server_state.domain, &session));
             profile.to_json().into_response()
         }
+        // No session until the second factor is checked, failed sign ins are reset once it is
+        Ok(Authentication::TwoFactorRequired(token)) => {
+            Json(json!({ "two_factor_required": true, "two_factor_token": token })).into_response()
+        }
+        Err(ServerError::WrongPassword) => {
+            if let Err(e) = rate_limit_service.record_failed_sign_in(&email, &ip).await {
+                return e.into_response();
+            }
+            ServerError::WrongPassword.into_response()
+        }
         Err(e) => e.into_response()
     };
 }
 
-pub async fn signup_user<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies, Json(signup): Json<SignUpForm>) -> Response {
-    return match server_state.context.service_context().user_service().signup_user(signup.email, signup.password, signup.username).await {
+// Second step of signing in for users with two factor authentication
+pub async fn complete_two_factor_sign_in<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies, client: ClientInfo, Json(form): Json<TwoFactorSignInForm>) -> Response {
+    let ip = client.ip.clone().unwrap_or_default();
+    match server_state.context.service_context().user_service().complete_two_factor_sign_in(form.token, form.code, client, form.remember_me).await {
+        Ok((profile, session, email)) => {
+            if let Err(e) = server_state.context.service_context().rate_limit_service().reset_failed_sign_ins(&email, &ip).await {
+                error!("Failed to reset failed sign ins: {}", e);
+            }
+            cookies.add(create_session_cookie(&server_state.domain, &session));
+            profile.to_json().into_response()
+        }
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn signup_user<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies, client: ClientInfo, Json(signup): Json<SignUpForm>) -> Response {
+    return match server_state.context.service_context().user_service().signup_user(signup.email, signup.password, signup.username, client).await {
         Ok((profile, session)) => {
-            let mut cookie = Cookie::new("session_id", session.get_id());
-            cookie.set_http_only(true);
-            cookie.set_secure(true);
-            cookie.set_same_site(SameSite::Strict);
-            cookie.set_domain(server_state.domain.to_string());
-            cookie.set_path("/");
-            cookies.add(cookie);
+            cookies.add(create_session_cookie(&server_state.domain, &session));
             profile.to_json().into_response()
         }
         Err(e) => e.into_response()
     };
 }
 
+pub async fn change_password<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies, client: ClientInfo, Json(form): Json<ChangePasswordForm>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().user_service().change_password(session.get_user_id(), form.current_password, form.new_password, client, session.is_persistent()).await {
+        Ok(new_session) => {
+            cookies.add(create_session_cookie(&server_state.domain, &new_session));
+            StatusCode::OK.into_response()
+        }
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn request_password_reset<C: ContextTrait + 'static>(State(server_state): State<Arc<ServerState<C>>>, Json(form): Json<PasswordResetRequestForm>) -> Response {
+    // Handled in the background, so neither the response nor the time it takes tells if the email is in use
+    tokio::spawn(async move {
+        if let Err(e) = server_state.context.service_context().user_service().request_password_reset(form.email).await {
+            error!("Failed to handle password reset request: {}", e);
+        }
+    });
+    StatusCode::OK.into_response()
+}
+
+pub async fn reset_password<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Json(form): Json<PasswordResetForm>) -> Response {
+    match server_state.context.service_context().user_service().reset_password(form.token, form.new_password).await {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn send_email_verification<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().user_service().send_email_verification(session.get_user_id()).await {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn verify_email<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Json(form): Json<VerifyEmailForm>) -> Response {
+    match server_state.context.service_context().user_service().verify_email(form.token).await {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
 pub async fn signout_user<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies) -> Response {
     if let Some(mut cookie) = cookies.get("session_id") {
         match server_state.context.repository_context().session_repository().remove_by_id(cookie.value()).await {
             Ok(_) => {
                 cookie.set_http_only(true);
                 cookie.set_secure(true);
-                cookie.set_same_site(SameSite::Strict);
+                cookie.set_same_site(SameSite::Lax);
                 cookie.set_domain(server_state.domain.to_string());
                 cookie.set_path("/");
                 cookie.make_removal();
@@ -94,10 +213,23 @@ pub async fn signout_user<C: ContextTrait>(State(server_state): State<Arc<Server
     }
 }
 
+// CSRF token of the session, has to be sent along with every request that changes state
+pub async fn get_csrf_token<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.csrf_tokens.create(&session.get_id()) {
+        Ok(token) => Json(json!({ "csrf_token": token })).into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
 // Return the profile associated with a given session
 pub async fn load_session<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies) -> Response {
     if let Some(cookie) = cookies.get("session_id") {
-        match server_state.context.repository_context().session_repository().find_by_id(cookie.value(), Some(86400)).await {
+        match server_state.context.service_context().user_service().find_session(cookie.value().to_string()).await {
             Ok(session_data) => {
                 server_state.context.service_context().profile_service().find_profile_by_id(session_data.get_profile_id())
                     .await
@@ -109,4 +241,19 @@ pub async fn load_session<C: ContextTrait>(State(server_state): State<Arc<Server
     } else {
         ServerError::NoSessionReceived.into_response()
     }
+}
+
+pub fn create_session_cookie(domain: &str, session: &Session) -> Cookie<'static> {
+    let mut cookie = Cookie::new("session_id", session.get_id());
+    cookie.set_http_only(true);
+    cookie.set_secure(true);
+    // Lax so that the session is kept on navigations from other sites, requests changing state are protected by CSRF tokens
+    cookie.set_same_site(SameSite::Lax);
+    cookie.set_domain(domain.to_string());
+    cookie.set_path("/");
+    // Other sessions get a cookie that is removed when the browser closes
+    if let (true, Some(expires_at)) = (session.is_persistent(), session.get_expires_at()) {
+        cookie.set_max_age(Duration::seconds((expires_at - Utc::now()).num_seconds()));
+    }
+    cookie
 }
\ No newline at end of file
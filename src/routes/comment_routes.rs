@@ -0,0 +1,76 @@
+use std::sync::Arc;
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use crate::auth_layer::VerifiedSession;
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::types::IdType;
+use crate::routes::figure_routes::{page_response, PageQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::ServerState;
+use crate::services::traits::CommentServiceTrait;
+use crate::utilities::cursor::CursorKind;
+
+#[derive(Deserialize)]
+pub struct CreateCommentForm {
+    pub content: String,
+    // Set when replying to a comment
+    pub parent_id: Option<IdType>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateCommentForm {
+    pub content: String,
+}
+
+// Comments on the figure that aren't replies, newest first
+pub async fn browse_comments<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Path(figure_id): Path<IdType>, Query(page): Query<PageQuery>) -> Response {
+    let starting_from_comment_id = match page.cursor.map(|cursor| server_state.cursor_signer.decode(CursorKind::Comments, &cursor)).transpose() {
+        Ok(comment_id) => comment_id,
+        Err(e) => return e.into_response()
+    };
+    let limit = page.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    // Fetch one comment more than requested to find out if there is a next page
+    match server_state.context.service_context().comment_service().find_comments(figure_id, starting_from_comment_id, limit as i32 + 1).await {
+        Ok(comments) => page_response("comments", comments, limit, CursorKind::Comments, |comment| comment.id, &server_state.cursor_signer),
+        Err(e) => e.into_response()
+    }
+}
+
+// Replies to a comment, oldest first
+pub async fn browse_replies<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Path(comment_id): Path<IdType>, Query(page): Query<PageQuery>) -> Response {
+    let starting_from_comment_id = match page.cursor.map(|cursor| server_state.cursor_signer.decode(CursorKind::Comments, &cursor)).transpose() {
+        Ok(comment_id) => comment_id,
+        Err(e) => return e.into_response()
+    };
+    let limit = page.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    // Fetch one reply more than requested to find out if there is a next page
+    match server_state.context.service_context().comment_service().find_replies(comment_id, starting_from_comment_id, limit as i32 + 1).await {
+        Ok(replies) => page_response("comments", replies, limit, CursorKind::Comments, |reply| reply.id, &server_state.cursor_signer),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn create_comment<C: ContextTrait>(VerifiedSession { session }: VerifiedSession, State(server_state): State<Arc<ServerState<C>>>, Path(figure_id): Path<IdType>, Json(form): Json<CreateCommentForm>) -> Response {
+    match server_state.context.service_context().comment_service().create_comment(figure_id, session.get_profile_id(), form.parent_id, form.content).await {
+        Ok(comment) => comment.to_json_string().into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn update_comment<C: ContextTrait>(VerifiedSession { session }: VerifiedSession, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>, Json(form): Json<UpdateCommentForm>) -> Response {
+    match server_state.context.service_context().comment_service().update_comment(id, session.get_profile_id(), form.content).await {
+        Ok(comment) => comment.to_json_string().into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn delete_comment<C: ContextTrait>(VerifiedSession { session }: VerifiedSession, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
+    match server_state.context.service_context().comment_service().delete_comment(id, session.get_profile_id()).await {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => e.into_response()
+    }
+}
\ No newline at end of file
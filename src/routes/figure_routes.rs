@@ -2,67 +2,113 @@ use std::io::Cursor;
 use std::sync::Arc;
 use anyhow::Context;
 use axum::Extension;
-use axum::extract::{Multipart, Path, State};
+use axum::extract::{Multipart, Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use bytes::Bytes;
 use image::GenericImageView;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use crate::auth_layer::VerifiedSession;
 use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::dtos::figure_dto::FigureDTO;
 use crate::entities::dtos::session_dtos::SessionOption;
 use crate::entities::types::IdType;
 use crate::server_errors::ServerError;
 use crate::ServerState;
-use crate::services::traits::FigureServiceTrait;
+use crate::services::traits::{FigureServiceTrait, LikeServiceTrait};
+use crate::utilities::cursor::{CursorKind, CursorSigner};
 
-pub async fn get_figure<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
-    let figure = server_state.context.service_context().figure_service().find_figure_by_id(id).await;
-    match figure {
-        Ok(figure) => figure.to_json_string().into_response(),
+pub async fn get_figure<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
+    let mut figure = match server_state.context.service_context().figure_service().find_figure_by_id(id).await {
+        Ok(figure) => figure,
+        Err(e) => return e.into_response()
+    };
+    match server_state.context.service_context().like_service().mark_liked_figures(session.profile_id(), std::slice::from_mut(&mut figure)).await {
+        Ok(_) => figure.to_json_string().into_response(),
         Err(e) => e.into_response()
     }
 }
 
-pub async fn browse_figures<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>) -> Response {
-    get_figures_with_parameters(State(server_state), None, None).await
+// Used when no limit is given
+pub const DEFAULT_PAGE_SIZE: u32 = 3;
+const LANDING_PAGE_SIZE: u32 = 9;
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+#[derive(Deserialize)]
+pub struct PageQuery {
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+}
+
+pub async fn browse_figures<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Query(page): Query<PageQuery>) -> Response {
+    get_figures_page(&server_state, &session, page, DEFAULT_PAGE_SIZE, None).await
+}
+
+// Deprecated, use browse_figures with a cursor instead
+pub async fn browse_figures_starting_from_figure_id<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Path(starting_from_figure_id): Path<IdType>) -> Response {
+    get_figures_with_parameters(&server_state, &session, Some(starting_from_figure_id), None, DEFAULT_PAGE_SIZE).await
+}
+
+pub async fn browse_figures_from_profile<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Path(profile_id): Path<IdType>, Query(page): Query<PageQuery>) -> Response {
+    get_figures_page(&server_state, &session, page, DEFAULT_PAGE_SIZE, Some(profile_id)).await
 }
 
-pub async fn browse_figures_starting_from_figure_id<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Path(starting_from_figure_id): Path<IdType>) -> Response {
-    get_figures_with_parameters(State(server_state), Some(starting_from_figure_id), None).await
+// Deprecated, use browse_figures_from_profile with a cursor instead
+pub async fn browse_figures_from_profile_starting_from_figure_id<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Path((profile_id, starting_from_figure_id)): Path<(IdType, IdType)>) -> Response {
+    get_figures_with_parameters(&server_state, &session, Some(starting_from_figure_id), Some(profile_id), DEFAULT_PAGE_SIZE).await
 }
 
-pub async fn browse_figures_from_profile<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Path(profile_id): Path<IdType>) -> Response {
-    get_figures_with_parameters(State(server_state), None, Some(profile_id)).await
+pub async fn landing_page_figures<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Query(page): Query<PageQuery>) -> Response {
+    get_figures_page(&server_state, &session, page, LANDING_PAGE_SIZE, None).await
 }
 
-pub async fn browse_figures_from_profile_starting_from_figure_id<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Path((profile_id, starting_from_figure_id)): Path<(IdType, IdType)>) -> Response {
-    get_figures_with_parameters(State(server_state), Some(starting_from_figure_id), Some(profile_id)).await
+pub async fn get_figures_page<C: ContextTrait>(server_state: &ServerState<C>, session: &SessionOption, page: PageQuery, default_limit: u32, profile_id: Option<IdType>) -> Response {
+    let starting_from_figure_id = match page.cursor.map(|cursor| server_state.cursor_signer.decode(CursorKind::Figures, &cursor)).transpose() {
+        Ok(figure_id) => figure_id,
+        Err(e) => return e.into_response()
+    };
+    let limit = page.limit.unwrap_or(default_limit).clamp(1, MAX_PAGE_SIZE);
+    get_figures_with_parameters(server_state, session, starting_from_figure_id, profile_id, limit).await
 }
 
-async fn get_figures_with_parameters<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, starting_from_figure_id: Option<IdType>, profile_id: Option<IdType>) -> Response {
-    let figures = server_state.context.service_context().figure_service().find_figures_starting_from_id_with_profile_id(starting_from_figure_id, profile_id, 3).await;
+async fn get_figures_with_parameters<C: ContextTrait>(server_state: &ServerState<C>, session: &SessionOption, starting_from_figure_id: Option<IdType>, profile_id: Option<IdType>, limit: u32) -> Response {
+    // Fetch one figure more than requested to find out if there is a next page
+    let figures = server_state.context.service_context().figure_service().find_figures_starting_from_id_with_profile_id(starting_from_figure_id, profile_id, limit as i32 + 1).await;
     match figures {
-        Ok(figures) => {
-            json!({
-                "figures": figures
-            }).to_string().into_response()
-        }
+        Ok(figures) => figures_page_response(server_state, session, figures, limit).await,
         Err(e) => e.into_response()
     }
 }
 
-pub async fn landing_page_figures<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>) -> Response {
-    let figures = server_state.context.service_context().figure_service().find_figures_starting_from_id_with_profile_id(None, None, 9).await;
-    match figures {
-        Ok(figures) => {
-            json!({
-                "figures": figures
-            }).to_string().into_response()
-        }
+// Page of figures with liked_by_me set for the profile of the request
+pub async fn figures_page_response<C: ContextTrait>(server_state: &ServerState<C>, session: &SessionOption, mut figures: Vec<FigureDTO>, limit: u32) -> Response {
+    match server_state.context.service_context().like_service().mark_liked_figures(session.profile_id(), &mut figures).await {
+        Ok(_) => page_response("figures", figures, limit, CursorKind::Figures, |figure| figure.id, &server_state.cursor_signer),
         Err(e) => e.into_response()
     }
 }
 
+// Response for a page of a keyset paginated listing, `items` should hold one item more than `limit` if there is a next page
+pub fn page_response<T: Serialize>(name: &str, mut items: Vec<T>, limit: u32, kind: CursorKind, get_id: impl Fn(&T) -> IdType, cursor_signer: &CursorSigner) -> Response {
+    let has_more = items.len() > limit as usize;
+    items.truncate(limit as usize);
+
+    let next_cursor = match items.last() {
+        Some(last) if has_more => match cursor_signer.encode(kind, get_id(last)) {
+            Ok(cursor) => Some(cursor),
+            Err(e) => return e.into_response()
+        },
+        _ => None
+    };
+
+    json!({
+        name: items,
+        "next_cursor": next_cursor,
+        "has_more": has_more
+    }).to_string().into_response()
+}
+
 pub async fn get_total_figures_count<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>) -> Response {
     match server_state.context.service_context().figure_service().get_total_figures_count().await {
         Ok(id) => id.to_string().into_response(),
@@ -70,21 +116,16 @@ pub async fn get_total_figures_count<C: ContextTrait>(State(server_state): State
     }
 }
 
-pub async fn upload_figure<C: ContextTrait>(session: Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, multipart: Multipart) -> Response {
-    let session = match &session.session_opt {
-        Some(s) => s,
-        None => return StatusCode::UNAUTHORIZED.into_response()
-    };
-
+pub async fn upload_figure<C: ContextTrait>(VerifiedSession { session }: VerifiedSession, State(server_state): State<Arc<ServerState<C>>>, multipart: Multipart) -> Response {
     let result = parse_multipart(multipart).await;
-    let (title, description, image, width, height) = match result {
+    let (title, description, tags, image) = match result {
         Ok(tuple) => tuple,
         Err(_e) => {
             return ServerError::InvalidMultipart.into_response();
         }
     };
 
-    match server_state.context.service_context().figure_service().create(title, description, image, width, height, session.get_profile_id()).await {
+    match server_state.context.service_context().figure_service().create(title, description, tags, image, session.get_profile_id()).await {
         Ok(figure) => {
             json!({
                 "figure_id": figure.id
@@ -94,6 +135,27 @@ pub async fn upload_figure<C: ContextTrait>(session: Extension<SessionOption>, S
     }
 }
 
+pub async fn update_figure<C: ContextTrait>(VerifiedSession { session }: VerifiedSession, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>, multipart: Multipart) -> Response {
+    let (title, description, tags, image) = match parse_update_figure_multipart(multipart).await {
+        Ok(tuple) => tuple,
+        Err(_e) => {
+            return ServerError::InvalidMultipart.into_response();
+        }
+    };
+
+    match server_state.context.service_context().figure_service().update_figure(id, session.get_profile_id(), title, description, tags, image).await {
+        Ok(figure) => figure.to_json_string().into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn delete_figure<C: ContextTrait>(VerifiedSession { session }: VerifiedSession, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
+    match server_state.context.service_context().figure_service().delete_figure(id, session.get_profile_id()).await {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
 pub async fn get_total_figures_by_profile<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
     match server_state.context.service_context().figure_service().get_total_figures_by_profile(id).await {
         Ok(total) => total.to_string().into_response(),
@@ -101,9 +163,10 @@ pub async fn get_total_figures_by_profile<C: ContextTrait>(State(server_state):
     }
 }
 
-async fn parse_multipart(mut multipart: Multipart) -> Result<(String, Option<String>, Bytes, u32, u32), anyhow::Error> {
+async fn parse_multipart(mut multipart: Multipart) -> Result<(String, Option<String>, Vec<String>, (Bytes, u32, u32)), anyhow::Error> {
     let mut title: Option<String> = None;
     let mut description: Option<String> = None;
+    let mut tags: Vec<String> = Vec::new();
     let mut image: Option<Bytes> = None;
 
     while let Ok(Some(field)) = multipart.next_field().await {
@@ -112,6 +175,7 @@ async fn parse_multipart(mut multipart: Multipart) -> Result<(String, Option<Str
         match name.as_str() {
             "title" => title = Some(String::from_utf8(data.to_vec())?),
             "description" => description = Some(String::from_utf8(data.to_vec())?),
+            "tags" => tags.extend(split_tags(&String::from_utf8(data.to_vec())?)),
             "file" => image = Some(data),
             _ => {}
         };
@@ -123,24 +187,68 @@ async fn parse_multipart(mut multipart: Multipart) -> Result<(String, Option<Str
     let title = title.unwrap();
     let image = image.unwrap();
 
-    let format = parse_image_format(&image)?.to_vec();
+    let image = convert_image(&image)?;
+
+    Ok((title, description, tags, image))
+}
+
+// Every field is optional, a missing (or empty) field leaves the figure unchanged.
+// Except for tags, an empty tags field removes the tags of the figure.
+async fn parse_update_figure_multipart(mut multipart: Multipart) -> Result<(Option<String>, Option<String>, Option<Vec<String>>, Option<(Bytes, u32, u32)>), anyhow::Error> {
+    let mut title: Option<String> = None;
+    let mut description: Option<String> = None;
+    let mut tags: Option<Vec<String>> = None;
+    let mut image: Option<Bytes> = None;
+
+    while let Ok(Some(field)) = multipart.next_field().await {
+        let name = field.name().context("Multipart parse failed: no field name")?.to_string();
+        let data = field.bytes().await?;
+        match name.as_str() {
+            "title" => title = Some(String::from_utf8(data.to_vec())?),
+            "description" => description = Some(String::from_utf8(data.to_vec())?),
+            "tags" => tags.get_or_insert_with(Vec::new).extend(split_tags(&String::from_utf8(data.to_vec())?)),
+            "file" => image = Some(data),
+            _ => {}
+        };
+    };
+
+    let image = match image {
+        Some(image) if !image.is_empty() => Some(convert_image(&image)?),
+        _ => None
+    };
+
+    Ok((title.filter(|title| !title.is_empty()), description, tags, image))
+}
+
+// Tags can be given as a comma separated list, in one or more fields
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// Validate the image format and convert it to JPEG, returns the converted image with its dimensions
+fn convert_image(image: &Bytes) -> Result<(Bytes, u32, u32), anyhow::Error> {
+    let format = parse_image_format(image)?.to_vec();
     if !format.contains(&"jpg") && !format.contains(&"jpeg") && !format.contains(&"png") {
-        return Err(ServerError::InvalidImage)?;
+        return Err(ServerError::InvalidImage.into());
     }
 
-    let (width, height) = match get_image_dimensions(&image) {
+    let (width, height) = match get_image_dimensions(image) {
         Ok(tuple) => tuple,
         Err(_e) => {
-            return Err(ServerError::InvalidImage)?;
+            return Err(ServerError::InvalidImage.into());
         }
     };
 
     // Convert to JPEG
     let mut buffer = vec![];
-    let parsed_image = image::load_from_memory(&image)?;
+    let parsed_image = image::load_from_memory(image)?;
     parsed_image.write_to(&mut Cursor::new(&mut buffer), image::ImageOutputFormat::Jpeg(90))?;
 
-    Ok((title, description, Bytes::from(buffer.to_vec()), width, height))
+    Ok((Bytes::from(buffer.to_vec()), width, height))
 }
 
 fn get_image_dimensions(image: &Bytes) -> Result<(u32, u32), anyhow::Error> {
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+use axum::Extension;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde_json::json;
+use crate::auth_layer::VerifiedSession;
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::dtos::session_dtos::SessionOption;
+use crate::entities::types::IdType;
+use crate::routes::figure_routes::{figures_page_response, page_response, PageQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::ServerState;
+use crate::services::traits::FollowServiceTrait;
+use crate::utilities::cursor::CursorKind;
+
+pub async fn follow_profile<C: ContextTrait>(VerifiedSession { session }: VerifiedSession, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
+    match server_state.context.service_context().follow_service().follow_profile(session.get_profile_id(), id).await {
+        Ok(profile) => json!({
+            "profile": profile
+        }).to_string().into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn unfollow_profile<C: ContextTrait>(VerifiedSession { session }: VerifiedSession, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
+    match server_state.context.service_context().follow_service().unfollow_profile(session.get_profile_id(), id).await {
+        Ok(profile) => json!({
+            "profile": profile
+        }).to_string().into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn browse_followers<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>, Query(page): Query<PageQuery>) -> Response {
+    let starting_from_follow_id = match page.cursor.map(|cursor| server_state.cursor_signer.decode(CursorKind::Follows, &cursor)).transpose() {
+        Ok(follow_id) => follow_id,
+        Err(e) => return e.into_response()
+    };
+    let limit = page.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    // Fetch one profile more than requested to find out if there is a next page
+    match server_state.context.service_context().follow_service().find_followers(id, starting_from_follow_id, limit as i32 + 1).await {
+        Ok(profiles) => page_response("profiles", profiles, limit, CursorKind::Follows, |profile| profile.follow_id, &server_state.cursor_signer),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn browse_following<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>, Query(page): Query<PageQuery>) -> Response {
+    let starting_from_follow_id = match page.cursor.map(|cursor| server_state.cursor_signer.decode(CursorKind::Follows, &cursor)).transpose() {
+        Ok(follow_id) => follow_id,
+        Err(e) => return e.into_response()
+    };
+    let limit = page.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    // Fetch one profile more than requested to find out if there is a next page
+    match server_state.context.service_context().follow_service().find_following(id, starting_from_follow_id, limit as i32 + 1).await {
+        Ok(profiles) => page_response("profiles", profiles, limit, CursorKind::Follows, |profile| profile.follow_id, &server_state.cursor_signer),
+        Err(e) => e.into_response()
+    }
+}
+
+// Figures of the profiles the signed in profile follows, newest first
+pub async fn feed<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Query(page): Query<PageQuery>) -> Response {
+    let profile_id = match session.profile_id() {
+        Some(profile_id) => profile_id,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+    let starting_from_figure_id = match page.cursor.map(|cursor| server_state.cursor_signer.decode(CursorKind::Figures, &cursor)).transpose() {
+        Ok(figure_id) => figure_id,
+        Err(e) => return e.into_response()
+    };
+    let limit = page.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    // Fetch one figure more than requested to find out if there is a next page
+    match server_state.context.service_context().follow_service().find_feed(profile_id, starting_from_figure_id, limit as i32 + 1).await {
+        Ok(figures) => figures_page_response(&server_state, &session, figures, limit).await,
+        Err(e) => e.into_response()
+    }
+}
\ No newline at end of file
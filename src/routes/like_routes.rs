@@ -0,0 +1,41 @@
+use std::sync::Arc;
+use axum::Extension;
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Response};
+use crate::auth_layer::VerifiedSession;
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::dtos::session_dtos::SessionOption;
+use crate::entities::types::IdType;
+use crate::routes::figure_routes::{page_response, PageQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::ServerState;
+use crate::services::traits::LikeServiceTrait;
+use crate::utilities::cursor::CursorKind;
+
+pub async fn like_figure<C: ContextTrait>(VerifiedSession { session }: VerifiedSession, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
+    match server_state.context.service_context().like_service().like_figure(id, session.get_profile_id()).await {
+        Ok(figure) => figure.to_json_string().into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn unlike_figure<C: ContextTrait>(VerifiedSession { session }: VerifiedSession, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
+    match server_state.context.service_context().like_service().unlike_figure(id, session.get_profile_id()).await {
+        Ok(figure) => figure.to_json_string().into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+// Figures liked by a profile, most recently liked first
+pub async fn browse_liked_figures<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Path(profile_id): Path<IdType>, Query(page): Query<PageQuery>) -> Response {
+    let starting_from_like_id = match page.cursor.map(|cursor| server_state.cursor_signer.decode(CursorKind::Likes, &cursor)).transpose() {
+        Ok(like_id) => like_id,
+        Err(e) => return e.into_response()
+    };
+    let limit = page.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    // Fetch one figure more than requested to find out if there is a next page
+    match server_state.context.service_context().like_service().find_liked_figures(profile_id, starting_from_like_id, limit as i32 + 1, session.profile_id()).await {
+        Ok(figures) => page_response("figures", figures, limit, CursorKind::Likes, |liked_figure| liked_figure.like_id, &server_state.cursor_signer),
+        Err(e) => e.into_response()
+    }
+}
\ No newline at end of file
@@ -1,4 +1,16 @@
 pub mod authentication_routes;
 pub mod misc_routes;
 pub mod figure_routes;
-pub mod profile_routes;
\ No newline at end of file
+pub mod profile_routes;
+pub mod moderation_routes;
+pub mod admin_routes;
+pub mod session_routes;
+pub mod two_factor_routes;
+pub mod access_token_routes;
+pub mod oidc_routes;
+pub mod account_routes;
+pub mod tag_routes;
+pub mod search_routes;
+pub mod like_routes;
+pub mod comment_routes;
+pub mod follow_routes;
\ No newline at end of file
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use tracing::info;
+use crate::auth_layer::{Moderator, RequireRole};
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::types::IdType;
+use crate::ServerState;
+use crate::services::traits::{CommentServiceTrait, FigureServiceTrait};
+
+pub async fn moderate_delete_figure<C: ContextTrait>(moderator: RequireRole<Moderator>, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
+    match server_state.context.service_context().figure_service().delete_figure_as_moderator(id).await {
+        Ok(_) => {
+            info!("Figure (id: {}) deleted by moderator (user id: {})", id, moderator.session.get_user_id());
+            StatusCode::OK.into_response()
+        }
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn moderate_delete_comment<C: ContextTrait>(moderator: RequireRole<Moderator>, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<IdType>) -> Response {
+    match server_state.context.service_context().comment_service().delete_comment_as_moderator(id).await {
+        Ok(_) => {
+            info!("Comment (id: {}) deleted by moderator (user id: {})", id, moderator.session.get_user_id());
+            StatusCode::OK.into_response()
+        }
+        Err(e) => e.into_response()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use axum::extract::{Query, State};
+use axum::response::{IntoResponse, Redirect, Response};
+use cookie::{Cookie, SameSite};
+use cookie::time::Duration;
+use serde::Deserialize;
+use tower_cookies::Cookies;
+use tracing::error;
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::dtos::session_dtos::{Authentication, ClientInfo};
+use crate::routes::authentication_routes::create_session_cookie;
+use crate::server_errors::ServerError;
+use crate::ServerState;
+use crate::services::traits::{OidcServiceTrait, UserServiceTrait};
+
+const OIDC_STATE_COOKIE: &str = "oidc_state";
+const OIDC_STATE_COOKIE_PATH: &str = "/users/oidc";
+
+#[derive(Deserialize)]
+pub struct OidcSignInQuery {
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+#[derive(Deserialize)]
+pub struct OidcCallbackQuery {
+    pub state: Option<String>,
+    pub code: Option<String>,
+    // Sent by the provider instead of a code when the user cancelled or the request was refused
+    pub error: Option<String>,
+}
+
+// Redirects the browser to the sign in page of the provider
+pub async fn start_oidc_sign_in<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies, Query(query): Query<OidcSignInQuery>) -> Response {
+    match server_state.context.service_context().oidc_service().start_sign_in(query.remember_me).await {
+        Ok((state, authorization_url)) => {
+            cookies.add(create_state_cookie(&server_state.domain, state));
+            Redirect::to(&authorization_url).into_response()
+        }
+        Err(e) => e.into_response()
+    }
+}
+
+// The provider redirects the browser back here, it is sent on to the frontend with the outcome
+pub async fn complete_oidc_sign_in<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, cookies: Cookies, client: ClientInfo, Query(query): Query<OidcCallbackQuery>) -> Response {
+    match sign_in_from_callback(&server_state, &cookies, client, query).await {
+        Ok(Authentication::SignedIn(_profile, session)) => {
+            cookies.add(create_session_cookie(&server_state.domain, &session));
+            Redirect::to(&server_state.origin).into_response()
+        }
+        // In the fragment, so that the token isn't sent to any server
+        Ok(Authentication::TwoFactorRequired(token)) => {
+            Redirect::to(&format!("{}/signin/two-factor#token={}", server_state.origin, token)).into_response()
+        }
+        Err(e) => {
+            // Not turned into a response, which is what logs internal errors otherwise
+            if let ServerError::InternalError(error) = &e {
+                error!("Internal server error: {}\n{}", error, error.backtrace());
+            }
+            Redirect::to(&format!("{}/signin?error={}", server_state.origin, e)).into_response()
+        }
+    }
+}
+
+async fn sign_in_from_callback<C: ContextTrait>(server_state: &ServerState<C>, cookies: &Cookies, client: ClientInfo, query: OidcCallbackQuery) -> Result<Authentication, ServerError> {
+    // The state has to come back to the browser that started the sign in,
+    // otherwise anyone could sign a victim into their own account with a callback link
+    let state_cookie = cookies.get(OIDC_STATE_COOKIE).map(|cookie| cookie.value().to_string());
+    let mut removal = create_state_cookie(&server_state.domain, String::new());
+    removal.make_removal();
+    cookies.add(removal);
+
+    let (state, code) = match (query.state, query.code, query.error) {
+        (Some(state), Some(code), None) => (state, code),
+        _ => return Err(ServerError::InvalidOidcSignIn)
+    };
+    if state_cookie.as_deref() != Some(state.as_str()) {
+        return Err(ServerError::InvalidOidcSignIn);
+    }
+
+    let (identity, remember_me) = server_state.context.service_context().oidc_service().complete_sign_in(state, code).await?;
+    server_state.context.service_context().user_service().sign_in_with_external_identity(identity, client, remember_me).await
+}
+
+fn create_state_cookie(domain: &str, state: String) -> Cookie<'static> {
+    let mut cookie = Cookie::new(OIDC_STATE_COOKIE, state);
+    cookie.set_http_only(true);
+    cookie.set_secure(true);
+    // Lax so that it is sent along when the provider redirects back
+    cookie.set_same_site(SameSite::Lax);
+    cookie.set_domain(domain.to_string());
+    cookie.set_path(OIDC_STATE_COOKIE_PATH);
+    cookie.set_max_age(Duration::minutes(10));
+    cookie
+}
\ No newline at end of file
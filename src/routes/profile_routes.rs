@@ -1,15 +1,14 @@
 use std::io::Cursor;
 use std::sync::Arc;
 use anyhow::Context;
-use axum::Extension;
 use axum::extract::{Multipart, Path, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use bytes::Bytes;
 use serde_json::json;
+use crate::auth_layer::VerifiedSession;
 use crate::context::{ContextTrait, ServiceContextTrait};
 use crate::entities::dtos::profile_dto::ProfileWithoutUserIdDTO;
-use crate::entities::dtos::session_dtos::SessionOption;
 use crate::entities::types::IdType;
 use crate::server_errors::ServerError;
 use crate::ServerState;
@@ -35,12 +34,7 @@ pub async fn get_total_profiles_count<C: ContextTrait>(State(server_state): Stat
     }
 }
 
-pub async fn update_profile<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, session: Extension<SessionOption>, multipart: Multipart) -> Response {
-    let session = match &session.session_opt {
-        Some(s) => s,
-        None => return StatusCode::UNAUTHORIZED.into_response()
-    };
-
+pub async fn update_profile<C: ContextTrait>(VerifiedSession { session }: VerifiedSession, State(server_state): State<Arc<ServerState<C>>>, multipart: Multipart) -> Response {
     let result = parse_update_profile_multipart(multipart).await;
     let (display_name, bio, banner, profile_picture) = match result {
         Ok(tuple) => tuple,
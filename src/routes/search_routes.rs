@@ -0,0 +1,76 @@
+use std::sync::Arc;
+use axum::Extension;
+use axum::extract::{Query, State};
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::dtos::session_dtos::SessionOption;
+use crate::routes::figure_routes::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::ServerState;
+use crate::services::traits::{LikeServiceTrait, SearchServiceTrait};
+use crate::utilities::cursor::{CursorKind, CursorSigner};
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchType {
+    Figures,
+    Profiles,
+}
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    // Figures when not given
+    #[serde(rename = "type")]
+    pub search_type: Option<SearchType>,
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+}
+
+pub async fn search<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Query(query): Query<SearchQuery>) -> Response {
+    let offset = match query.cursor.map(|cursor| server_state.cursor_signer.decode(CursorKind::SearchResults, &cursor)).transpose() {
+        Ok(offset) => offset.unwrap_or(0),
+        Err(e) => return e.into_response()
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let search_service = server_state.context.service_context().search_service();
+
+    // Fetch one result more than requested to find out if there is a next page
+    match query.search_type.unwrap_or(SearchType::Figures) {
+        SearchType::Figures => {
+            let mut figures = match search_service.search_figures(query.q, offset, limit as i32 + 1).await {
+                Ok(figures) => figures,
+                Err(e) => return e.into_response()
+            };
+            match server_state.context.service_context().like_service().mark_liked_figures(session.profile_id(), &mut figures).await {
+                Ok(_) => ranked_page_response("figures", figures, offset, limit, &server_state.cursor_signer),
+                Err(e) => e.into_response()
+            }
+        },
+        SearchType::Profiles => match search_service.search_profiles(query.q, offset, limit as i32 + 1).await {
+            Ok(profiles) => ranked_page_response("profiles", profiles, offset, limit, &server_state.cursor_signer),
+            Err(e) => e.into_response()
+        }
+    }
+}
+
+// Results are ranked rather than sorted by id, so the cursor holds the number of results to skip
+fn ranked_page_response<T: Serialize>(name: &str, mut items: Vec<T>, offset: i64, limit: u32, cursor_signer: &CursorSigner) -> Response {
+    let has_more = items.len() > limit as usize;
+    items.truncate(limit as usize);
+
+    let next_cursor = match has_more {
+        true => match cursor_signer.encode(CursorKind::SearchResults, offset + limit as i64) {
+            Ok(cursor) => Some(cursor),
+            Err(e) => return e.into_response()
+        },
+        false => None
+    };
+
+    json!({
+        name: items,
+        "next_cursor": next_cursor,
+        "has_more": has_more
+    }).to_string().into_response()
+}
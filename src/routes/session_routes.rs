@@ -0,0 +1,53 @@
+use std::sync::Arc;
+use axum::{Extension, Json};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::dtos::session_dtos::{SessionDTO, SessionOption};
+use crate::ServerState;
+use crate::services::traits::UserServiceTrait;
+
+pub async fn get_sessions<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().user_service().find_sessions(session.get_user_id()).await {
+        Ok(sessions) => {
+            let current_session_id = session.get_id();
+            Json(sessions.into_iter()
+                .map(|listed_session| SessionDTO::from_summary(listed_session, &current_session_id))
+                .collect::<Vec<SessionDTO>>())
+                .into_response()
+        }
+        Err(e) => e.into_response()
+    }
+}
+
+// Revoke a session by the public id it was listed with
+pub async fn revoke_session<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Path(id): Path<String>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().user_service().revoke_session(session.get_user_id(), id).await {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+// Revoke every session except the one of the request
+pub async fn revoke_other_sessions<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().user_service().revoke_other_sessions(session.get_user_id(), session.get_id()).await {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => e.into_response()
+    }
+}
\ No newline at end of file
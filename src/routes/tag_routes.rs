@@ -0,0 +1,43 @@
+use std::sync::Arc;
+use axum::Extension;
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::json;
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::dtos::session_dtos::SessionOption;
+use crate::routes::figure_routes::{figures_page_response, PageQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::ServerState;
+use crate::services::traits::FigureServiceTrait;
+use crate::utilities::cursor::CursorKind;
+
+const POPULAR_TAGS_LIMIT: u32 = 20;
+
+#[derive(Deserialize)]
+pub struct PopularTagsQuery {
+    pub limit: Option<u32>,
+}
+
+pub async fn browse_tag_figures<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Path(tag): Path<String>, Query(page): Query<PageQuery>) -> Response {
+    let starting_from_figure_id = match page.cursor.map(|cursor| server_state.cursor_signer.decode(CursorKind::Figures, &cursor)).transpose() {
+        Ok(figure_id) => figure_id,
+        Err(e) => return e.into_response()
+    };
+    let limit = page.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    // Fetch one figure more than requested to find out if there is a next page
+    match server_state.context.service_context().figure_service().find_figures_starting_from_id_with_tag(starting_from_figure_id, tag, limit as i32 + 1).await {
+        Ok(figures) => figures_page_response(&server_state, &session, figures, limit).await,
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn popular_tags<C: ContextTrait>(State(server_state): State<Arc<ServerState<C>>>, Query(query): Query<PopularTagsQuery>) -> Response {
+    let limit = query.limit.unwrap_or(POPULAR_TAGS_LIMIT).clamp(1, MAX_PAGE_SIZE);
+    match server_state.context.service_context().figure_service().find_popular_tags(limit as i32).await {
+        Ok(tags) => json!({
+            "tags": tags
+        }).to_string().into_response(),
+        Err(e) => e.into_response()
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+use axum::{Extension, Json};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use crate::context::{ContextTrait, ServiceContextTrait};
+use crate::entities::dtos::session_dtos::SessionOption;
+use crate::entities::dtos::two_factor_dtos::RecoveryCodesDTO;
+use crate::ServerState;
+use crate::services::traits::UserServiceTrait;
+
+#[derive(Deserialize)]
+pub struct StartTotpEnrolmentForm {
+    pub password: String,
+}
+
+#[derive(Deserialize)]
+pub struct ConfirmTotpForm {
+    pub code: String,
+}
+
+#[derive(Deserialize)]
+pub struct DisableTotpForm {
+    pub password: String,
+}
+
+// Starting again replaces the secret of an enrolment that wasn't confirmed.
+// The password is asked for so that a stolen session can't enrol a second factor of its own.
+pub async fn start_totp_enrolment<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Json(form): Json<StartTotpEnrolmentForm>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().user_service().start_totp_enrolment(session.get_user_id(), form.password).await {
+        Ok(enrolment) => Json(enrolment).into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+// The recovery codes are only returned here, they can't be retrieved later
+pub async fn confirm_totp_enrolment<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Json(form): Json<ConfirmTotpForm>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().user_service().confirm_totp_enrolment(session.get_user_id(), form.code).await {
+        Ok(recovery_codes) => Json(RecoveryCodesDTO { recovery_codes }).into_response(),
+        Err(e) => e.into_response()
+    }
+}
+
+pub async fn disable_totp<C: ContextTrait>(Extension(session): Extension<SessionOption>, State(server_state): State<Arc<ServerState<C>>>, Json(form): Json<DisableTotpForm>) -> Response {
+    let session = match session.session_opt {
+        Some(session) => session,
+        None => return StatusCode::UNAUTHORIZED.into_response()
+    };
+
+    match server_state.context.service_context().user_service().disable_totp(session.get_user_id(), form.password).await {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => e.into_response()
+    }
+}
\ No newline at end of file
@@ -1,7 +1,8 @@
 use serde::{Serialize};
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
-use axum::http::StatusCode;
+use axum::http::{HeaderValue, StatusCode};
+use axum::http::header::RETRY_AFTER;
 use axum::Json;
 use axum::response::IntoResponse;
 use tracing::error;
@@ -16,13 +17,46 @@ pub enum ServerError {
     UsernameAlreadyTaken,
     UserWithEmailNotFound,
     WrongPassword,
+    AccountSuspended,
+    EmailNotVerified,
+    EmailAlreadyVerified,
     ResourceNotFound,
+    // Session is valid but doesn't own the resource
+    Forbidden,
     // No session cookie received
     NoSessionReceived,
     InvalidImage,
     MissingFieldInForm,
     InvalidMultipart,
     ImageDimensionsTooLarge,
+    InvalidCursor,
+    // Unknown, expired or already used token
+    InvalidToken,
+    // Wrong, expired or already used TOTP code or recovery code
+    InvalidTwoFactorCode,
+    TwoFactorAlreadyEnabled,
+    TwoFactorNotEnabled,
+    // Confirming an enrolment that was never started
+    TwoFactorNotStarted,
+    InvalidAccessTokenName,
+    MissingAccessTokenScopes,
+    // The access token of the request doesn't grant access to the route
+    InsufficientScope,
+    // Missing or not matching the session of the request
+    InvalidCsrfToken,
+    OidcNotConfigured,
+    // Unknown or expired state, a code the provider rejected or an ID token that failed validation
+    InvalidOidcSignIn,
+    // Empty, too long or containing characters other than letters, digits and dashes
+    InvalidTag,
+    TooManyTags,
+    // Empty or longer than 100 characters
+    InvalidSearchQuery,
+    // Empty or longer than 1000 characters
+    InvalidComment,
+    CannotFollowYourself,
+    // Seconds until the client can try again
+    TooManyRequests(usize),
     InternalError(Arc<anyhow::Error>),
 }
 
@@ -42,12 +76,34 @@ impl Display for ServerError {
             ServerError::UsernameAlreadyTaken => "username-already-taken",
             ServerError::UserWithEmailNotFound => "user-with-email-not-found",
             ServerError::WrongPassword => "wrong-password",
+            ServerError::AccountSuspended => "account-suspended",
+            ServerError::EmailNotVerified => "email-not-verified",
+            ServerError::EmailAlreadyVerified => "email-already-verified",
             ServerError::ResourceNotFound => "resource-not-found",
+            ServerError::Forbidden => "forbidden",
             ServerError::NoSessionReceived => "no-session-received",
             ServerError::InvalidImage => "invalid-image",
             ServerError::MissingFieldInForm => "missing-field-in-form",
             ServerError::InvalidMultipart => "invalid-multipart",
             ServerError::ImageDimensionsTooLarge => "image-dimensions-too-large",
+            ServerError::InvalidCursor => "invalid-cursor",
+            ServerError::InvalidToken => "invalid-token",
+            ServerError::InvalidTwoFactorCode => "invalid-two-factor-code",
+            ServerError::TwoFactorAlreadyEnabled => "two-factor-already-enabled",
+            ServerError::TwoFactorNotEnabled => "two-factor-not-enabled",
+            ServerError::TwoFactorNotStarted => "two-factor-not-started",
+            ServerError::InvalidAccessTokenName => "invalid-access-token-name",
+            ServerError::MissingAccessTokenScopes => "missing-access-token-scopes",
+            ServerError::InsufficientScope => "insufficient-scope",
+            ServerError::InvalidCsrfToken => "invalid-csrf-token",
+            ServerError::OidcNotConfigured => "oidc-not-configured",
+            ServerError::InvalidOidcSignIn => "invalid-oidc-sign-in",
+            ServerError::InvalidTag => "invalid-tag",
+            ServerError::TooManyTags => "too-many-tags",
+            ServerError::InvalidSearchQuery => "invalid-search-query",
+            ServerError::InvalidComment => "invalid-comment",
+            ServerError::CannotFollowYourself => "cannot-follow-yourself",
+            ServerError::TooManyRequests(_) => "too-many-requests",
             ServerError::InternalError(_) => "internal-server-error"
         };
         write!(f, "{}", message)
@@ -65,12 +121,34 @@ impl IntoResponse for ServerError {
             ServerError::UsernameAlreadyTaken => StatusCode::BAD_REQUEST,
             ServerError::UserWithEmailNotFound => StatusCode::NOT_FOUND,
             ServerError::WrongPassword => StatusCode::BAD_REQUEST,
+            ServerError::AccountSuspended => StatusCode::FORBIDDEN,
+            ServerError::EmailNotVerified => StatusCode::FORBIDDEN,
+            ServerError::EmailAlreadyVerified => StatusCode::BAD_REQUEST,
             ServerError::ResourceNotFound => StatusCode::NOT_FOUND,
+            ServerError::Forbidden => StatusCode::FORBIDDEN,
             ServerError::NoSessionReceived => StatusCode::BAD_REQUEST,
             ServerError::InvalidImage => StatusCode::BAD_REQUEST,
             ServerError::MissingFieldInForm => StatusCode::BAD_REQUEST,
             ServerError::InvalidMultipart => StatusCode::BAD_REQUEST,
             ServerError::ImageDimensionsTooLarge => StatusCode::BAD_REQUEST,
+            ServerError::InvalidCursor => StatusCode::BAD_REQUEST,
+            ServerError::InvalidToken => StatusCode::BAD_REQUEST,
+            ServerError::InvalidTwoFactorCode => StatusCode::BAD_REQUEST,
+            ServerError::TwoFactorAlreadyEnabled => StatusCode::BAD_REQUEST,
+            ServerError::TwoFactorNotEnabled => StatusCode::BAD_REQUEST,
+            ServerError::TwoFactorNotStarted => StatusCode::BAD_REQUEST,
+            ServerError::InvalidAccessTokenName => StatusCode::BAD_REQUEST,
+            ServerError::MissingAccessTokenScopes => StatusCode::BAD_REQUEST,
+            ServerError::InsufficientScope => StatusCode::FORBIDDEN,
+            ServerError::InvalidCsrfToken => StatusCode::FORBIDDEN,
+            ServerError::OidcNotConfigured => StatusCode::NOT_FOUND,
+            ServerError::InvalidOidcSignIn => StatusCode::BAD_REQUEST,
+            ServerError::InvalidTag => StatusCode::BAD_REQUEST,
+            ServerError::TooManyTags => StatusCode::BAD_REQUEST,
+            ServerError::InvalidSearchQuery => StatusCode::BAD_REQUEST,
+            ServerError::InvalidComment => StatusCode::BAD_REQUEST,
+            ServerError::CannotFollowYourself => StatusCode::BAD_REQUEST,
+            ServerError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
             ServerError::InternalError(error) => {
                 let error = error.clone();
                 tokio::task::spawn(async move {
@@ -79,12 +157,16 @@ impl IntoResponse for ServerError {
                 StatusCode::INTERNAL_SERVER_ERROR
             },
         };
-        (
+        let mut response = (
             status_code,
             Json(ErrorResponse {
                 error: &self.to_string()
             })
-        ).into_response()
+        ).into_response();
+        if let ServerError::TooManyRequests(retry_after) = self {
+            response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+        response
     }
 }
 
@@ -0,0 +1,99 @@
+use std::marker::PhantomData;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use tracing::warn;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::entities::access_token::{AccessToken, Scope};
+use crate::entities::dtos::session_dtos::SessionFromStore;
+use crate::entities::types::IdType;
+use crate::repositories::traits::{AccessTokenRepositoryTrait, ProfileRepositoryTrait, TransactionTrait, UserRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::services::traits::AccessTokenServiceTrait;
+use crate::utilities::token::hash_token;
+use crate::utilities::traits::RandomNumberGenerator;
+
+const LAST_USED_UPDATE_INTERVAL_MINUTES: i64 = 5;
+
+pub struct AccessTokenService<T, A, U, P, R> {
+    access_token_repository: A,
+    user_repository: U,
+    profile_repository: P,
+    secure_random_generator: R,
+    marker: PhantomData<T>,
+}
+
+impl<T, A, U, P, R> AccessTokenService<T, A, U, P, R>
+    where T: TransactionTrait, A: AccessTokenRepositoryTrait<T>, U: UserRepositoryTrait<T>,
+          P: ProfileRepositoryTrait<T>, R: RandomNumberGenerator {
+    pub fn new(access_token_repository: A, user_repository: U, profile_repository: P, secure_random_generator: R) -> Self {
+        Self {
+            access_token_repository,
+            user_repository,
+            profile_repository,
+            secure_random_generator,
+            marker: PhantomData::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl<T, A, U, P, R> AccessTokenServiceTrait for AccessTokenService<T, A, U, P, R>
+    where T: TransactionTrait, A: AccessTokenRepositoryTrait<T>, U: UserRepositoryTrait<T>,
+          P: ProfileRepositoryTrait<T>, R: RandomNumberGenerator {
+    async fn create_access_token(&self, user_id: IdType, name: String, scopes: Vec<Scope>) -> Result<(String, AccessToken), ServerError> {
+        let name = name.trim().to_string();
+        if !is_access_token_name_valid(&name) {
+            return Err(ServerError::InvalidAccessTokenName);
+        }
+        let mut unique_scopes: Vec<Scope> = Vec::new();
+        for scope in scopes {
+            if !unique_scopes.contains(&scope) {
+                unique_scopes.push(scope);
+            }
+        }
+        if unique_scopes.is_empty() {
+            return Err(ServerError::MissingAccessTokenScopes);
+        }
+
+        let token = self.secure_random_generator.generate_token()?;
+        let access_token = self.access_token_repository.create(None, user_id, name, hash_token(&token), unique_scopes).await?;
+        Ok((token, access_token))
+    }
+
+    async fn find_access_tokens(&self, user_id: IdType) -> Result<Vec<AccessToken>, ServerError> {
+        self.access_token_repository.find_all_by_user_id(None, user_id).await
+    }
+
+    async fn revoke_access_token(&self, user_id: IdType, access_token_id: IdType) -> Result<(), ServerError> {
+        self.access_token_repository.delete(None, access_token_id, user_id).await
+    }
+
+    async fn find_session_by_token(&self, token: String) -> Result<SessionFromStore, ServerError> {
+        let access_token = self.access_token_repository.find_by_token_hash(None, &hash_token(&token)).await?;
+        // Checked on every request, so suspending a user or changing their role applies to their tokens right away
+        let user = self.user_repository.find_one_by_id(None, access_token.user_id).await?;
+        if user.suspended {
+            return Err(ServerError::AccountSuspended);
+        }
+        let profile = self.profile_repository.find_by_user_id(None, user.id).await?;
+
+        // Only written once in a while, so not every request writes to the database
+        let last_used_update_due = match access_token.last_used_at {
+            Some(last_used_at) => Utc::now() - last_used_at > Duration::minutes(LAST_USED_UPDATE_INTERVAL_MINUTES),
+            None => true
+        };
+        if last_used_update_due {
+            if let Err(e) = self.access_token_repository.update_last_used_at(None, access_token.id).await {
+                warn!("Failed to update last used of access token (id: {}): {}", access_token.id, e);
+            }
+        }
+
+        Ok(SessionFromStore::new(access_token.token_hash, user.id, profile.id, user.role)
+            .with_scopes(access_token.scopes))
+    }
+}
+
+// Between 1 and 50 characters, shown to the user to tell their tokens apart
+fn is_access_token_name_valid(name: &str) -> bool {
+    (1..=50).contains(&name.graphemes(true).count())
+}
\ No newline at end of file
@@ -0,0 +1,44 @@
+use crate::mailer::Mailer;
+use crate::server_errors::ServerError;
+
+// Composes the mails sent to users about their account, links in them point to the frontend
+#[derive(Clone)]
+pub struct AccountMailer<M> {
+    mailer: M,
+    frontend_url: String,
+}
+
+impl<M: Mailer> AccountMailer<M> {
+    pub fn new(mailer: M, frontend_url: String) -> Self {
+        Self {
+            mailer,
+            frontend_url: frontend_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub async fn send_password_reset(&self, to: &str, token: &str, valid_for_minutes: usize) -> Result<(), ServerError> {
+        let body = format!(
+            "Someone requested a password reset for your account.\n\n\
+            Use the following link to choose a new password, it is valid for {} minutes:\n{}/reset-password?token={}\n\n\
+            If this wasn't you, you can ignore this mail.",
+            valid_for_minutes, self.frontend_url, token);
+        self.mailer.send_mail(to, "Reset your password", body).await
+    }
+
+    pub async fn send_email_verification(&self, to: &str, token: &str, valid_for_hours: usize) -> Result<(), ServerError> {
+        let body = format!(
+            "Use the following link to verify the email address of your account, it is valid for {} hours:\n\
+            {}/verify-email?token={}",
+            valid_for_hours, self.frontend_url, token);
+        self.mailer.send_mail(to, "Verify your email address", body).await
+    }
+
+    pub async fn send_data_export(&self, to: &str, token: &str, valid_for_hours: usize) -> Result<(), ServerError> {
+        let body = format!(
+            "The export of your data you requested is ready.\n\n\
+            Use the following link to download it, it can be used once and is valid for {} hours:\n\
+            {}/data-export?token={}",
+            valid_for_hours, self.frontend_url, token);
+        self.mailer.send_mail(to, "Your data export is ready", body).await
+    }
+}
\ No newline at end of file
@@ -0,0 +1,92 @@
+use std::marker::PhantomData;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use tracing::warn;
+use crate::content_store::{ContentStore, delete_queued_objects, queue_object_deletions};
+use crate::entities::types::IdType;
+use crate::repositories::traits::{FigureRepositoryTrait, FollowRepositoryTrait, LikeRepositoryTrait, ObjectDeletionRepositoryTrait, ProfileRepositoryTrait, SessionRepositoryTrait, TransactionCreatorTrait, TransactionTrait, UserRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::services::traits::AccountServiceTrait;
+use crate::utilities::password::verify_password;
+
+// How long after signing in a user without a password can still delete the account
+const RECENT_SIGN_IN_MINUTES: i64 = 10;
+
+pub struct AccountService<TC, T, U, P, F, L, W, D, S, C> {
+    transaction_creator: TC,
+    user_repository: U,
+    profile_repository: P,
+    figure_repository: F,
+    like_repository: L,
+    follow_repository: W,
+    object_deletion_repository: D,
+    session_repository: S,
+    storage: C,
+    marker: PhantomData<T>,
+}
+
+impl<TC, T, U, P, F, L, W, D, S, C> AccountService<TC, T, U, P, F, L, W, D, S, C>
+    where TC: TransactionCreatorTrait<T>, T: TransactionTrait, U: UserRepositoryTrait<T>, P: ProfileRepositoryTrait<T>,
+          F: FigureRepositoryTrait<T>, L: LikeRepositoryTrait<T>, W: FollowRepositoryTrait<T>, D: ObjectDeletionRepositoryTrait<T>,
+          S: SessionRepositoryTrait, C: ContentStore {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(transaction_creator: TC, user_repository: U, profile_repository: P, figure_repository: F, like_repository: L, follow_repository: W, object_deletion_repository: D, session_repository: S, storage: C) -> Self {
+        Self {
+            transaction_creator,
+            user_repository,
+            profile_repository,
+            figure_repository,
+            like_repository,
+            follow_repository,
+            object_deletion_repository,
+            session_repository,
+            storage,
+            marker: PhantomData::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl<TC, T, U, P, F, L, W, D, S, C> AccountServiceTrait for AccountService<TC, T, U, P, F, L, W, D, S, C>
+    where TC: TransactionCreatorTrait<T>, T: TransactionTrait, U: UserRepositoryTrait<T>, P: ProfileRepositoryTrait<T>,
+          F: FigureRepositoryTrait<T>, L: LikeRepositoryTrait<T>, W: FollowRepositoryTrait<T>, D: ObjectDeletionRepositoryTrait<T>,
+          S: SessionRepositoryTrait, C: ContentStore {
+    async fn delete_account(&self, user_id: IdType, password: Option<String>, signed_in_at: Option<DateTime<Utc>>) -> Result<(), ServerError> {
+        let user = self.user_repository.find_one_by_id(None, user_id).await?;
+        match password {
+            Some(password) => verify_password(password, user.password.clone()).await?,
+            // Users created through an identity provider never got to know their password,
+            // signing in with the provider again right before confirms the deletion instead
+            None => {
+                let recently_signed_in = matches!(signed_in_at, Some(signed_in_at) if Utc::now() - signed_in_at < Duration::minutes(RECENT_SIGN_IN_MINUTES));
+                if !recently_signed_in || !self.user_repository.has_external_identity(None, user.id).await? {
+                    return Err(ServerError::WrongPassword);
+                }
+            }
+        }
+
+        let mut transaction = self.transaction_creator.create().await?;
+        let profile = self.profile_repository.find_by_user_id(Some(&mut transaction), user.id).await?;
+        // Removed before the profile so the like counts of the figures it liked
+        // and the follow counts of the profiles it followed or was followed by are lowered
+        self.like_repository.delete_by_profile_id(Some(&mut transaction), profile.id).await?;
+        self.follow_repository.delete_by_profile_id(Some(&mut transaction), profile.id).await?;
+        let figure_urls = self.figure_repository.delete_by_profile_id(Some(&mut transaction), profile.id).await?;
+        self.profile_repository.delete_by_id(Some(&mut transaction), profile.id).await?;
+        self.user_repository.delete_by_id(Some(&mut transaction), user.id).await?;
+        // Only removed once the rows are gone, so nothing can point to a missing image
+        let images = figure_urls.into_iter()
+            .chain(profile.banner)
+            .chain(profile.profile_picture)
+            .collect();
+        let deletions = queue_object_deletions(&self.storage, &self.object_deletion_repository, &mut transaction, images).await?;
+        transaction.commit().await?;
+
+        // Revoked first, the account is gone at this point and failing would only hide that from the user
+        if let Err(e) = self.session_repository.remove_all_by_user_id(user.id).await {
+            warn!("Failed to remove the sessions of deleted user (id: {}): {}", user.id, e);
+        }
+        delete_queued_objects(&self.storage, &self.object_deletion_repository, deletions).await;
+        Ok(())
+    }
+}
\ No newline at end of file
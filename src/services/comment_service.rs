@@ -0,0 +1,96 @@
+use std::marker::PhantomData;
+use async_trait::async_trait;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::entities::dtos::comment_dto::CommentDTO;
+use crate::entities::types::IdType;
+use crate::repositories::traits::{CommentRepositoryTrait, FigureRepositoryTrait, TransactionTrait};
+use crate::server_errors::ServerError;
+use crate::services::traits::CommentServiceTrait;
+
+const MAX_COMMENT_LENGTH: usize = 1000;
+
+pub struct CommentService<T, F, C> {
+    figure_repository: F,
+    comment_repository: C,
+    marker: PhantomData<T>,
+}
+
+impl<T: TransactionTrait, F: FigureRepositoryTrait<T>, C: CommentRepositoryTrait<T>> CommentService<T, F, C> {
+    pub fn new(figure_repository: F, comment_repository: C) -> Self {
+        Self {
+            figure_repository,
+            comment_repository,
+            marker: PhantomData::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl<T, F, C> CommentServiceTrait for CommentService<T, F, C>
+    where T: TransactionTrait, F: FigureRepositoryTrait<T>, C: CommentRepositoryTrait<T> {
+    async fn create_comment(&self, figure_id: IdType, profile_id: IdType, parent_id: Option<IdType>, content: String) -> Result<CommentDTO, ServerError> {
+        let content = validate_content(content)?;
+        // Not found instead of failing on the foreign key
+        self.figure_repository.find_by_id(None, figure_id).await?;
+
+        let thread_id = match parent_id {
+            Some(parent_id) => {
+                let parent = self.comment_repository.find_by_id(None, parent_id).await?;
+                if parent.figure_id != figure_id {
+                    return Err(ServerError::ResourceNotFound);
+                }
+                Some(parent.parent_id.unwrap_or(parent.id))
+            }
+            None => None
+        };
+
+        let comment_id = self.comment_repository.create(None, figure_id, profile_id, thread_id, content).await?;
+        self.comment_repository.find_by_id(None, comment_id).await
+    }
+
+    async fn find_comments(&self, figure_id: IdType, comment_id: Option<IdType>, limit: i32) -> Result<Vec<CommentDTO>, ServerError> {
+        self.figure_repository.find_by_id(None, figure_id).await?;
+        self.comment_repository.find_starting_from_id_with_figure_id(None, figure_id, comment_id, limit).await
+    }
+
+    async fn find_replies(&self, parent_id: IdType, comment_id: Option<IdType>, limit: i32) -> Result<Vec<CommentDTO>, ServerError> {
+        self.comment_repository.find_by_id(None, parent_id).await?;
+        self.comment_repository.find_replies_starting_from_id(None, parent_id, comment_id, limit).await
+    }
+
+    async fn update_comment(&self, comment_id: IdType, profile_id: IdType, content: String) -> Result<CommentDTO, ServerError> {
+        let content = validate_content(content)?;
+        let comment = self.comment_repository.find_by_id(None, comment_id).await?;
+        if comment.profile.id != profile_id {
+            return Err(ServerError::Forbidden);
+        }
+
+        self.comment_repository.update_content(None, comment_id, content).await?;
+        self.comment_repository.find_by_id(None, comment_id).await
+    }
+
+    async fn delete_comment(&self, comment_id: IdType, profile_id: IdType) -> Result<(), ServerError> {
+        let comment = self.comment_repository.find_by_id(None, comment_id).await?;
+        if comment.profile.id != profile_id {
+            let figure = self.figure_repository.find_by_id(None, comment.figure_id).await?;
+            if figure.profile.id != profile_id {
+                return Err(ServerError::Forbidden);
+            }
+        }
+        self.comment_repository.delete_by_id(None, comment_id).await
+    }
+
+    async fn delete_comment_as_moderator(&self, comment_id: IdType) -> Result<(), ServerError> {
+        self.comment_repository.find_by_id(None, comment_id).await?;
+        self.comment_repository.delete_by_id(None, comment_id).await
+    }
+}
+
+// Between 1 and 1000 characters, surrounding whitespace is ignored
+fn validate_content(content: String) -> Result<String, ServerError> {
+    let content = content.trim();
+    if !(1..=MAX_COMMENT_LENGTH).contains(&content.graphemes(true).count()) {
+        return Err(ServerError::InvalidComment);
+    }
+    Ok(content.to_string())
+}
\ No newline at end of file
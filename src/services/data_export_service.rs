@@ -0,0 +1,203 @@
+use std::fs::File;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use bytes::Bytes;
+use tempfile::NamedTempFile;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tracing::warn;
+use zip::CompressionMethod;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+use crate::content_store::ContentStore;
+use crate::entities::dtos::figure_dto::FigureDTO;
+use crate::entities::dtos::profile_dto::ProfileWithoutUserIdDTO;
+use crate::entities::dtos::user_dto::UserDTO;
+use crate::entities::one_time_token::OneTimeTokenKind;
+use crate::entities::profile::Profile;
+use crate::entities::types::IdType;
+use crate::mailer::Mailer;
+use crate::repositories::traits::{FigureRepositoryTrait, OneTimeTokenRepositoryTrait, ProfileRepositoryTrait, TransactionTrait, UserRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::services::account_mailer::AccountMailer;
+use crate::services::traits::DataExportServiceTrait;
+use crate::utilities::token::hash_token;
+use crate::utilities::traits::RandomNumberGenerator;
+
+// The export bucket removes archives after a day with a lifecycle rule, the link expires before that
+const DATA_EXPORT_TOKEN_EXPIRATION: usize = 86400;
+const DOWNLOAD_URL_EXPIRATION: Duration = Duration::from_secs(900);
+const FIGURES_PAGE_SIZE: i32 = 100;
+
+// File in the archive, images are already compressed so they are stored as is
+struct ArchiveEntry {
+    name: String,
+    contents: Bytes,
+    compress: bool,
+}
+
+impl ArchiveEntry {
+    fn json(name: &str, value: &impl serde::Serialize) -> Result<Self, ServerError> {
+        let contents = serde_json::to_vec_pretty(value)
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        Ok(Self { name: name.to_string(), contents: Bytes::from(contents), compress: true })
+    }
+
+    fn image(name: String, contents: Bytes) -> Self {
+        Self { name, contents, compress: false }
+    }
+}
+
+pub struct DataExportService<T, U, P, F, O, C, M, R> {
+    user_repository: U,
+    profile_repository: P,
+    figure_repository: F,
+    one_time_token_repository: O,
+    storage: C,
+    // Private bucket the archives are uploaded to
+    export_storage: C,
+    account_mailer: AccountMailer<M>,
+    secure_random_generator: R,
+    marker: PhantomData<T>,
+}
+
+impl<T, U, P, F, O, C, M, R> DataExportService<T, U, P, F, O, C, M, R>
+    where T: TransactionTrait, U: UserRepositoryTrait<T>, P: ProfileRepositoryTrait<T>, F: FigureRepositoryTrait<T>,
+          O: OneTimeTokenRepositoryTrait, C: ContentStore, M: Mailer, R: RandomNumberGenerator {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(user_repository: U, profile_repository: P, figure_repository: F, one_time_token_repository: O, storage: C, export_storage: C, account_mailer: AccountMailer<M>, secure_random_generator: R) -> Self {
+        Self {
+            user_repository,
+            profile_repository,
+            figure_repository,
+            one_time_token_repository,
+            storage,
+            export_storage,
+            account_mailer,
+            secure_random_generator,
+            marker: PhantomData::default(),
+        }
+    }
+
+    async fn find_all_figures(&self, profile_id: IdType) -> Result<Vec<FigureDTO>, ServerError> {
+        let mut figures = Vec::new();
+        loop {
+            let starting_from_id = figures.last().map(|figure: &FigureDTO| figure.id);
+            let page = self.figure_repository.find_starting_from_id_with_profile_id(None, starting_from_id, Some(profile_id), FIGURES_PAGE_SIZE).await?;
+            let last_page = page.len() < FIGURES_PAGE_SIZE as usize;
+            figures.extend(page);
+            if last_page {
+                return Ok(figures);
+            }
+        }
+    }
+
+    // Images that can't be fetched are left out, their urls are still in the exported metadata
+    async fn fetch_image(&self, url: &str) -> Option<Bytes> {
+        let object_name = match self.storage.get_object_name(url) {
+            Some(object_name) => object_name,
+            None => {
+                warn!("Url {} is not part of the content store, leaving it out of the data export", url);
+                return None;
+            }
+        };
+        match self.storage.get_object(&object_name).await {
+            Ok(image) => Some(image),
+            Err(e) => {
+                warn!("Failed to fetch object {} for a data export: {}", object_name, e);
+                None
+            }
+        }
+    }
+
+    // Errors once the archive writer stopped receiving entries
+    async fn send_entries(&self, sender: &Sender<ArchiveEntry>, user: UserDTO, profile: Profile, figures: Vec<FigureDTO>) -> Result<(), ServerError> {
+        let closed = |_| ServerError::InternalError(Arc::new(anyhow!("archive writer stopped")));
+        for figure in &figures {
+            if let Some(image) = self.fetch_image(&figure.url).await {
+                sender.send(ArchiveEntry::image(format!("figures/{}.jpg", figure.id), image)).await.map_err(closed)?;
+            }
+        }
+        for (name, url) in [("profile/banner.jpg", &profile.banner), ("profile/profile_picture.jpg", &profile.profile_picture)] {
+            let image = match url {
+                Some(url) => self.fetch_image(url).await,
+                None => continue
+            };
+            if let Some(image) = image {
+                sender.send(ArchiveEntry::image(name.to_string(), image)).await.map_err(closed)?;
+            }
+        }
+        sender.send(ArchiveEntry::json("account.json", &user)?).await.map_err(closed)?;
+        sender.send(ArchiveEntry::json("profile.json", &ProfileWithoutUserIdDTO::from(profile))?).await.map_err(closed)?;
+        sender.send(ArchiveEntry::json("figures.json", &figures)?).await.map_err(closed)
+    }
+}
+
+#[async_trait]
+impl<T, U, P, F, O, C, M, R> DataExportServiceTrait for DataExportService<T, U, P, F, O, C, M, R>
+    where T: TransactionTrait, U: UserRepositoryTrait<T>, P: ProfileRepositoryTrait<T>, F: FigureRepositoryTrait<T>,
+          O: OneTimeTokenRepositoryTrait, C: ContentStore, M: Mailer, R: RandomNumberGenerator {
+    async fn export_data(&self, user_id: IdType) -> Result<(), ServerError> {
+        let user = self.user_repository.find_one_by_id(None, user_id).await?;
+        let profile = self.profile_repository.find_by_user_id(None, user.id).await?;
+        let figures = self.find_all_figures(profile.id).await?;
+
+        // The archive is written to a temporary file while the images are fetched one by one,
+        // so only a single image is kept in memory at a time
+        let archive_file = NamedTempFile::new()
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        let file = archive_file.reopen()
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        let (sender, receiver) = channel(1);
+        let writer = tokio::task::spawn_blocking(move || write_archive(file, receiver));
+
+        let email = user.email.clone();
+        let entries_sent = self.send_entries(&sender, UserDTO::from(user), profile, figures).await;
+        drop(sender);
+        // A writer that failed stops receiving entries, so its error explains a failed send
+        writer.await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))??;
+        entries_sent?;
+
+        let token = self.secure_random_generator.generate_token()?;
+        let token_hash = hash_token(&token);
+        self.export_storage.upload_file(&archive_object_name(&token_hash), archive_file.path(), "application/zip").await?;
+        self.one_time_token_repository.create(OneTimeTokenKind::DataExport, &token_hash, user_id, DATA_EXPORT_TOKEN_EXPIRATION).await?;
+
+        self.account_mailer.send_data_export(&email, &token, DATA_EXPORT_TOKEN_EXPIRATION / 3600).await
+    }
+
+    async fn take_data_export(&self, token: String) -> Result<String, ServerError> {
+        let token_hash = hash_token(&token);
+        // Only used up once the download url was created, so a failing store doesn't cost the user their link
+        self.one_time_token_repository.find(OneTimeTokenKind::DataExport, &token_hash).await?;
+        let url = self.export_storage.get_download_url(&archive_object_name(&token_hash), DOWNLOAD_URL_EXPIRATION).await?;
+        self.one_time_token_repository.take(OneTimeTokenKind::DataExport, &token_hash).await?;
+        Ok(url)
+    }
+}
+
+// Only derivable from the token, the name isn't given out
+fn archive_object_name(token_hash: &str) -> String {
+    format!("exports/{}.zip", token_hash)
+}
+
+fn write_archive(file: File, mut receiver: Receiver<ArchiveEntry>) -> Result<(), ServerError> {
+    let mut writer = ZipWriter::new(file);
+    while let Some(entry) = receiver.blocking_recv() {
+        let compression_method = match entry.compress {
+            true => CompressionMethod::Deflated,
+            false => CompressionMethod::Stored
+        };
+        writer.start_file(entry.name, FileOptions::default().compression_method(compression_method))
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        writer.write_all(&entry.contents)
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+    }
+    writer.finish()
+        .map(|_| ())
+        .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+}
@@ -1,34 +1,120 @@
 use std::marker::PhantomData;
 use async_trait::async_trait;
 use bytes::Bytes;
+use lazy_static::lazy_static;
+use regex::Regex;
+use tracing::warn;
 use uuid::Uuid;
-use crate::content_store::ContentStore;
+use crate::content_store::{ContentStore, delete_queued_objects, queue_object_deletions};
 use crate::entities::dtos::figure_dto::FigureDTO;
 use crate::entities::figure::Figure;
+use crate::entities::object_deletion::ObjectDeletion;
+use crate::entities::tag::TagCount;
 use crate::entities::types::IdType;
-use crate::repositories::traits::{FigureRepositoryTrait, TransactionTrait};
+use crate::repositories::traits::{FigureRepositoryTrait, ObjectDeletionRepositoryTrait, TagRepositoryTrait, TransactionCreatorTrait, TransactionTrait};
 use crate::server_errors::ServerError;
 use crate::services::traits::FigureServiceTrait;
 
-pub struct FigureService<T, F, S> {
+lazy_static! {
+    static ref TAG_REGEX: Regex =
+    Regex::new("^[a-z0-9]+(?:-[a-z0-9]+)*$").unwrap();
+}
+
+const MAX_TAG_LENGTH: usize = 30;
+const MAX_TAGS_PER_FIGURE: usize = 10;
+
+pub struct FigureService<TC, T, F, G, D, S> {
+    transaction_creator: TC,
     figure_repository: F,
+    tag_repository: G,
+    object_deletion_repository: D,
     storage: S,
     marker: PhantomData<T>,
 }
 
-impl<T: TransactionTrait, F: FigureRepositoryTrait<T>, S: ContentStore> FigureService<T, F, S> {
-    pub fn new(figure_repository: F, storage: S) -> Self {
+impl<TC, T, F, G, D, S> FigureService<TC, T, F, G, D, S>
+    where TC: TransactionCreatorTrait<T>, T: TransactionTrait, F: FigureRepositoryTrait<T>,
+          G: TagRepositoryTrait<T>, D: ObjectDeletionRepositoryTrait<T>, S: ContentStore {
+    pub fn new(transaction_creator: TC, figure_repository: F, tag_repository: G, object_deletion_repository: D, storage: S) -> Self {
         Self {
+            transaction_creator,
             figure_repository,
+            tag_repository,
+            object_deletion_repository,
             storage,
             marker: PhantomData::default(),
         }
     }
+
+    // Delete the figure and its image
+    async fn remove_figure(&self, figure: FigureDTO) -> Result<(), ServerError> {
+        let mut transaction = self.transaction_creator.create().await?;
+        self.figure_repository.delete_figure_by_id(Some(&mut transaction), figure.id).await?;
+        let deletions = queue_object_deletions(&self.storage, &self.object_deletion_repository, &mut transaction, vec![figure.url]).await?;
+        transaction.commit().await?;
+        delete_queued_objects(&self.storage, &self.object_deletion_repository, deletions).await;
+        Ok(())
+    }
+
+    async fn save_new_figure(&self, figure: Figure, tags: Vec<String>) -> Result<Figure, ServerError> {
+        let mut transaction = self.transaction_creator.create().await?;
+        let figure = self.figure_repository.create(Some(&mut transaction), figure).await?;
+        self.tag_repository.replace_figure_tags(Some(&mut transaction), figure.id, tags).await?;
+        transaction.commit().await?;
+        Ok(figure)
+    }
+
+    // The replaced image is queued for deletion along with the update, so it is only removed once nothing points to it
+    async fn save_figure_update(&self, figure: &FigureDTO, tags: Option<Vec<String>>, replaced_url: Option<String>) -> Result<Vec<ObjectDeletion>, ServerError> {
+        let mut transaction = self.transaction_creator.create().await?;
+        self.figure_repository.update_figure(Some(&mut transaction), Figure {
+            id: figure.id,
+            title: figure.title.clone(),
+            description: figure.description.clone(),
+            width: figure.width,
+            height: figure.height,
+            url: figure.url.clone(),
+            profile_id: figure.profile.id,
+        }).await?;
+        let deletions = queue_object_deletions(&self.storage, &self.object_deletion_repository, &mut transaction, replaced_url.into_iter().collect()).await?;
+        if let Some(tags) = tags {
+            self.tag_repository.replace_figure_tags(Some(&mut transaction), figure.id, tags).await?;
+        }
+        transaction.commit().await?;
+        Ok(deletions)
+    }
+
+    // Removes an image uploaded for a figure that failed to be saved, nothing points to it
+    async fn discard_upload(&self, url: String) {
+        let mut transaction = match self.transaction_creator.create().await {
+            Ok(transaction) => transaction,
+            Err(e) => return self.delete_upload(&url, e).await
+        };
+        let deletions = match queue_object_deletions(&self.storage, &self.object_deletion_repository, &mut transaction, vec![url.clone()]).await {
+            Ok(deletions) => deletions,
+            Err(e) => return self.delete_upload(&url, e).await
+        };
+        if let Err(e) = transaction.commit().await {
+            return self.delete_upload(&url, e).await;
+        }
+        delete_queued_objects(&self.storage, &self.object_deletion_repository, deletions).await;
+    }
+
+    // Last resort when the deletion of an upload can't be queued, likely because the database is unavailable
+    async fn delete_upload(&self, url: &str, queue_error: ServerError) {
+        warn!("Failed to queue the deletion of unused upload {}, deleting it right away: {}", url, queue_error);
+        if let Some(name) = self.storage.get_object_name(url) {
+            if let Err(e) = self.storage.delete_object(&name).await {
+                warn!("Failed to delete unused upload {}, it is left behind: {}", url, e);
+            }
+        }
+    }
 }
 
 #[async_trait]
-impl<T, F, S> FigureServiceTrait for FigureService<T, F, S>
-    where T: TransactionTrait, F: FigureRepositoryTrait<T>, S: ContentStore {
+impl<TC, T, F, G, D, S> FigureServiceTrait for FigureService<TC, T, F, G, D, S>
+    where TC: TransactionCreatorTrait<T>, T: TransactionTrait, F: FigureRepositoryTrait<T>,
+          G: TagRepositoryTrait<T>, D: ObjectDeletionRepositoryTrait<T>, S: ContentStore {
     async fn find_figure_by_id(&self, figure_id: IdType) -> Result<FigureDTO, ServerError> {
         self.figure_repository.find_by_id(None, figure_id)
             .await
@@ -41,25 +127,95 @@ impl<T, F, S> FigureServiceTrait for FigureService<T, F, S>
             .map_err(ServerError::from)
     }
 
-    async fn create(&self, title: String, description: Option<String>, image: Bytes, width: u32, height: u32, profile_id: IdType) -> Result<Figure, ServerError> {
+    async fn find_figures_starting_from_id_with_tag(&self, figure_id: Option<IdType>, tag: String, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
+        let tag = normalize_tag(&tag)?;
+        self.figure_repository.find_starting_from_id_with_tag(None, figure_id, tag, limit).await
+    }
+
+    async fn find_popular_tags(&self, limit: i32) -> Result<Vec<TagCount>, ServerError> {
+        self.tag_repository.find_popular(None, limit).await
+    }
+
+    async fn create(&self, title: String, description: Option<String>, tags: Vec<String>, image: (Bytes, u32, u32), profile_id: IdType) -> Result<Figure, ServerError> {
+        let (image, width, height) = image;
         if width > i32::MAX as u32 || height > i32::MAX as u32 {
             return Err(ServerError::ImageDimensionsTooLarge);
         }
+        let tags = normalize_tags(tags)?;
 
         let uid = Uuid::new_v4();
         let uid = uid.to_string();
         let url = self.storage.upload_image(uid.as_str(), image).await?;
-        self.figure_repository.create(None, Figure {
+
+        let result = self.save_new_figure(Figure {
             id: 0,
             title,
             description,
             width: width as i32,
             height: height as i32,
-            url,
+            url: url.clone(),
             profile_id,
-        })
-            .await
-            .map_err(ServerError::from)
+        }, tags).await;
+        if result.is_err() {
+            self.discard_upload(url).await;
+        }
+        result
+    }
+
+    async fn update_figure(&self, figure_id: IdType, profile_id: IdType, title: Option<String>, description: Option<String>, tags: Option<Vec<String>>, image: Option<(Bytes, u32, u32)>) -> Result<FigureDTO, ServerError> {
+        let mut figure = self.figure_repository.find_by_id(None, figure_id).await?;
+        if figure.profile.id != profile_id {
+            return Err(ServerError::Forbidden);
+        }
+        let tags = tags.map(normalize_tags).transpose()?;
+
+        if let Some(title) = title {
+            figure.title = title;
+        }
+        if description.is_some() {
+            figure.description = description;
+        }
+        let mut replaced_url = None;
+        if let Some((image, width, height)) = image {
+            if width > i32::MAX as u32 || height > i32::MAX as u32 {
+                return Err(ServerError::ImageDimensionsTooLarge);
+            }
+            let uid = Uuid::new_v4().to_string();
+            let url = self.storage.upload_image(uid.as_str(), image).await?;
+            replaced_url = Some(std::mem::replace(&mut figure.url, url));
+            figure.width = width as i32;
+            figure.height = height as i32;
+        }
+
+        match self.save_figure_update(&figure, tags.clone(), replaced_url.clone()).await {
+            Ok(deletions) => {
+                delete_queued_objects(&self.storage, &self.object_deletion_repository, deletions).await;
+                if let Some(tags) = tags {
+                    figure.tags = tags;
+                }
+                Ok(figure)
+            }
+            Err(e) => {
+                // Only the upload is removed, the figure still points to the replaced image
+                if replaced_url.is_some() {
+                    self.discard_upload(figure.url).await;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn delete_figure(&self, figure_id: IdType, profile_id: IdType) -> Result<(), ServerError> {
+        let figure = self.figure_repository.find_by_id(None, figure_id).await?;
+        if figure.profile.id != profile_id {
+            return Err(ServerError::Forbidden);
+        }
+        self.remove_figure(figure).await
+    }
+
+    async fn delete_figure_as_moderator(&self, figure_id: IdType) -> Result<(), ServerError> {
+        let figure = self.figure_repository.find_by_id(None, figure_id).await?;
+        self.remove_figure(figure).await
     }
 
     async fn get_total_figures_by_profile(&self, profile_id: IdType) -> Result<IdType, ServerError> {
@@ -73,4 +229,30 @@ impl<T, F, S> FigureServiceTrait for FigureService<T, F, S>
             .await
             .map_err(ServerError::from)
     }
+}
+
+// Tags are stored lowercase without a leading '#', the same tag given twice is only kept once
+fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, ServerError> {
+    let mut normalized_tags: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = normalize_tag(&tag)?;
+        if !normalized_tags.contains(&tag) {
+            normalized_tags.push(tag);
+        }
+    }
+    if normalized_tags.len() > MAX_TAGS_PER_FIGURE {
+        return Err(ServerError::TooManyTags);
+    }
+    normalized_tags.sort();
+    Ok(normalized_tags)
+}
+
+// Lowercase letters and digits, optionally with single dashes in between, 30 character limit
+fn normalize_tag(tag: &str) -> Result<String, ServerError> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix('#').unwrap_or(tag).to_lowercase();
+    if tag.len() > MAX_TAG_LENGTH || !TAG_REGEX.is_match(&tag) {
+        return Err(ServerError::InvalidTag);
+    }
+    Ok(tag)
 }
\ No newline at end of file
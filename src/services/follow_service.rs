@@ -0,0 +1,62 @@
+use std::marker::PhantomData;
+use async_trait::async_trait;
+use crate::entities::dtos::figure_dto::FigureDTO;
+use crate::entities::dtos::profile_dto::{FollowProfileDTO, ProfileWithoutUserIdDTO};
+use crate::entities::types::IdType;
+use crate::repositories::traits::{FollowRepositoryTrait, ProfileRepositoryTrait, TransactionTrait};
+use crate::server_errors::ServerError;
+use crate::services::traits::FollowServiceTrait;
+
+pub struct FollowService<T, P, W> {
+    profile_repository: P,
+    follow_repository: W,
+    marker: PhantomData<T>,
+}
+
+impl<T: TransactionTrait, P: ProfileRepositoryTrait<T>, W: FollowRepositoryTrait<T>> FollowService<T, P, W> {
+    pub fn new(profile_repository: P, follow_repository: W) -> Self {
+        Self {
+            profile_repository,
+            follow_repository,
+            marker: PhantomData::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl<T, P, W> FollowServiceTrait for FollowService<T, P, W>
+    where T: TransactionTrait, P: ProfileRepositoryTrait<T>, W: FollowRepositoryTrait<T> {
+    async fn follow_profile(&self, follower_id: IdType, profile_id: IdType) -> Result<ProfileWithoutUserIdDTO, ServerError> {
+        if follower_id == profile_id {
+            return Err(ServerError::CannotFollowYourself);
+        }
+        // Not found instead of failing on the foreign key
+        self.profile_repository.find_by_id(None, profile_id).await?;
+        self.follow_repository.follow(None, follower_id, profile_id).await?;
+
+        let profile = self.profile_repository.find_by_id(None, profile_id).await?;
+        Ok(ProfileWithoutUserIdDTO::from(profile))
+    }
+
+    async fn unfollow_profile(&self, follower_id: IdType, profile_id: IdType) -> Result<ProfileWithoutUserIdDTO, ServerError> {
+        let mut profile = self.profile_repository.find_by_id(None, profile_id).await?;
+        if self.follow_repository.unfollow(None, follower_id, profile_id).await? {
+            profile.follower_count -= 1;
+        }
+        Ok(ProfileWithoutUserIdDTO::from(profile))
+    }
+
+    async fn find_followers(&self, profile_id: IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError> {
+        self.profile_repository.find_by_id(None, profile_id).await?;
+        self.follow_repository.find_followers_starting_from_id(None, profile_id, follow_id, limit).await
+    }
+
+    async fn find_following(&self, profile_id: IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError> {
+        self.profile_repository.find_by_id(None, profile_id).await?;
+        self.follow_repository.find_following_starting_from_id(None, profile_id, follow_id, limit).await
+    }
+
+    async fn find_feed(&self, profile_id: IdType, figure_id: Option<IdType>, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
+        self.follow_repository.find_feed_starting_from_id(None, profile_id, figure_id, limit).await
+    }
+}
\ No newline at end of file
@@ -0,0 +1,70 @@
+use std::marker::PhantomData;
+use async_trait::async_trait;
+use crate::entities::dtos::figure_dto::{FigureDTO, LikedFigureDTO};
+use crate::entities::types::IdType;
+use crate::repositories::traits::{FigureRepositoryTrait, LikeRepositoryTrait, TransactionTrait};
+use crate::server_errors::ServerError;
+use crate::services::traits::LikeServiceTrait;
+
+pub struct LikeService<T, F, L> {
+    figure_repository: F,
+    like_repository: L,
+    marker: PhantomData<T>,
+}
+
+impl<T: TransactionTrait, F: FigureRepositoryTrait<T>, L: LikeRepositoryTrait<T>> LikeService<T, F, L> {
+    pub fn new(figure_repository: F, like_repository: L) -> Self {
+        Self {
+            figure_repository,
+            like_repository,
+            marker: PhantomData::default(),
+        }
+    }
+
+    async fn mark_liked(&self, viewer_profile_id: Option<IdType>, figures: Vec<&mut FigureDTO>) -> Result<(), ServerError> {
+        let profile_id = match viewer_profile_id {
+            Some(profile_id) if !figures.is_empty() => profile_id,
+            _ => return Ok(())
+        };
+        let figure_ids = figures.iter().map(|figure| figure.id).collect();
+        let liked_figure_ids = self.like_repository.find_liked_figure_ids(None, profile_id, figure_ids).await?;
+        for figure in figures {
+            figure.liked_by_me = liked_figure_ids.contains(&figure.id);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T, F, L> LikeServiceTrait for LikeService<T, F, L>
+    where T: TransactionTrait, F: FigureRepositoryTrait<T>, L: LikeRepositoryTrait<T> {
+    async fn like_figure(&self, figure_id: IdType, profile_id: IdType) -> Result<FigureDTO, ServerError> {
+        // Not found instead of failing on the foreign key
+        self.figure_repository.find_by_id(None, figure_id).await?;
+        self.like_repository.like(None, figure_id, profile_id).await?;
+
+        let mut figure = self.figure_repository.find_by_id(None, figure_id).await?;
+        figure.liked_by_me = true;
+        Ok(figure)
+    }
+
+    async fn unlike_figure(&self, figure_id: IdType, profile_id: IdType) -> Result<FigureDTO, ServerError> {
+        self.like_repository.unlike(None, figure_id, profile_id).await?;
+        self.figure_repository.find_by_id(None, figure_id).await
+    }
+
+    async fn find_liked_figures(&self, profile_id: IdType, like_id: Option<IdType>, limit: i32, viewer_profile_id: Option<IdType>) -> Result<Vec<LikedFigureDTO>, ServerError> {
+        let mut liked_figures = self.figure_repository.find_liked_starting_from_id(None, profile_id, like_id, limit).await?;
+        // A profile viewing its own likes liked every figure in them
+        if viewer_profile_id == Some(profile_id) {
+            liked_figures.iter_mut().for_each(|liked_figure| liked_figure.figure.liked_by_me = true);
+        } else {
+            self.mark_liked(viewer_profile_id, liked_figures.iter_mut().map(|liked_figure| &mut liked_figure.figure).collect()).await?;
+        }
+        Ok(liked_figures)
+    }
+
+    async fn mark_liked_figures(&self, viewer_profile_id: Option<IdType>, figures: &mut [FigureDTO]) -> Result<(), ServerError> {
+        self.mark_liked(viewer_profile_id, figures.iter_mut().collect()).await
+    }
+}
@@ -1,4 +1,14 @@
 pub mod user_service;
 pub mod profile_service;
 pub mod figure_service;
-pub mod traits;
\ No newline at end of file
+pub mod traits;
+pub mod account_mailer;
+pub mod rate_limit_service;
+pub mod access_token_service;
+pub mod oidc_service;
+pub mod account_service;
+pub mod data_export_service;
+pub mod search_service;
+pub mod like_service;
+pub mod comment_service;
+pub mod follow_service;
\ No newline at end of file
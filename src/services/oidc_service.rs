@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use crate::entities::external_identity::ExternalIdentity;
+use crate::entities::oidc_sign_in::OidcSignIn;
+use crate::oidc::IdentityProvider;
+use crate::repositories::traits::OidcSignInRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::traits::OidcServiceTrait;
+use crate::utilities::token::hash_token;
+use crate::utilities::traits::RandomNumberGenerator;
+
+const OIDC_SIGN_IN_EXPIRATION: usize = 600;
+
+#[derive(Clone)]
+pub struct OidcService<L, I, R> {
+    sign_in_repository: L,
+    // None when no provider is configured
+    identity_provider: Option<I>,
+    secure_random_generator: R,
+}
+
+impl<L, I, R> OidcService<L, I, R>
+    where L: OidcSignInRepositoryTrait, I: IdentityProvider, R: RandomNumberGenerator {
+    pub fn new(sign_in_repository: L, identity_provider: Option<I>, secure_random_generator: R) -> Self {
+        OidcService {
+            sign_in_repository,
+            identity_provider,
+            secure_random_generator,
+        }
+    }
+
+    fn identity_provider(&self) -> Result<&I, ServerError> {
+        self.identity_provider.as_ref()
+            .ok_or(ServerError::OidcNotConfigured)
+    }
+}
+
+#[async_trait]
+impl<L, I, R> OidcServiceTrait for OidcService<L, I, R>
+    where L: OidcSignInRepositoryTrait, I: IdentityProvider, R: RandomNumberGenerator {
+    async fn start_sign_in(&self, remember_me: bool) -> Result<(String, String), ServerError> {
+        let identity_provider = self.identity_provider()?;
+        let state = self.secure_random_generator.generate_token()?;
+        let sign_in = OidcSignIn {
+            nonce: self.secure_random_generator.generate_token()?,
+            code_verifier: self.secure_random_generator.generate_token()?,
+            remember_me,
+        };
+
+        // The S256 challenge is the base64url encoded SHA-256 hash of the verifier (RFC 7636)
+        let code_challenge = hash_token(&sign_in.code_verifier);
+        let authorization_url = identity_provider.authorization_url(&state, &sign_in.nonce, &code_challenge).await?;
+        self.sign_in_repository.create(&hash_token(&state), &sign_in, OIDC_SIGN_IN_EXPIRATION).await?;
+        Ok((state, authorization_url))
+    }
+
+    async fn complete_sign_in(&self, state: String, code: String) -> Result<(ExternalIdentity, bool), ServerError> {
+        let identity_provider = self.identity_provider()?;
+        // Taken before redeeming the code, so that every state can only be used once
+        let sign_in = self.sign_in_repository.take(&hash_token(&state)).await?;
+        let identity = identity_provider.exchange_code(&code, &sign_in.code_verifier, &sign_in.nonce).await?;
+        Ok((identity, sign_in.remember_me))
+    }
+}
\ No newline at end of file
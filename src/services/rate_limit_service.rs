@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use crate::entities::rate_limit::RateLimit;
+use crate::repositories::traits::RateLimitRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::traits::RateLimitServiceTrait;
+
+// Failed sign ins from an ip are counted for a day, the account gets locked out for that ip from the threshold on
+const FAILED_SIGN_IN_WINDOW: usize = 86400;
+const LOCKOUT_THRESHOLD: u64 = 5;
+// Doubles with every failure over the threshold, up to the max
+const LOCKOUT_BASE_DURATION: usize = 60;
+const LOCKOUT_MAX_DURATION: usize = 3600;
+
+#[derive(Clone)]
+pub struct RateLimitService<R> {
+    rate_limit_repository: R,
+}
+
+impl<R: RateLimitRepositoryTrait> RateLimitService<R> {
+    pub fn new(rate_limit_repository: R) -> Self {
+        RateLimitService {
+            rate_limit_repository
+        }
+    }
+}
+
+#[async_trait]
+impl<R: RateLimitRepositoryTrait> RateLimitServiceTrait for RateLimitService<R> {
+    async fn hit(&self, scope: &str, key: &str, limit: RateLimit) -> Result<(), ServerError> {
+        let (count, time_until_reset) = self.rate_limit_repository.increment(&format!("{}:{}", scope, key), limit.window).await?;
+        match count > limit.max_requests {
+            true => Err(ServerError::TooManyRequests(time_until_reset.max(1))),
+            false => Ok(())
+        }
+    }
+
+    async fn check_sign_in_lockout(&self, email: &str, ip: &str) -> Result<(), ServerError> {
+        match self.rate_limit_repository.time_to_live(&lockout_key(email, ip)).await? {
+            Some(time_until_unlock) => Err(ServerError::TooManyRequests(time_until_unlock)),
+            None => Ok(())
+        }
+    }
+
+    async fn record_failed_sign_in(&self, email: &str, ip: &str) -> Result<(), ServerError> {
+        let (failures, _) = self.rate_limit_repository.increment(&failed_sign_ins_key(email, ip), FAILED_SIGN_IN_WINDOW).await?;
+        if failures >= LOCKOUT_THRESHOLD {
+            self.rate_limit_repository.set(&lockout_key(email, ip), lockout_duration(failures)).await?;
+        }
+        Ok(())
+    }
+
+    async fn reset_failed_sign_ins(&self, email: &str, ip: &str) -> Result<(), ServerError> {
+        self.rate_limit_repository.remove(&failed_sign_ins_key(email, ip)).await
+    }
+}
+
+fn lockout_duration(failures: u64) -> usize {
+    // Capped shift so that the duration can't overflow
+    let doublings = (failures.saturating_sub(LOCKOUT_THRESHOLD)).min(16) as u32;
+    (LOCKOUT_BASE_DURATION << doublings).min(LOCKOUT_MAX_DURATION)
+}
+
+fn failed_sign_ins_key(email: &str, ip: &str) -> String {
+    format!("failed_sign_ins:{}:{}", email.to_lowercase(), ip)
+}
+
+fn lockout_key(email: &str, ip: &str) -> String {
+    format!("sign_in_lockout:{}:{}", email.to_lowercase(), ip)
+}
\ No newline at end of file
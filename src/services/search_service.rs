@@ -0,0 +1,48 @@
+use std::marker::PhantomData;
+use async_trait::async_trait;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::entities::dtos::figure_dto::FigureDTO;
+use crate::entities::dtos::profile_dto::ProfileWithoutUserIdDTO;
+use crate::repositories::traits::{FigureRepositoryTrait, ProfileRepositoryTrait, TransactionTrait};
+use crate::server_errors::ServerError;
+use crate::services::traits::SearchServiceTrait;
+
+pub struct SearchService<T, F, P> {
+    figure_repository: F,
+    profile_repository: P,
+    marker: PhantomData<T>,
+}
+
+impl<T: TransactionTrait, F: FigureRepositoryTrait<T>, P: ProfileRepositoryTrait<T>> SearchService<T, F, P> {
+    pub fn new(figure_repository: F, profile_repository: P) -> Self {
+        Self {
+            figure_repository,
+            profile_repository,
+            marker: PhantomData::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl<T, F, P> SearchServiceTrait for SearchService<T, F, P>
+    where T: TransactionTrait, F: FigureRepositoryTrait<T>, P: ProfileRepositoryTrait<T> {
+    async fn search_figures(&self, query: String, offset: i64, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
+        let query = validate_query(query)?;
+        self.figure_repository.search(None, query, offset, limit).await
+    }
+
+    async fn search_profiles(&self, query: String, offset: i64, limit: i32) -> Result<Vec<ProfileWithoutUserIdDTO>, ServerError> {
+        let query = validate_query(query)?;
+        let profiles = self.profile_repository.search(None, query, offset, limit).await?;
+        Ok(profiles.into_iter().map(ProfileWithoutUserIdDTO::from).collect())
+    }
+}
+
+// Between 1 and 100 characters, surrounding whitespace is ignored
+fn validate_query(query: String) -> Result<String, ServerError> {
+    let query = query.trim();
+    if !(1..=100).contains(&query.graphemes(true).count()) {
+        return Err(ServerError::InvalidSearchQuery);
+    }
+    Ok(query.to_string())
+}
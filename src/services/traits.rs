@@ -1,17 +1,116 @@
 use async_trait::async_trait;
 use bytes::Bytes;
-use crate::entities::dtos::figure_dto::FigureDTO;
-use crate::entities::dtos::profile_dto::ProfileDTO;
-use crate::entities::dtos::session_dtos::Session;
+use chrono::{DateTime, Utc};
+use crate::entities::access_token::{AccessToken, Scope};
+use crate::entities::dtos::comment_dto::CommentDTO;
+use crate::entities::dtos::figure_dto::{FigureDTO, LikedFigureDTO};
+use crate::entities::dtos::profile_dto::{FollowProfileDTO, ProfileDTO, ProfileWithoutUserIdDTO};
+use crate::entities::dtos::session_dtos::{Authentication, ClientInfo, Session, SessionFromStore, SessionSummary};
+use crate::entities::dtos::two_factor_dtos::TotpEnrolmentDTO;
+use crate::entities::dtos::user_dto::UserWithProfileDTO;
+use crate::entities::external_identity::ExternalIdentity;
 use crate::entities::figure::Figure;
 use crate::entities::profile::Profile;
+use crate::entities::rate_limit::RateLimit;
+use crate::entities::tag::TagCount;
 use crate::entities::types::IdType;
 use crate::server_errors::ServerError;
 
 #[async_trait]
 pub trait UserServiceTrait: Send + Sync {
-    async fn signup_user(&self, email: String, password: String, username: String) -> Result<(ProfileDTO, Session), ServerError>;
-    async fn authenticate_user(&self, email: String, password: String) -> Result<(ProfileDTO, Session), ServerError>;
+    async fn signup_user(&self, email: String, password: String, username: String, client: ClientInfo) -> Result<(ProfileDTO, Session), ServerError>;
+    // Users with two factor authentication get a token to complete the sign in with instead of a session
+    async fn authenticate_user(&self, email: String, password: String, client: ClientInfo, remember_me: bool) -> Result<Authentication, ServerError>;
+    // The code is a TOTP code or one of the recovery codes, the token is used up even if the code is wrong.
+    // Also returns the email of the user, to reset the failed sign ins with
+    async fn complete_two_factor_sign_in(&self, token: String, code: String, client: ClientInfo, remember_me: bool) -> Result<(ProfileDTO, Session, String), ServerError>;
+    // Two factor authentication isn't required until the enrolment is confirmed with a code
+    async fn start_totp_enrolment(&self, user_id: IdType, password: String) -> Result<TotpEnrolmentDTO, ServerError>;
+    // Returns the recovery codes, only their hashes are stored
+    async fn confirm_totp_enrolment(&self, user_id: IdType, code: String) -> Result<Vec<String>, ServerError>;
+    async fn disable_totp(&self, user_id: IdType, password: String) -> Result<(), ServerError>;
+    // Signs in the user the identity is linked to. An unlinked identity is linked to the user with the same email
+    // if both sides verified it, otherwise a new user and profile are created for it.
+    async fn sign_in_with_external_identity(&self, identity: ExternalIdentity, client: ClientInfo, remember_me: bool) -> Result<Authentication, ServerError>;
+    // Returns a new session, every other session of the user is removed
+    // The new session keeps the remember me of the current one
+    async fn change_password(&self, user_id: IdType, current_password: String, new_password: String, client: ClientInfo, remember_me: bool) -> Result<Session, ServerError>;
+    // Extends the session by its policy, sessions past their max lifetime are removed
+    async fn find_session(&self, session_id: String) -> Result<Session, ServerError>;
+    // Most recently used sessions first
+    async fn find_sessions(&self, user_id: IdType) -> Result<Vec<SessionSummary>, ServerError>;
+    async fn revoke_session(&self, user_id: IdType, public_session_id: String) -> Result<(), ServerError>;
+    async fn revoke_other_sessions(&self, user_id: IdType, current_session_id: String) -> Result<(), ServerError>;
+    // Mails a reset link if a user with the email exists, doesn't tell the caller if one does
+    async fn request_password_reset(&self, email: String) -> Result<(), ServerError>;
+    async fn reset_password(&self, token: String, new_password: String) -> Result<(), ServerError>;
+    async fn send_email_verification(&self, user_id: IdType) -> Result<(), ServerError>;
+    async fn verify_email(&self, token: String) -> Result<(), ServerError>;
+    async fn is_email_verified(&self, user_id: IdType) -> Result<bool, ServerError>;
+    async fn find_user_by_id(&self, user_id: IdType) -> Result<UserWithProfileDTO, ServerError>;
+    async fn find_users_starting_from_id(&self, user_id: Option<IdType>, search: Option<String>, limit: i32) -> Result<Vec<UserWithProfileDTO>, ServerError>;
+    // Suspending also removes all sessions of the user
+    async fn set_user_suspended(&self, user_id: IdType, suspended: bool) -> Result<(), ServerError>;
+    async fn invalidate_user_sessions(&self, user_id: IdType) -> Result<(), ServerError>;
+}
+
+#[async_trait]
+pub trait AccountServiceTrait: Send + Sync {
+    // Removes the user along with the profile, figures and images for good, and every session of the user.
+    // Confirmed with the password, or for users with a linked identity by a session that signed in moments ago
+    async fn delete_account(&self, user_id: IdType, password: Option<String>, signed_in_at: Option<DateTime<Utc>>) -> Result<(), ServerError>;
+}
+
+#[async_trait]
+pub trait DataExportServiceTrait: Send + Sync {
+    // Builds an archive of everything stored about the user and mails a link to download it
+    async fn export_data(&self, user_id: IdType) -> Result<(), ServerError>;
+    // The link can be used once, it is exchanged for a short lived url to download the archive from the store
+    async fn take_data_export(&self, token: String) -> Result<String, ServerError>;
+}
+
+#[async_trait]
+pub trait LikeServiceTrait: Send + Sync {
+    // Both return the figure with its new like count
+    async fn like_figure(&self, figure_id: IdType, profile_id: IdType) -> Result<FigureDTO, ServerError>;
+    async fn unlike_figure(&self, figure_id: IdType, profile_id: IdType) -> Result<FigureDTO, ServerError>;
+    // Figures liked by the profile, most recently liked first, marked for the viewing profile
+    async fn find_liked_figures(&self, profile_id: IdType, like_id: Option<IdType>, limit: i32, viewer_profile_id: Option<IdType>) -> Result<Vec<LikedFigureDTO>, ServerError>;
+    // Sets liked_by_me of the figures the viewing profile liked, nothing to do without a profile
+    async fn mark_liked_figures(&self, viewer_profile_id: Option<IdType>, figures: &mut [FigureDTO]) -> Result<(), ServerError>;
+}
+
+#[async_trait]
+pub trait FollowServiceTrait: Send + Sync {
+    // Both return the followed profile with its new follower count
+    async fn follow_profile(&self, follower_id: IdType, profile_id: IdType) -> Result<ProfileWithoutUserIdDTO, ServerError>;
+    async fn unfollow_profile(&self, follower_id: IdType, profile_id: IdType) -> Result<ProfileWithoutUserIdDTO, ServerError>;
+    // Both most recently followed first
+    async fn find_followers(&self, profile_id: IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError>;
+    async fn find_following(&self, profile_id: IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError>;
+    // Figures of the profiles the profile follows, newest first
+    async fn find_feed(&self, profile_id: IdType, figure_id: Option<IdType>, limit: i32) -> Result<Vec<FigureDTO>, ServerError>;
+}
+
+#[async_trait]
+pub trait CommentServiceTrait: Send + Sync {
+    // Replying to a reply adds to the thread of the comment it replies to, threads are one level deep
+    async fn create_comment(&self, figure_id: IdType, profile_id: IdType, parent_id: Option<IdType>, content: String) -> Result<CommentDTO, ServerError>;
+    async fn find_comments(&self, figure_id: IdType, comment_id: Option<IdType>, limit: i32) -> Result<Vec<CommentDTO>, ServerError>;
+    async fn find_replies(&self, parent_id: IdType, comment_id: Option<IdType>, limit: i32) -> Result<Vec<CommentDTO>, ServerError>;
+    // Only the author can edit a comment
+    async fn update_comment(&self, comment_id: IdType, profile_id: IdType, content: String) -> Result<CommentDTO, ServerError>;
+    // The author and the owner of the figure can delete a comment
+    async fn delete_comment(&self, comment_id: IdType, profile_id: IdType) -> Result<(), ServerError>;
+    // Deletes any comment, only for moderators
+    async fn delete_comment_as_moderator(&self, comment_id: IdType) -> Result<(), ServerError>;
+}
+
+#[async_trait]
+pub trait SearchServiceTrait: Send + Sync {
+    // Results are ranked by relevance, the offset is the number of results to skip
+    async fn search_figures(&self, query: String, offset: i64, limit: i32) -> Result<Vec<FigureDTO>, ServerError>;
+    async fn search_profiles(&self, query: String, offset: i64, limit: i32) -> Result<Vec<ProfileWithoutUserIdDTO>, ServerError>;
 }
 
 #[async_trait]
@@ -25,7 +124,45 @@ pub trait ProfileServiceTrait: Send + Sync {
 pub trait FigureServiceTrait: Send + Sync {
     async fn find_figure_by_id(&self, figure_id: IdType) -> Result<FigureDTO, ServerError>;
     async fn find_figures_starting_from_id_with_profile_id(&self, figure_id: Option<IdType>, profile_id: Option<IdType>, limit: i32) -> Result<Vec<FigureDTO>, ServerError>;
-    async fn create(&self, title: String, description: Option<String>, image: Bytes, width: u32, height: u32, profile_id: IdType) -> Result<Figure, ServerError>;
+    async fn find_figures_starting_from_id_with_tag(&self, figure_id: Option<IdType>, tag: String, limit: i32) -> Result<Vec<FigureDTO>, ServerError>;
+    async fn find_popular_tags(&self, limit: i32) -> Result<Vec<TagCount>, ServerError>;
+    // The image is given with its width and height
+    async fn create(&self, title: String, description: Option<String>, tags: Vec<String>, image: (Bytes, u32, u32), profile_id: IdType) -> Result<Figure, ServerError>;
+    // Tags are only replaced when given, an empty list removes them
+    async fn update_figure(&self, figure_id: IdType, profile_id: IdType, title: Option<String>, description: Option<String>, tags: Option<Vec<String>>, image: Option<(Bytes, u32, u32)>) -> Result<FigureDTO, ServerError>;
+    async fn delete_figure(&self, figure_id: IdType, profile_id: IdType) -> Result<(), ServerError>;
+    // Delete any figure regardless of its owner, callers must check the role of the session
+    async fn delete_figure_as_moderator(&self, figure_id: IdType) -> Result<(), ServerError>;
     async fn get_total_figures_by_profile(&self, figure_id: IdType) -> Result<IdType, ServerError>;
     async fn get_total_figures_count(&self) -> Result<IdType, ServerError>;
+}
+
+#[async_trait]
+pub trait RateLimitServiceTrait: Send + Sync {
+    // Counts a request of the client identified by key, errors with TooManyRequests once over the limit
+    async fn hit(&self, scope: &str, key: &str, limit: RateLimit) -> Result<(), ServerError>;
+    // Errors with TooManyRequests while sign ins to the account from the ip are locked out.
+    // Locked out per ip so that nobody can lock the owner out, guesses spread over many ips are left to the rate limit per account.
+    async fn check_sign_in_lockout(&self, email: &str, ip: &str) -> Result<(), ServerError>;
+    // Every failed sign in over the threshold locks the account out for longer
+    async fn record_failed_sign_in(&self, email: &str, ip: &str) -> Result<(), ServerError>;
+    async fn reset_failed_sign_ins(&self, email: &str, ip: &str) -> Result<(), ServerError>;
+}
+
+#[async_trait]
+pub trait AccessTokenServiceTrait: Send + Sync {
+    // Returns the token along with what is stored of it, the token itself can't be retrieved later
+    async fn create_access_token(&self, user_id: IdType, name: String, scopes: Vec<Scope>) -> Result<(String, AccessToken), ServerError>;
+    async fn find_access_tokens(&self, user_id: IdType) -> Result<Vec<AccessToken>, ServerError>;
+    async fn revoke_access_token(&self, user_id: IdType, access_token_id: IdType) -> Result<(), ServerError>;
+    // Session for a request authenticated with the token, limited to the scopes of the token
+    async fn find_session_by_token(&self, token: String) -> Result<SessionFromStore, ServerError>;
+}
+
+#[async_trait]
+pub trait OidcServiceTrait: Send + Sync {
+    // Returns the state to bind to the browser and the url of the provider to redirect it to
+    async fn start_sign_in(&self, remember_me: bool) -> Result<(String, String), ServerError>;
+    // Completes the sign in started with the state, returns the identity and whether to remember the session
+    async fn complete_sign_in(&self, state: String, code: String) -> Result<(ExternalIdentity, bool), ServerError>;
 }
\ No newline at end of file
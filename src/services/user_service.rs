@@ -1,21 +1,30 @@
 use std::marker::PhantomData;
 use std::sync::Arc;
 use async_trait::async_trait;
-use argon2::{Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier};
-use argon2::Algorithm::Argon2id;
-use argon2::password_hash::SaltString;
+use chrono::{Duration, Utc};
 
 use lazy_static::lazy_static;
 use regex::Regex;
 use unicode_segmentation::UnicodeSegmentation;
 use crate::server_errors::ServerError;
-use rand_core::OsRng;
 use crate::entities::dtos::profile_dto::ProfileDTO;
-use crate::entities::dtos::session_dtos::Session;
-use crate::repositories::traits::{ProfileRepositoryTrait, SessionRepositoryTrait, TransactionCreatorTrait, TransactionTrait, UserRepositoryTrait};
+use crate::entities::dtos::session_dtos::{Authentication, ClientInfo, Session, SessionPolicies, SessionSummary};
+use crate::entities::dtos::two_factor_dtos::TotpEnrolmentDTO;
+use crate::entities::dtos::user_dto::UserWithProfileDTO;
+use crate::entities::external_identity::ExternalIdentity;
+use crate::entities::types::IdType;
+use crate::entities::user::{User, UserAndProfileFromQuery};
+use crate::entities::one_time_token::OneTimeTokenKind;
+use crate::mailer::Mailer;
+use crate::repositories::traits::{OneTimeTokenRepositoryTrait, ProfileRepositoryTrait, SessionRepositoryTrait, TransactionCreatorTrait, TransactionTrait, UserRepositoryTrait};
+use crate::services::account_mailer::AccountMailer;
 use crate::services::traits::UserServiceTrait;
+use crate::utilities::password::{self, PasswordHashPolicy};
+use crate::utilities::token::hash_token;
+use crate::utilities::totp;
 use crate::utilities::traits::RandomNumberGenerator;
 use interpol::format as iformat;
+use tracing::warn;
 
 lazy_static! {
     static ref EMAIL_REGEX: Regex =
@@ -24,44 +33,242 @@ lazy_static! {
     Regex::new("^[a-zA-Z0-9]+-*[a-zA-Z0-9]+?$").unwrap();
 }
 
+const PASSWORD_RESET_TOKEN_EXPIRATION: usize = 3600;
+const EMAIL_VERIFICATION_TOKEN_EXPIRATION: usize = 86400;
+const LAST_SEEN_UPDATE_INTERVAL_MINUTES: i64 = 5;
+const TWO_FACTOR_SIGN_IN_TOKEN_EXPIRATION: usize = 300;
+const TOTP_ISSUER: &str = "Figure";
+// 160 bits, the length RFC 4226 recommends for HMAC-SHA1
+const TOTP_SECRET_BYTES: usize = 20;
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_BYTES: usize = 10;
+// Leaves room for a dash and four digits within the 15 character limit
+const EXTERNAL_USERNAME_BASE_LENGTH: usize = 10;
+const EXTERNAL_USERNAME_ATTEMPTS: usize = 5;
+
 #[derive(Clone)]
-pub struct UserService<TC, T, U, P, S, R> {
+pub struct UserService<TC, T, U, P, S, O, M, R> {
     transaction_creator: TC,
     marker: PhantomData<T>,
     user_repository: U,
     profile_repository: P,
     session_repository: S,
+    one_time_token_repository: O,
+    account_mailer: AccountMailer<M>,
     secure_random_generator: R,
-
+    session_policies: SessionPolicies,
+    password_hash_policy: PasswordHashPolicy,
 }
 
-impl<TC, T, U, P, S, R> UserService<TC, T, U, P, S, R>
+impl<TC, T, U, P, S, O, M, R> UserService<TC, T, U, P, S, O, M, R>
     where
         TC: TransactionCreatorTrait<T>,
         T: TransactionTrait,
         U: UserRepositoryTrait<T>,
         P: ProfileRepositoryTrait<T>,
         S: SessionRepositoryTrait,
+        O: OneTimeTokenRepositoryTrait,
+        M: Mailer,
         R: RandomNumberGenerator,
 {
-    pub fn new(transaction_creator: TC, user_repository: U, profile_repository: P, session_repository: S, secure_random_generator: R) -> Self {
+    pub fn new(transaction_creator: TC, user_repository: U, profile_repository: P, session_repository: S, one_time_token_repository: O, account_mailer: AccountMailer<M>, secure_random_generator: R) -> Self {
         UserService {
             user_repository,
             profile_repository,
             session_repository,
+            one_time_token_repository,
+            account_mailer,
             transaction_creator,
             secure_random_generator,
+            session_policies: SessionPolicies::default(),
+            password_hash_policy: PasswordHashPolicy::default(),
             marker: PhantomData::default(),
         }
     }
+
+    pub fn with_session_policies(mut self, session_policies: SessionPolicies) -> Self {
+        self.session_policies = session_policies;
+        self
+    }
+
+    pub fn with_password_hash_policy(mut self, password_hash_policy: PasswordHashPolicy) -> Self {
+        self.password_hash_policy = password_hash_policy;
+        self
+    }
+
+    async fn hash_password(&self, password: &str) -> Result<String, ServerError> {
+        let password_length = password.graphemes(true).count();
+        if password_length < 8 {
+            return Err(ServerError::PasswordTooShort);
+        }
+        if password_length > 60 {
+            return Err(ServerError::PasswordTooLong);
+        }
+        password::hash_password(password.to_string(), self.password_hash_policy).await
+    }
+
+    // Replaces a hash made with a lower cost than the current policy, needs the password the user just signed in with
+    async fn upgrade_password_hash(&self, user: &User, password: String) {
+        if !self.password_hash_policy.needs_rehash(&user.password) {
+            return;
+        }
+        let result = match password::hash_password(password, self.password_hash_policy).await {
+            Ok(password_hash) => self.user_repository.update_password(None, user.id, password_hash).await,
+            Err(e) => Err(e)
+        };
+        if let Err(e) = result {
+            warn!("Failed to upgrade the password hash of user (id: {}): {}", user.id, e);
+        }
+    }
+
+    async fn create_session(&self, user: &User, profile_id: IdType, client: ClientInfo, persistent: bool) -> Result<Session, ServerError> {
+        let policy = self.session_policies.for_session(persistent);
+        let session = Session::new(
+            self.secure_random_generator.generate_token()?,
+            user.id,
+            profile_id,
+            user.role,
+            client,
+            Some(policy.idle_timeout.min(policy.max_lifetime)),
+        );
+        let expires_at = session.get_created_at() + Duration::seconds(policy.max_lifetime as i64);
+        self.session_repository.create(session.with_expiry(persistent, Some(expires_at))).await
+    }
+
+    // Users with two factor authentication get a token to complete the sign in with instead of a session
+    async fn sign_in(&self, user: &User, client: ClientInfo, remember_me: bool) -> Result<Authentication, ServerError> {
+        if user.is_two_factor_enabled() {
+            let token = self.secure_random_generator.generate_token()?;
+            self.one_time_token_repository.create(OneTimeTokenKind::TwoFactorSignIn, &hash_token(&token), user.id, TWO_FACTOR_SIGN_IN_TOKEN_EXPIRATION).await?;
+            return Ok(Authentication::TwoFactorRequired(token));
+        }
+        let profile = match self.profile_repository.find_by_user_id(None, user.id).await {
+            Ok(profile) => profile,
+            Err(e) => return Err(ServerError::InternalError(Arc::new(anyhow::Error::from(e)
+                .context(iformat!("Profile associated with user (id: {user.id}) not found."))))),
+        };
+
+        let session = self.create_session(user, profile.id, client, remember_me).await?;
+        Ok(Authentication::SignedIn(ProfileDTO::from(profile), session))
+    }
+
+    // Links the identity to the user with the same email, or creates a user and profile for it
+    async fn link_external_identity(&self, identity: ExternalIdentity) -> Result<User, ServerError> {
+        let email = match &identity.email {
+            Some(email) if is_email_valid(email) => email.to_lowercase(),
+            _ => return Err(ServerError::InvalidEmail)
+        };
+
+        // A password nobody knows, one can be set with a password reset.
+        // Hashed before the transaction is opened, the same as at sign up, even if it ends up unused
+        let password_hash = password::hash_password(self.secure_random_generator.generate_token()?, self.password_hash_policy).await?;
+
+        let mut transaction = self.transaction_creator.create().await?;
+        let (user, created) = match self.user_repository.find_one_by_email(Some(&mut transaction), email.clone()).await {
+            // Both sides have to have verified the email,
+            // otherwise whoever used the email first on either side could take over the account on the other
+            Ok(user) if identity.email_verified && user.verified_at.is_some() => (user, false),
+            Ok(_user) => return Err(ServerError::EmailAlreadyInUse),
+            Err(ServerError::ResourceNotFound) => {
+                let user = self.user_repository.create(Some(&mut transaction), email.clone(), password_hash).await?;
+                let username = self.available_username(&mut transaction, &identity, &email).await?;
+                self.profile_repository.create(Some(&mut transaction), username, user.id).await?;
+                if identity.email_verified {
+                    self.user_repository.update_verified_at(Some(&mut transaction), user.id).await?;
+                }
+                (user, true)
+            }
+            Err(e) => return Err(e)
+        };
+        self.user_repository.create_external_identity(Some(&mut transaction), user.id, identity.issuer, identity.subject).await?;
+        transaction.commit().await?;
+
+        if created && !identity.email_verified {
+            if let Err(e) = self.send_verification_token(&user).await {
+                warn!("Failed to send verification mail to user (id: {}): {}", user.id, e);
+            }
+        }
+        Ok(user)
+    }
+
+    // Username from the name at the provider or the email, with digits added when it is taken
+    async fn available_username(&self, transaction: &mut T, identity: &ExternalIdentity, email: &str) -> Result<String, ServerError> {
+        let name = match &identity.preferred_username {
+            Some(preferred_username) => preferred_username.as_str(),
+            None => email.split('@').next().unwrap_or_default()
+        };
+        let mut base: String = name.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .take(EXTERNAL_USERNAME_BASE_LENGTH)
+            .collect();
+        if base.len() < 3 {
+            base = "user".to_string();
+        }
+
+        let mut username = base.clone();
+        for _ in 0..EXTERNAL_USERNAME_ATTEMPTS {
+            match self.profile_repository.find_by_username(Some(&mut *transaction), &username).await {
+                Err(ServerError::ResourceNotFound) => return Ok(username),
+                Err(e) => return Err(e),
+                Ok(_profile) => {
+                    let mut bytes = [0u8; 2];
+                    self.secure_random_generator.fill_bytes(&mut bytes)?;
+                    username = format!("{}-{:04}", base, u16::from_be_bytes(bytes) % 10000);
+                }
+            }
+        }
+        Err(ServerError::UsernameAlreadyTaken)
+    }
+
+    // Accepts a TOTP code once, or a recovery code which is removed
+    async fn verify_second_factor(&self, user: &User, code: &str) -> Result<(), ServerError> {
+        if !totp::is_code_format(code) {
+            return self.user_repository.remove_recovery_code(None, user.id, &hash_token(&normalize_recovery_code(code))).await
+                .map_err(|e| match e {
+                    ServerError::ResourceNotFound => ServerError::InvalidTwoFactorCode,
+                    e => e
+                });
+        }
+
+        let secret = match user.totp_secret.as_deref().and_then(totp::base32_decode) {
+            Some(secret) => secret,
+            None => return Err(ServerError::TwoFactorNotEnabled)
+        };
+        let step = match totp::verify_code(&secret, code, totp::time_step(Utc::now().timestamp()))? {
+            Some(step) => step,
+            None => return Err(ServerError::InvalidTwoFactorCode)
+        };
+        self.user_repository.update_totp_last_used_step(None, user.id, step).await
+            .map_err(|e| match e {
+                ServerError::ResourceNotFound => ServerError::InvalidTwoFactorCode,
+                e => e
+            })
+    }
+
+    // Formatted in groups of four so they are easier to write down
+    fn generate_recovery_code(&self) -> Result<String, ServerError> {
+        let mut bytes = [0u8; RECOVERY_CODE_BYTES];
+        self.secure_random_generator.fill_bytes(&mut bytes)?;
+        let code = totp::base32_encode(&bytes).to_lowercase();
+        let groups: Vec<&str> = code.as_bytes().chunks(4)
+            .map(|group| std::str::from_utf8(group).unwrap_or_default())
+            .collect();
+        Ok(groups.join("-"))
+    }
+
+    async fn send_verification_token(&self, user: &User) -> Result<(), ServerError> {
+        let token = self.secure_random_generator.generate_token()?;
+        self.one_time_token_repository.create(OneTimeTokenKind::EmailVerification, &hash_token(&token), user.id, EMAIL_VERIFICATION_TOKEN_EXPIRATION).await?;
+        self.account_mailer.send_email_verification(&user.email, &token, EMAIL_VERIFICATION_TOKEN_EXPIRATION / 3600).await
+    }
 }
 
 #[async_trait]
-impl<TC, T, U, P, S, R> UserServiceTrait for UserService<TC, T, U, P, S, R>
+impl<TC, T, U, P, S, O, M, R> UserServiceTrait for UserService<TC, T, U, P, S, O, M, R>
     where TC: TransactionCreatorTrait<T>, T: TransactionTrait,
           U: UserRepositoryTrait<T>, P: ProfileRepositoryTrait<T>, S: SessionRepositoryTrait,
-          R: RandomNumberGenerator {
-    async fn signup_user(&self, email: String, password: String, username: String) -> Result<(ProfileDTO, Session), ServerError> {
+          O: OneTimeTokenRepositoryTrait, M: Mailer, R: RandomNumberGenerator {
+    async fn signup_user(&self, email: String, password: String, username: String, client: ClientInfo) -> Result<(ProfileDTO, Session), ServerError> {
         if !is_email_valid(&email) {
             return Err(ServerError::InvalidEmail);
         }
@@ -69,51 +276,252 @@ impl<TC, T, U, P, S, R> UserServiceTrait for UserService<TC, T, U, P, S, R>
             return Err(ServerError::InvalidUsername);
         }
 
-        let password_hash_result = hash_password(&password, true);
-        let password_hash = match password_hash_result {
-            Ok(hash) => hash,
-            Err(e) => return Err(e)
-        };
+        let password_hash = self.hash_password(&password).await?;
 
         let mut transaction = self.transaction_creator.create().await?;
         let user = self.user_repository.create(Some(&mut transaction), email, password_hash).await?;
         let profile = self.profile_repository.create(Some(&mut transaction), username, user.id).await?;
         transaction.commit().await?;
 
-        let session = Session::new(
-            self.secure_random_generator.generate()?.to_string(),
-            user.id,
-            profile.id,
-            Some(86400),
-        );
+        let session = self.create_session(&user, profile.id, client, false).await?;
 
-        let session = self.session_repository.create(session).await?;
+        // The account is usable without verifying, a new mail can be requested if this one doesn't arrive
+        if let Err(e) = self.send_verification_token(&user).await {
+            warn!("Failed to send verification mail to user (id: {}): {}", user.id, e);
+        }
         Ok((ProfileDTO::from(profile), session))
     }
 
-    async fn authenticate_user(&self, email: String, password: String) -> Result<(ProfileDTO, Session), ServerError> {
+    async fn authenticate_user(&self, email: String, password: String, client: ClientInfo, remember_me: bool) -> Result<Authentication, ServerError> {
         let user = match self.user_repository.find_one_by_email(None, email).await {
             Ok(user) => user,
             Err(_e) => return Err(ServerError::UserWithEmailNotFound),
         };
 
-        let parsed_hash = match PasswordHash::new(&user.password) {
-            Ok(hash) => hash,
-            Err(e) => {
-                return Err(ServerError::InternalError(Arc::new(e.into())));
-            }
+        password::verify_password(password.clone(), user.password.clone()).await?;
+        if user.suspended {
+            return Err(ServerError::AccountSuspended);
+        }
+        self.upgrade_password_hash(&user, password).await;
+        self.sign_in(&user, client, remember_me).await
+    }
+
+    async fn complete_two_factor_sign_in(&self, token: String, code: String, client: ClientInfo, remember_me: bool) -> Result<(ProfileDTO, Session, String), ServerError> {
+        // Taken before checking the code, so every guess needs the password again
+        let user_id = self.one_time_token_repository.take(OneTimeTokenKind::TwoFactorSignIn, &hash_token(&token)).await?;
+        let user = self.user_repository.find_one_by_id(None, user_id).await?;
+        if user.suspended {
+            return Err(ServerError::AccountSuspended);
+        }
+        if !user.is_two_factor_enabled() {
+            return Err(ServerError::TwoFactorNotEnabled);
+        }
+        self.verify_second_factor(&user, code.trim()).await?;
+
+        let profile = self.profile_repository.find_by_user_id(None, user.id).await?;
+        let session = self.create_session(&user, profile.id, client, remember_me).await?;
+        Ok((ProfileDTO::from(profile), session, user.email))
+    }
+
+    async fn start_totp_enrolment(&self, user_id: IdType, password: String) -> Result<TotpEnrolmentDTO, ServerError> {
+        let user = self.user_repository.find_one_by_id(None, user_id).await?;
+        password::verify_password(password, user.password.clone()).await?;
+        if user.is_two_factor_enabled() {
+            return Err(ServerError::TwoFactorAlreadyEnabled);
+        }
+
+        let mut secret_bytes = [0u8; TOTP_SECRET_BYTES];
+        self.secure_random_generator.fill_bytes(&mut secret_bytes)?;
+        let secret = totp::base32_encode(&secret_bytes);
+        self.user_repository.update_totp_secret(None, user.id, Some(secret.clone())).await?;
+
+        let otpauth_uri = totp::otpauth_uri(TOTP_ISSUER, &user.email, &secret)?;
+        Ok(TotpEnrolmentDTO { secret, otpauth_uri })
+    }
+
+    async fn confirm_totp_enrolment(&self, user_id: IdType, code: String) -> Result<Vec<String>, ServerError> {
+        let user = self.user_repository.find_one_by_id(None, user_id).await?;
+        if user.is_two_factor_enabled() {
+            return Err(ServerError::TwoFactorAlreadyEnabled);
+        }
+        let secret = match user.totp_secret.as_deref().and_then(totp::base32_decode) {
+            Some(secret) => secret,
+            None => return Err(ServerError::TwoFactorNotStarted)
+        };
+        let step = match totp::verify_code(&secret, code.trim(), totp::time_step(Utc::now().timestamp()))? {
+            Some(step) => step,
+            None => return Err(ServerError::InvalidTwoFactorCode)
         };
-        if Argon2::default().verify_password(password.as_bytes(), &parsed_hash).is_err() {
-            return Err(ServerError::WrongPassword);
+
+        let mut recovery_codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+        for _ in 0..RECOVERY_CODE_COUNT {
+            recovery_codes.push(self.generate_recovery_code()?);
         }
-        let profile = match self.profile_repository.find_by_user_id(None, user.id).await {
-            Ok(profile) => profile,
-            Err(e) => return Err(ServerError::InternalError(Arc::new(anyhow::Error::from(e)
-                .context(iformat!("Profile associated with user (id: {user.id}) not found."))))),
+        let code_hashes = recovery_codes.iter()
+            .map(|code| hash_token(&normalize_recovery_code(code)))
+            .collect();
+
+        let mut transaction = self.transaction_creator.create().await?;
+        self.user_repository.enable_totp(Some(&mut transaction), user.id, step).await?;
+        self.user_repository.replace_recovery_codes(Some(&mut transaction), user.id, code_hashes).await?;
+        transaction.commit().await?;
+        Ok(recovery_codes)
+    }
+
+    async fn disable_totp(&self, user_id: IdType, password: String) -> Result<(), ServerError> {
+        let user = self.user_repository.find_one_by_id(None, user_id).await?;
+        password::verify_password(password, user.password.clone()).await?;
+        if !user.is_two_factor_enabled() {
+            return Err(ServerError::TwoFactorNotEnabled);
+        }
+
+        let mut transaction = self.transaction_creator.create().await?;
+        self.user_repository.update_totp_secret(Some(&mut transaction), user.id, None).await?;
+        self.user_repository.replace_recovery_codes(Some(&mut transaction), user.id, Vec::new()).await?;
+        transaction.commit().await
+    }
+
+    async fn sign_in_with_external_identity(&self, identity: ExternalIdentity, client: ClientInfo, remember_me: bool) -> Result<Authentication, ServerError> {
+        let user = match self.user_repository.find_one_by_external_identity(None, &identity.issuer, &identity.subject).await {
+            Ok(user) => user,
+            Err(ServerError::ResourceNotFound) => self.link_external_identity(identity).await?,
+            Err(e) => return Err(e)
         };
+        if user.suspended {
+            return Err(ServerError::AccountSuspended);
+        }
+        self.sign_in(&user, client, remember_me).await
+    }
 
-        let session = self.session_repository.create(Session::new(self.secure_random_generator.generate()?.to_string(), user.id, profile.id, Some(86400))).await?;
-        Ok((ProfileDTO::from(profile), session))
+    async fn change_password(&self, user_id: IdType, current_password: String, new_password: String, client: ClientInfo, remember_me: bool) -> Result<Session, ServerError> {
+        let user = self.user_repository.find_one_by_id(None, user_id).await?;
+        password::verify_password(current_password, user.password.clone()).await?;
+
+        let password_hash = self.hash_password(&new_password).await?;
+        self.user_repository.update_password(None, user.id, password_hash).await?;
+
+        // Sessions could have been created by someone who knew the old password, including the current one
+        self.session_repository.remove_all_by_user_id(user.id).await?;
+        let profile = self.profile_repository.find_by_user_id(None, user.id).await?;
+        self.create_session(&user, profile.id, client, remember_me).await
+    }
+
+    async fn find_session(&self, session_id: String) -> Result<Session, ServerError> {
+        let mut session = self.session_repository.find_and_extend(&session_id, self.session_policies).await?;
+        let policy = self.session_policies.for_session(session.is_persistent());
+        // Sessions from before sessions had a max lifetime are limited by their creation time
+        let expires_at = session.get_expires_at()
+            .unwrap_or_else(|| session.get_created_at() + Duration::seconds(policy.max_lifetime as i64));
+
+        let now = Utc::now();
+        match policy.time_until_expiration(expires_at, now) {
+            // Already extended by the whole idle timeout, only shortened close to the max lifetime
+            Some(time_until_expiration) if time_until_expiration < policy.idle_timeout => {
+                self.session_repository.update_expiration(&session_id, time_until_expiration).await?;
+            }
+            Some(_) => {}
+            None => {
+                self.session_repository.remove_by_id(&session_id).await?;
+                return Err(ServerError::ResourceNotFound);
+            }
+        }
+
+        // Only written once in a while, so not every request writes the whole session
+        if now - session.get_last_seen_at() > Duration::minutes(LAST_SEEN_UPDATE_INTERVAL_MINUTES) {
+            session.set_last_seen_at(now);
+            if let Err(e) = self.session_repository.update_last_seen(&session).await {
+                warn!("Failed to update last seen of session: {}", e);
+            }
+        }
+        Ok(session)
+    }
+
+    async fn find_sessions(&self, user_id: IdType) -> Result<Vec<SessionSummary>, ServerError> {
+        let mut sessions = self.session_repository.find_all_by_user_id(user_id).await?;
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.last_seen_at));
+        Ok(sessions)
+    }
+
+    async fn revoke_session(&self, user_id: IdType, public_session_id: String) -> Result<(), ServerError> {
+        // Only sessions of the user are searched, so other users' sessions can't be revoked
+        let sessions = self.session_repository.find_all_by_user_id(user_id).await?;
+        match sessions.iter().any(|session| session.public_id == public_session_id) {
+            true => self.session_repository.remove_by_public_id(&public_session_id).await,
+            false => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn revoke_other_sessions(&self, user_id: IdType, current_session_id: String) -> Result<(), ServerError> {
+        let current_public_id = hash_token(&current_session_id);
+        let sessions = self.session_repository.find_all_by_user_id(user_id).await?;
+        for session in sessions.iter().filter(|session| session.public_id != current_public_id) {
+            self.session_repository.remove_by_public_id(&session.public_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn request_password_reset(&self, email: String) -> Result<(), ServerError> {
+        let user = match self.user_repository.find_one_by_email(None, email.to_lowercase()).await {
+            Ok(user) => user,
+            Err(ServerError::ResourceNotFound) => return Ok(()),
+            Err(e) => return Err(e)
+        };
+
+        let token = self.secure_random_generator.generate_token()?;
+        self.one_time_token_repository.create(OneTimeTokenKind::PasswordReset, &hash_token(&token), user.id, PASSWORD_RESET_TOKEN_EXPIRATION).await?;
+        self.account_mailer.send_password_reset(&user.email, &token, PASSWORD_RESET_TOKEN_EXPIRATION / 60).await
+    }
+
+    async fn reset_password(&self, token: String, new_password: String) -> Result<(), ServerError> {
+        // Hash first so that the token isn't used up by a password that gets rejected
+        let password_hash = self.hash_password(&new_password).await?;
+        let user_id = self.one_time_token_repository.take(OneTimeTokenKind::PasswordReset, &hash_token(&token)).await?;
+
+        self.user_repository.update_password(None, user_id, password_hash).await?;
+        self.session_repository.remove_all_by_user_id(user_id).await
+    }
+
+    async fn send_email_verification(&self, user_id: IdType) -> Result<(), ServerError> {
+        let user = self.user_repository.find_one_by_id(None, user_id).await?;
+        if user.verified_at.is_some() {
+            return Err(ServerError::EmailAlreadyVerified);
+        }
+        self.send_verification_token(&user).await
+    }
+
+    async fn verify_email(&self, token: String) -> Result<(), ServerError> {
+        let user_id = self.one_time_token_repository.take(OneTimeTokenKind::EmailVerification, &hash_token(&token)).await?;
+        self.user_repository.update_verified_at(None, user_id).await
+    }
+
+    async fn is_email_verified(&self, user_id: IdType) -> Result<bool, ServerError> {
+        self.user_repository.find_one_by_id(None, user_id).await
+            .map(|user| user.verified_at.is_some())
+    }
+
+    async fn find_user_by_id(&self, user_id: IdType) -> Result<UserWithProfileDTO, ServerError> {
+        let user = self.user_repository.find_one_by_id(None, user_id).await?;
+        let profile = self.profile_repository.find_by_user_id(None, user_id).await?;
+        Ok(UserWithProfileDTO::from(UserAndProfileFromQuery { user, profile }))
+    }
+
+    async fn find_users_starting_from_id(&self, user_id: Option<IdType>, search: Option<String>, limit: i32) -> Result<Vec<UserWithProfileDTO>, ServerError> {
+        let users = self.user_repository.find_starting_from_id_with_search(None, user_id, search, limit).await?;
+        Ok(users.into_iter().map(UserWithProfileDTO::from).collect())
+    }
+
+    async fn set_user_suspended(&self, user_id: IdType, suspended: bool) -> Result<(), ServerError> {
+        self.user_repository.update_suspended(None, user_id, suspended).await?;
+        if suspended {
+            self.session_repository.remove_all_by_user_id(user_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn invalidate_user_sessions(&self, user_id: IdType) -> Result<(), ServerError> {
+        // Makes sure the user exists, removing the sessions of an unknown user would silently succeed
+        self.user_repository.find_one_by_id(None, user_id).await?;
+        self.session_repository.remove_all_by_user_id(user_id).await
     }
 }
 
@@ -130,29 +538,10 @@ fn is_username_valid(username: &str) -> bool {
     USERNAME_REGEX.is_match(username) && (3..=15).contains(&username_count)
 }
 
-pub fn hash_password(password: &str, with_checks: bool) -> Result<String, ServerError> {
-    if with_checks {
-        let password_length = password.graphemes(true).count();
-        if password_length < 8 {
-            return Err(ServerError::PasswordTooShort);
-        }
-        if password_length > 60 {
-            return Err(ServerError::PasswordTooLong);
-        }
-    }
-
-    let password_salt = SaltString::generate(&mut OsRng);
-    let argon2_params = match Params::new(8192, 5, 1, Some(32)) {
-        Ok(argon2_params) => argon2_params,
-        Err(e) => {
-            return Err(ServerError::InternalError(Arc::new(e.into())));
-        }
-    };
-    let password_hash = match Argon2::new(Argon2id, argon2::Version::V0x13, argon2_params).hash_password(password.as_ref(), &password_salt) {
-        Ok(password_hash) => password_hash,
-        Err(e) => {
-            return Err(ServerError::InternalError(Arc::new(e.into())));
-        }
-    };
-    Ok(password_hash.to_string())
+// Recovery codes are accepted regardless of case and dashes
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
 }
\ No newline at end of file
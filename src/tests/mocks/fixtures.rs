@@ -0,0 +1,84 @@
+use crate::entities::dtos::session_dtos::SessionPolicies;
+use crate::entities::figure::Figure;
+use crate::entities::types::IdType;
+use crate::repositories::traits::ProfileRepositoryTrait;
+use crate::services::account_mailer::AccountMailer;
+use crate::services::user_service::UserService;
+use crate::tests::mocks::mock_mailer::MockMailer;
+use crate::tests::mocks::repositories::mock_one_time_token_repository::MockOneTimeTokenRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_session_repository::MockSessionRepository;
+use crate::tests::mocks::repositories::mock_transaction::{MockTransaction, MockTransactionCreator};
+use crate::tests::mocks::repositories::mock_user_repository::MockUserRepository;
+use crate::tests::mocks::utilities::secure_rand_generator::FakeRandomGenerator;
+use crate::utilities::password::PasswordHashPolicy;
+
+pub type TestUserService = UserService<MockTransactionCreator, MockTransaction, MockUserRepository, MockProfileRepository, MockSessionRepository, MockOneTimeTokenRepository, MockMailer, FakeRandomGenerator>;
+
+// Mocks of a user service, they share their state with the ones of the built service
+#[derive(Clone)]
+pub struct UserServiceBuilder {
+    pub user_repository: MockUserRepository,
+    pub profile_repository: MockProfileRepository,
+    pub session_repository: MockSessionRepository,
+    pub one_time_token_repository: MockOneTimeTokenRepository,
+    pub mailer: MockMailer,
+    session_policies: Option<SessionPolicies>,
+    password_hash_policy: Option<PasswordHashPolicy>,
+}
+
+impl UserServiceBuilder {
+    pub fn new() -> Self {
+        let profile_repository = MockProfileRepository::new();
+        UserServiceBuilder {
+            user_repository: MockUserRepository::new(profile_repository.clone()),
+            profile_repository,
+            session_repository: MockSessionRepository::new(),
+            one_time_token_repository: MockOneTimeTokenRepository::new(),
+            mailer: MockMailer::new(),
+            session_policies: None,
+            password_hash_policy: None,
+        }
+    }
+
+    pub fn with_session_policies(mut self, session_policies: SessionPolicies) -> Self {
+        self.session_policies = Some(session_policies);
+        self
+    }
+
+    pub fn with_password_hash_policy(mut self, password_hash_policy: PasswordHashPolicy) -> Self {
+        self.password_hash_policy = Some(password_hash_policy);
+        self
+    }
+
+    pub fn build(&self) -> TestUserService {
+        let mut user_service = UserService::new(MockTransactionCreator::new(), self.user_repository.clone(), self.profile_repository.clone(), self.session_repository.clone(), self.one_time_token_repository.clone(), AccountMailer::new(self.mailer.clone(), "https://frontend.test".to_string()), FakeRandomGenerator::new());
+        if let Some(session_policies) = self.session_policies {
+            user_service = user_service.with_session_policies(session_policies);
+        }
+        if let Some(password_hash_policy) = self.password_hash_policy {
+            user_service = user_service.with_password_hash_policy(password_hash_policy);
+        }
+        user_service
+    }
+}
+
+// Profiles with the given usernames, the user id of each is its index
+pub async fn create_profiles(profile_repository: &MockProfileRepository, usernames: &[&str]) {
+    for (user_id, username) in usernames.iter().enumerate() {
+        profile_repository.create(None, username.to_string(), user_id as IdType).await.unwrap();
+    }
+}
+
+// Figure stored in the mock content store, set a different url for figures that are actually uploaded
+pub fn figure(title: &str, profile_id: IdType) -> Figure {
+    Figure {
+        id: 0,
+        title: title.to_string(),
+        description: None,
+        width: 10,
+        height: 10,
+        url: "https://mock.storage/figure".to_string(),
+        profile_id,
+    }
+}
\ No newline at end of file
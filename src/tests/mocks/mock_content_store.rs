@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use bytes::Bytes;
+use crate::content_store::ContentStore;
+use crate::server_errors::ServerError;
+
+#[derive(Clone)]
+pub struct MockContentStore {
+    objects: Arc<Mutex<HashMap<String, Bytes>>>,
+    unavailable: Arc<AtomicBool>,
+}
+
+impl MockContentStore {
+    pub fn new() -> Self {
+        Self {
+            objects: Arc::new(Mutex::new(HashMap::new())),
+            unavailable: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.objects.lock().unwrap().contains_key(name)
+    }
+
+    pub fn object_count(&self) -> usize {
+        self.objects.lock().unwrap().len()
+    }
+
+    // Every request fails while the store is unavailable
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.unavailable.store(unavailable, Ordering::SeqCst);
+    }
+
+    fn check_available(&self) -> Result<(), ServerError> {
+        match self.unavailable.load(Ordering::SeqCst) {
+            true => Err(ServerError::InternalError(Arc::new(anyhow!("content store unavailable")))),
+            false => Ok(())
+        }
+    }
+}
+
+#[async_trait]
+impl ContentStore for MockContentStore {
+    async fn upload_object(&self, name: &str, bytes: Bytes, _content_type: &str) -> Result<String, ServerError> {
+        self.check_available()?;
+        self.objects.lock().unwrap().insert(name.to_string(), bytes);
+        Ok(format!("{}{}", self.get_base_url(), name))
+    }
+
+    async fn upload_file(&self, name: &str, path: &Path, _content_type: &str) -> Result<(), ServerError> {
+        self.check_available()?;
+        let bytes = tokio::fs::read(path).await
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        self.objects.lock().unwrap().insert(name.to_string(), Bytes::from(bytes));
+        Ok(())
+    }
+
+    async fn get_object(&self, name: &str) -> Result<Bytes, ServerError> {
+        self.check_available()?;
+        self.objects.lock().unwrap().get(name)
+            .cloned()
+            .ok_or(ServerError::ResourceNotFound)
+    }
+
+    async fn get_download_url(&self, name: &str, _expires_in: Duration) -> Result<String, ServerError> {
+        self.check_available()?;
+        match self.contains(name) {
+            true => Ok(format!("{}presigned/{}", self.get_base_url(), name)),
+            false => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn delete_object(&self, name: &str) -> Result<(), ServerError> {
+        self.check_available()?;
+        self.objects.lock().unwrap().remove(name);
+        Ok(())
+    }
+
+    fn get_base_url(&self) -> String {
+        "https://mock.storage/".to_string()
+    }
+}
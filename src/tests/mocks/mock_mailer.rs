@@ -0,0 +1,40 @@
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use crate::mailer::Mailer;
+use crate::server_errors::ServerError;
+
+#[derive(Clone, Debug)]
+pub struct SentMail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Clone)]
+pub struct MockMailer {
+    sent: Arc<Mutex<Vec<SentMail>>>,
+}
+
+impl MockMailer {
+    pub fn new() -> Self {
+        Self {
+            sent: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn sent(&self) -> Vec<SentMail> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Mailer for MockMailer {
+    async fn send_mail(&self, to: &str, subject: &str, body: String) -> Result<(), ServerError> {
+        self.sent.lock().unwrap().push(SentMail {
+            to: to.to_string(),
+            subject: subject.to_string(),
+            body,
+        });
+        Ok(())
+    }
+}
\ No newline at end of file
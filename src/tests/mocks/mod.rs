@@ -1,2 +1,6 @@
 pub mod repositories;
-pub mod utilities;
\ No newline at end of file
+pub mod utilities;
+pub mod mock_content_store;
+pub mod mock_mailer;
+pub mod stand_in_identity_provider;
+pub mod fixtures;
\ No newline at end of file
@@ -0,0 +1,78 @@
+use std::cmp::Reverse;
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use chrono::Utc;
+use crate::entities::access_token::{AccessToken, Scope};
+use crate::entities::types::IdType;
+use crate::repositories::traits::AccessTokenRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+
+#[derive(Clone)]
+pub struct MockAccessTokenRepository {
+    db: Arc<Mutex<Vec<AccessToken>>>,
+}
+
+impl MockAccessTokenRepository {
+    pub fn new() -> Self {
+        Self {
+            db: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+#[async_trait]
+impl AccessTokenRepositoryTrait<MockTransaction> for MockAccessTokenRepository {
+    async fn create(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType, name: String, token_hash: String, scopes: Vec<Scope>) -> Result<AccessToken, ServerError> {
+        let mut db = self.db.lock().unwrap();
+        let access_token = AccessToken {
+            id: db.iter().map(|access_token| access_token.id + 1).max().unwrap_or(0),
+            user_id,
+            name,
+            token_hash,
+            scopes,
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        db.push(access_token.clone());
+        Ok(access_token)
+    }
+
+    async fn find_by_token_hash(&self, _transaction: Option<&mut MockTransaction>, token_hash: &str) -> Result<AccessToken, ServerError> {
+        self.db.lock().unwrap().iter()
+            .find(|access_token| access_token.token_hash == token_hash)
+            .cloned()
+            .ok_or(ServerError::ResourceNotFound)
+    }
+
+    async fn find_all_by_user_id(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType) -> Result<Vec<AccessToken>, ServerError> {
+        let mut access_tokens: Vec<AccessToken> = self.db.lock().unwrap().iter()
+            .filter(|access_token| access_token.user_id == user_id)
+            .cloned()
+            .collect();
+        access_tokens.sort_by_key(|access_token| Reverse(access_token.id));
+        Ok(access_tokens)
+    }
+
+    async fn update_last_used_at(&self, _transaction: Option<&mut MockTransaction>, access_token_id: IdType) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        match db.iter_mut().find(|access_token| access_token.id == access_token_id) {
+            Some(access_token) => {
+                access_token.last_used_at = Some(Utc::now());
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn delete(&self, _transaction: Option<&mut MockTransaction>, access_token_id: IdType, user_id: IdType) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        match db.iter().position(|access_token| access_token.id == access_token_id && access_token.user_id == user_id) {
+            Some(index) => {
+                db.remove(index);
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,130 @@
+use std::cmp::Reverse;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Utc};
+use crate::entities::dtos::comment_dto::CommentDTO;
+use crate::entities::dtos::profile_dto::ProfileDTO;
+use crate::entities::types::IdType;
+use crate::repositories::traits::{CommentRepositoryTrait, ProfileRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+
+#[derive(Clone)]
+pub struct MockComment {
+    pub id: IdType,
+    pub figure_id: IdType,
+    pub profile_id: IdType,
+    pub parent_id: Option<IdType>,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+pub struct MockCommentRepository {
+    db: Arc<Mutex<Vec<MockComment>>>,
+    next_id: Arc<Mutex<IdType>>,
+    profile_repository: MockProfileRepository,
+}
+
+impl MockCommentRepository {
+    pub fn new(profile_repository: MockProfileRepository) -> Self {
+        MockCommentRepository {
+            db: Arc::new(Mutex::new(Vec::new())),
+            next_id: Arc::new(Mutex::new(0)),
+            profile_repository,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.db.lock().unwrap().len()
+    }
+
+    async fn to_dto(&self, comment: MockComment) -> Result<CommentDTO, ServerError> {
+        let profile = self.profile_repository.find_by_id(None, comment.profile_id).await?;
+        let reply_count = self.db.lock().unwrap().iter().filter(|reply| reply.parent_id == Some(comment.id)).count() as IdType;
+        Ok(CommentDTO {
+            id: comment.id,
+            figure_id: comment.figure_id,
+            parent_id: comment.parent_id,
+            content: comment.content,
+            created_at: comment.created_at,
+            edited_at: comment.edited_at,
+            profile: ProfileDTO::from(profile),
+            reply_count,
+        })
+    }
+
+    async fn to_dtos(&self, comments: Vec<MockComment>) -> Result<Vec<CommentDTO>, ServerError> {
+        let mut dtos = Vec::with_capacity(comments.len());
+        for comment in comments {
+            dtos.push(self.to_dto(comment).await?);
+        }
+        Ok(dtos)
+    }
+}
+
+#[async_trait]
+impl CommentRepositoryTrait<MockTransaction> for MockCommentRepository {
+    async fn create(&self, _transaction: Option<&mut MockTransaction>, figure_id: IdType, profile_id: IdType, parent_id: Option<IdType>, content: String) -> Result<IdType, ServerError> {
+        let mut next_id = self.next_id.lock().unwrap();
+        let id = *next_id;
+        *next_id += 1;
+        self.db.lock().unwrap().push(MockComment { id, figure_id, profile_id, parent_id, content, created_at: Utc::now(), edited_at: None });
+        Ok(id)
+    }
+
+    async fn find_by_id(&self, _transaction: Option<&mut MockTransaction>, comment_id: IdType) -> Result<CommentDTO, ServerError> {
+        let comment = self.db.lock().unwrap()
+            .iter()
+            .find(|comment| comment.id == comment_id)
+            .cloned()
+            .ok_or(ServerError::ResourceNotFound)?;
+        self.to_dto(comment).await
+    }
+
+    async fn find_starting_from_id_with_figure_id(&self, _transaction: Option<&mut MockTransaction>, figure_id: IdType, comment_id: Option<IdType>, limit: i32) -> Result<Vec<CommentDTO>, ServerError> {
+        let mut comments: Vec<MockComment> = self.db.lock().unwrap()
+            .iter()
+            .filter(|comment| comment.figure_id == figure_id && comment.parent_id.is_none())
+            .filter(|comment| match comment_id {
+                Some(id) => comment.id < id,
+                None => true
+            })
+            .cloned()
+            .collect();
+        comments.sort_by_key(|comment| Reverse(comment.id));
+        comments.truncate(limit as usize);
+        self.to_dtos(comments).await
+    }
+
+    async fn find_replies_starting_from_id(&self, _transaction: Option<&mut MockTransaction>, parent_id: IdType, comment_id: Option<IdType>, limit: i32) -> Result<Vec<CommentDTO>, ServerError> {
+        let mut replies: Vec<MockComment> = self.db.lock().unwrap()
+            .iter()
+            .filter(|comment| comment.parent_id == Some(parent_id))
+            .filter(|comment| match comment_id {
+                Some(id) => comment.id > id,
+                None => true
+            })
+            .cloned()
+            .collect();
+        replies.sort_by_key(|comment| comment.id);
+        replies.truncate(limit as usize);
+        self.to_dtos(replies).await
+    }
+
+    async fn update_content(&self, _transaction: Option<&mut MockTransaction>, comment_id: IdType, content: String) -> Result<(), ServerError> {
+        if let Some(comment) = self.db.lock().unwrap().iter_mut().find(|comment| comment.id == comment_id) {
+            comment.content = content;
+            comment.edited_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    async fn delete_by_id(&self, _transaction: Option<&mut MockTransaction>, comment_id: IdType) -> Result<(), ServerError> {
+        // Stands in for the cascading delete of the replies
+        self.db.lock().unwrap().retain(|comment| comment.id != comment_id && comment.parent_id != Some(comment_id));
+        Ok(())
+    }
+}
\ No newline at end of file
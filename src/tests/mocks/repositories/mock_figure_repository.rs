@@ -0,0 +1,177 @@
+use std::cmp::Reverse;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use crate::entities::dtos::figure_dto::{FigureDTO, LikedFigureDTO};
+use crate::entities::dtos::profile_dto::ProfileDTO;
+use crate::entities::figure::Figure;
+use crate::entities::types::IdType;
+use crate::repositories::traits::{FigureRepositoryTrait, ProfileRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::tests::mocks::repositories::mock_like_repository::MockLikeRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_search::search_rank;
+use crate::tests::mocks::repositories::mock_tag_repository::MockTagRepository;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+
+#[derive(Clone)]
+pub struct MockFigureRepository {
+    db: Arc<Mutex<Vec<Figure>>>,
+    profile_repository: MockProfileRepository,
+    tag_repository: MockTagRepository,
+    like_repository: MockLikeRepository,
+}
+
+impl MockFigureRepository {
+    pub fn new(profile_repository: MockProfileRepository, tag_repository: MockTagRepository, like_repository: MockLikeRepository) -> Self {
+        MockFigureRepository {
+            db: Arc::new(Mutex::new(Vec::new())),
+            profile_repository,
+            tag_repository,
+            like_repository,
+        }
+    }
+
+    async fn to_dto(&self, figure: Figure) -> Result<FigureDTO, ServerError> {
+        let profile = self.profile_repository.find_by_id(None, figure.profile_id).await?;
+        let tags = self.tag_repository.find_by_figure_id(figure.id);
+        let like_count = self.like_repository.count_by_figure_id(figure.id);
+        Ok(FigureDTO::from(figure, ProfileDTO::from(profile), tags, like_count))
+    }
+
+    async fn to_dtos(&self, mut figures: Vec<Figure>, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
+        figures.sort_by_key(|figure| Reverse(figure.id));
+        figures.truncate(limit as usize);
+
+        let mut dtos = Vec::with_capacity(figures.len());
+        for figure in figures {
+            dtos.push(self.to_dto(figure).await?);
+        }
+        Ok(dtos)
+    }
+}
+
+#[async_trait]
+impl FigureRepositoryTrait<MockTransaction> for MockFigureRepository {
+    async fn create(&self, _transaction: Option<&mut MockTransaction>, mut figure: Figure) -> Result<Figure, ServerError> {
+        let mut db = self.db.lock().unwrap();
+        figure.id = db.len() as IdType;
+        db.push(figure.clone());
+        Ok(figure)
+    }
+
+    async fn find_by_id(&self, _transaction: Option<&mut MockTransaction>, figure_id: IdType) -> Result<FigureDTO, ServerError> {
+        let figure = self.db.lock().unwrap()
+            .iter()
+            .find(|figure| figure.id == figure_id)
+            .cloned()
+            .ok_or(ServerError::ResourceNotFound)?;
+        self.to_dto(figure).await
+    }
+
+    async fn find_starting_from_id_with_profile_id(&self, _transaction: Option<&mut MockTransaction>, figure_id: Option<IdType>, profile_id: Option<IdType>, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
+        let figures: Vec<Figure> = self.db.lock().unwrap()
+            .iter()
+            .filter(|figure| match figure_id {
+                Some(id) => figure.id < id,
+                None => true
+            })
+            .filter(|figure| match profile_id {
+                Some(id) => figure.profile_id == id,
+                None => true
+            })
+            .cloned()
+            .collect();
+        self.to_dtos(figures, limit).await
+    }
+
+    async fn find_starting_from_id_with_tag(&self, _transaction: Option<&mut MockTransaction>, figure_id: Option<IdType>, tag: String, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
+        let figures: Vec<Figure> = self.db.lock().unwrap()
+            .iter()
+            .filter(|figure| match figure_id {
+                Some(id) => figure.id < id,
+                None => true
+            })
+            .filter(|figure| self.tag_repository.find_by_figure_id(figure.id).contains(&tag))
+            .cloned()
+            .collect();
+        self.to_dtos(figures, limit).await
+    }
+
+    async fn search(&self, _transaction: Option<&mut MockTransaction>, query: String, offset: i64, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
+        let mut results: Vec<(usize, Figure)> = self.db.lock().unwrap()
+            .iter()
+            .filter_map(|figure| {
+                let fields = [Some(figure.title.as_str()), figure.description.as_deref()];
+                search_rank(&query, &fields).map(|rank| (rank, figure.clone()))
+            })
+            .collect();
+        results.sort_by_key(|(rank, figure)| Reverse((*rank, figure.id)));
+
+        let mut dtos = Vec::new();
+        for (_, figure) in results.into_iter().skip(offset as usize).take(limit as usize) {
+            dtos.push(self.to_dto(figure).await?);
+        }
+        Ok(dtos)
+    }
+
+    async fn find_liked_starting_from_id(&self, _transaction: Option<&mut MockTransaction>, profile_id: IdType, like_id: Option<IdType>, limit: i32) -> Result<Vec<LikedFigureDTO>, ServerError> {
+        let mut likes = self.like_repository.find_by_profile_id(profile_id);
+        likes.retain(|like| match like_id {
+            Some(id) => like.id < id,
+            None => true
+        });
+        likes.sort_by_key(|like| Reverse(like.id));
+        likes.truncate(limit as usize);
+
+        let mut dtos = Vec::with_capacity(likes.len());
+        for like in likes {
+            dtos.push(LikedFigureDTO {
+                like_id: like.id,
+                figure: self.find_by_id(None, like.figure_id).await?,
+            });
+        }
+        Ok(dtos)
+    }
+
+    async fn update_figure(&self, _transaction: Option<&mut MockTransaction>, figure: Figure) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        match db.iter().position(|f| f.id == figure.id) {
+            Some(position) => {
+                db[position] = figure;
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn delete_figure_by_id(&self, _transaction: Option<&mut MockTransaction>, figure_id: IdType) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        db.retain(|figure| figure.id != figure_id);
+        self.tag_repository.remove_by_figure_id(figure_id);
+        self.like_repository.remove_by_figure_id(figure_id);
+        Ok(())
+    }
+
+    async fn delete_by_profile_id(&self, _transaction: Option<&mut MockTransaction>, profile_id: IdType) -> Result<Vec<String>, ServerError> {
+        let mut db = self.db.lock().unwrap();
+        let urls = db.iter()
+            .filter(|figure| figure.profile_id == profile_id)
+            .map(|figure| {
+                self.tag_repository.remove_by_figure_id(figure.id);
+                self.like_repository.remove_by_figure_id(figure.id);
+                figure.url.clone()
+            })
+            .collect();
+        db.retain(|figure| figure.profile_id != profile_id);
+        Ok(urls)
+    }
+
+    async fn count_by_profile_id(&self, _transaction: Option<&mut MockTransaction>, profile_id: IdType) -> Result<IdType, ServerError> {
+        let db = self.db.lock().unwrap();
+        Ok(db.iter().filter(|figure| figure.profile_id == profile_id).count() as IdType)
+    }
+
+    async fn get_total_figures_count(&self, _transaction: Option<&mut MockTransaction>) -> Result<IdType, ServerError> {
+        Ok(self.db.lock().unwrap().len() as IdType)
+    }
+}
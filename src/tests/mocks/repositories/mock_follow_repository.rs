@@ -0,0 +1,125 @@
+use std::cmp::Reverse;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use crate::entities::dtos::figure_dto::FigureDTO;
+use crate::entities::dtos::profile_dto::{FollowProfileDTO, ProfileWithoutUserIdDTO};
+use crate::entities::types::IdType;
+use crate::repositories::traits::{FigureRepositoryTrait, FollowRepositoryTrait, ProfileRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::tests::mocks::repositories::mock_figure_repository::MockFigureRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+
+#[derive(Clone, Copy)]
+pub struct MockFollow {
+    pub id: IdType,
+    pub follower_id: IdType,
+    pub followed_id: IdType,
+}
+
+#[derive(Clone)]
+pub struct MockFollowRepository {
+    db: Arc<Mutex<Vec<MockFollow>>>,
+    next_id: Arc<Mutex<IdType>>,
+    profile_repository: MockProfileRepository,
+    figure_repository: MockFigureRepository,
+}
+
+impl MockFollowRepository {
+    pub fn new(profile_repository: MockProfileRepository, figure_repository: MockFigureRepository) -> Self {
+        MockFollowRepository {
+            db: Arc::new(Mutex::new(Vec::new())),
+            next_id: Arc::new(Mutex::new(0)),
+            profile_repository,
+            figure_repository,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.db.lock().unwrap().len()
+    }
+
+    // The follows matching the filter, most recent first, with the profile picked from each follow
+    async fn find_follow_profiles(&self, filter: impl Fn(&MockFollow) -> bool, pick_profile: impl Fn(&MockFollow) -> IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError> {
+        let mut follows: Vec<MockFollow> = self.db.lock().unwrap()
+            .iter()
+            .filter(|follow| filter(follow))
+            .filter(|follow| match follow_id {
+                Some(id) => follow.id < id,
+                None => true
+            })
+            .copied()
+            .collect();
+        follows.sort_by_key(|follow| Reverse(follow.id));
+        follows.truncate(limit as usize);
+
+        let mut dtos = Vec::with_capacity(follows.len());
+        for follow in follows {
+            let profile = self.profile_repository.find_by_id(None, pick_profile(&follow)).await?;
+            dtos.push(FollowProfileDTO {
+                follow_id: follow.id,
+                profile: ProfileWithoutUserIdDTO::from(profile),
+            });
+        }
+        Ok(dtos)
+    }
+}
+
+#[async_trait]
+impl FollowRepositoryTrait<MockTransaction> for MockFollowRepository {
+    async fn follow(&self, _transaction: Option<&mut MockTransaction>, follower_id: IdType, followed_id: IdType) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        if db.iter().any(|follow| follow.follower_id == follower_id && follow.followed_id == followed_id) {
+            return Ok(());
+        }
+        let mut next_id = self.next_id.lock().unwrap();
+        db.push(MockFollow { id: *next_id, follower_id, followed_id });
+        *next_id += 1;
+        self.profile_repository.add_follow_counts(follower_id, followed_id, 1);
+        Ok(())
+    }
+
+    async fn unfollow(&self, _transaction: Option<&mut MockTransaction>, follower_id: IdType, followed_id: IdType) -> Result<bool, ServerError> {
+        let mut db = self.db.lock().unwrap();
+        let count = db.len();
+        db.retain(|follow| follow.follower_id != follower_id || follow.followed_id != followed_id);
+        let removed = db.len() < count;
+        if removed {
+            self.profile_repository.add_follow_counts(follower_id, followed_id, -1);
+        }
+        Ok(removed)
+    }
+
+    async fn find_followers_starting_from_id(&self, _transaction: Option<&mut MockTransaction>, profile_id: IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError> {
+        self.find_follow_profiles(|follow| follow.followed_id == profile_id, |follow| follow.follower_id, follow_id, limit).await
+    }
+
+    async fn find_following_starting_from_id(&self, _transaction: Option<&mut MockTransaction>, profile_id: IdType, follow_id: Option<IdType>, limit: i32) -> Result<Vec<FollowProfileDTO>, ServerError> {
+        self.find_follow_profiles(|follow| follow.follower_id == profile_id, |follow| follow.followed_id, follow_id, limit).await
+    }
+
+    async fn find_feed_starting_from_id(&self, _transaction: Option<&mut MockTransaction>, profile_id: IdType, figure_id: Option<IdType>, limit: i32) -> Result<Vec<FigureDTO>, ServerError> {
+        let followed_ids: Vec<IdType> = self.db.lock().unwrap()
+            .iter()
+            .filter(|follow| follow.follower_id == profile_id)
+            .map(|follow| follow.followed_id)
+            .collect();
+
+        let mut figures = Vec::new();
+        for followed_id in followed_ids {
+            figures.extend(self.figure_repository.find_starting_from_id_with_profile_id(None, figure_id, Some(followed_id), limit).await?);
+        }
+        figures.sort_by_key(|figure| Reverse(figure.id));
+        figures.truncate(limit as usize);
+        Ok(figures)
+    }
+
+    async fn delete_by_profile_id(&self, _transaction: Option<&mut MockTransaction>, profile_id: IdType) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        for follow in db.iter().filter(|follow| follow.follower_id == profile_id || follow.followed_id == profile_id) {
+            self.profile_repository.add_follow_counts(follow.follower_id, follow.followed_id, -1);
+        }
+        db.retain(|follow| follow.follower_id != profile_id && follow.followed_id != profile_id);
+        Ok(())
+    }
+}
\ No newline at end of file
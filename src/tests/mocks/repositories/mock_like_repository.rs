@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use crate::entities::types::IdType;
+use crate::repositories::traits::LikeRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+
+#[derive(Clone, Copy)]
+pub struct MockLike {
+    pub id: IdType,
+    pub figure_id: IdType,
+    pub profile_id: IdType,
+}
+
+#[derive(Clone)]
+pub struct MockLikeRepository {
+    db: Arc<Mutex<Vec<MockLike>>>,
+    next_id: Arc<Mutex<IdType>>,
+}
+
+impl MockLikeRepository {
+    pub fn new() -> Self {
+        MockLikeRepository {
+            db: Arc::new(Mutex::new(Vec::new())),
+            next_id: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    pub fn count_by_figure_id(&self, figure_id: IdType) -> IdType {
+        self.db.lock().unwrap().iter().filter(|like| like.figure_id == figure_id).count() as IdType
+    }
+
+    pub fn find_by_profile_id(&self, profile_id: IdType) -> Vec<MockLike> {
+        self.db.lock().unwrap().iter().filter(|like| like.profile_id == profile_id).copied().collect()
+    }
+
+    // Stands in for the cascading delete of the database
+    pub fn remove_by_figure_id(&self, figure_id: IdType) {
+        self.db.lock().unwrap().retain(|like| like.figure_id != figure_id);
+    }
+}
+
+#[async_trait]
+impl LikeRepositoryTrait<MockTransaction> for MockLikeRepository {
+    async fn like(&self, _transaction: Option<&mut MockTransaction>, figure_id: IdType, profile_id: IdType) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        if db.iter().any(|like| like.figure_id == figure_id && like.profile_id == profile_id) {
+            return Ok(());
+        }
+        let mut next_id = self.next_id.lock().unwrap();
+        db.push(MockLike { id: *next_id, figure_id, profile_id });
+        *next_id += 1;
+        Ok(())
+    }
+
+    async fn unlike(&self, _transaction: Option<&mut MockTransaction>, figure_id: IdType, profile_id: IdType) -> Result<(), ServerError> {
+        self.db.lock().unwrap().retain(|like| like.figure_id != figure_id || like.profile_id != profile_id);
+        Ok(())
+    }
+
+    async fn find_liked_figure_ids(&self, _transaction: Option<&mut MockTransaction>, profile_id: IdType, figure_ids: Vec<IdType>) -> Result<Vec<IdType>, ServerError> {
+        Ok(self.db.lock().unwrap()
+            .iter()
+            .filter(|like| like.profile_id == profile_id && figure_ids.contains(&like.figure_id))
+            .map(|like| like.figure_id)
+            .collect())
+    }
+
+    async fn delete_by_profile_id(&self, _transaction: Option<&mut MockTransaction>, profile_id: IdType) -> Result<(), ServerError> {
+        self.db.lock().unwrap().retain(|like| like.profile_id != profile_id);
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use crate::entities::object_deletion::ObjectDeletion;
+use crate::entities::types::IdType;
+use crate::repositories::traits::ObjectDeletionRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+
+// Deletions are always due, retry delays are ignored
+#[derive(Clone)]
+pub struct MockObjectDeletionRepository {
+    db: Arc<Mutex<Vec<ObjectDeletion>>>,
+    next_id: Arc<Mutex<IdType>>,
+}
+
+impl MockObjectDeletionRepository {
+    pub fn new() -> Self {
+        MockObjectDeletionRepository {
+            db: Arc::new(Mutex::new(Vec::new())),
+            next_id: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    pub fn queued_names(&self) -> Vec<String> {
+        self.db.lock().unwrap().iter().map(|deletion| deletion.name.clone()).collect()
+    }
+}
+
+#[async_trait]
+impl ObjectDeletionRepositoryTrait<MockTransaction> for MockObjectDeletionRepository {
+    async fn create(&self, _transaction: Option<&mut MockTransaction>, names: Vec<String>, _first_retry_after: i64) -> Result<Vec<ObjectDeletion>, ServerError> {
+        let mut db = self.db.lock().unwrap();
+        let mut next_id = self.next_id.lock().unwrap();
+        let deletions = names.into_iter()
+            .map(|name| {
+                let deletion = ObjectDeletion {
+                    id: *next_id,
+                    name,
+                    attempts: 0,
+                };
+                *next_id += 1;
+                deletion
+            })
+            .collect::<Vec<ObjectDeletion>>();
+        db.extend(deletions.iter().cloned());
+        Ok(deletions)
+    }
+
+    async fn find_due(&self, _transaction: Option<&mut MockTransaction>, limit: i32) -> Result<Vec<ObjectDeletion>, ServerError> {
+        Ok(self.db.lock().unwrap().iter().take(limit as usize).cloned().collect())
+    }
+
+    async fn postpone(&self, _transaction: Option<&mut MockTransaction>, id: IdType, _retry_after: i64) -> Result<(), ServerError> {
+        if let Some(deletion) = self.db.lock().unwrap().iter_mut().find(|deletion| deletion.id == id) {
+            deletion.attempts += 1;
+        }
+        Ok(())
+    }
+
+    async fn delete_by_id(&self, _transaction: Option<&mut MockTransaction>, id: IdType) -> Result<(), ServerError> {
+        self.db.lock().unwrap().retain(|deletion| deletion.id != id);
+        Ok(())
+    }
+}
\ No newline at end of file
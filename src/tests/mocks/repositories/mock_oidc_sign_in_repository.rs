@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use crate::entities::oidc_sign_in::OidcSignIn;
+use crate::repositories::traits::OidcSignInRepositoryTrait;
+use crate::server_errors::ServerError;
+
+// Sign ins never expire
+#[derive(Clone)]
+pub struct MockOidcSignInRepository {
+    sign_ins: Arc<Mutex<HashMap<String, OidcSignIn>>>,
+}
+
+impl MockOidcSignInRepository {
+    pub fn new() -> Self {
+        Self {
+            sign_ins: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+#[async_trait]
+impl OidcSignInRepositoryTrait for MockOidcSignInRepository {
+    async fn create(&self, state_hash: &str, sign_in: &OidcSignIn, _time_until_expiration: usize) -> Result<(), ServerError> {
+        self.sign_ins.lock().unwrap().insert(state_hash.to_string(), sign_in.clone());
+        Ok(())
+    }
+
+    async fn take(&self, state_hash: &str) -> Result<OidcSignIn, ServerError> {
+        self.sign_ins.lock().unwrap().remove(state_hash)
+            .ok_or(ServerError::InvalidOidcSignIn)
+    }
+}
\ No newline at end of file
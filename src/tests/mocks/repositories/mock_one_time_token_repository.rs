@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use crate::entities::one_time_token::OneTimeTokenKind;
+use crate::entities::types::IdType;
+use crate::repositories::traits::OneTimeTokenRepositoryTrait;
+use crate::server_errors::ServerError;
+
+// Tokens never expire
+#[derive(Clone)]
+pub struct MockOneTimeTokenRepository {
+    tokens: Arc<Mutex<HashMap<String, IdType>>>,
+}
+
+impl MockOneTimeTokenRepository {
+    pub fn new() -> Self {
+        Self {
+            tokens: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+#[async_trait]
+impl OneTimeTokenRepositoryTrait for MockOneTimeTokenRepository {
+    async fn create(&self, kind: OneTimeTokenKind, token_hash: &str, user_id: IdType, _time_until_expiration: usize) -> Result<(), ServerError> {
+        self.tokens.lock().unwrap().insert(format!("{}:{}", kind.as_str(), token_hash), user_id);
+        Ok(())
+    }
+
+    async fn find(&self, kind: OneTimeTokenKind, token_hash: &str) -> Result<IdType, ServerError> {
+        self.tokens.lock().unwrap().get(&format!("{}:{}", kind.as_str(), token_hash))
+            .copied()
+            .ok_or(ServerError::InvalidToken)
+    }
+
+    async fn take(&self, kind: OneTimeTokenKind, token_hash: &str) -> Result<IdType, ServerError> {
+        self.tokens.lock().unwrap().remove(&format!("{}:{}", kind.as_str(), token_hash))
+            .ok_or(ServerError::InvalidToken)
+    }
+}
\ No newline at end of file
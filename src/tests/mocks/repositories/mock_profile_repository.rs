@@ -1,9 +1,11 @@
+use std::cmp::Reverse;
 use async_trait::async_trait;
 use std::sync::{Arc, Mutex};
 use crate::entities::profile::Profile;
 use crate::entities::types::IdType;
 use crate::repositories::traits::ProfileRepositoryTrait;
 use crate::server_errors::ServerError;
+use crate::tests::mocks::repositories::mock_search::search_rank;
 use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
 
 #[derive(Clone)]
@@ -17,6 +19,18 @@ impl MockProfileRepository {
             db: Arc::new(Mutex::new(Vec::new()))
         }
     }
+
+    // Stands in for the counts the follow statements keep on the profiles
+    pub fn add_follow_counts(&self, follower_id: IdType, followed_id: IdType, change: IdType) {
+        for profile in self.db.lock().unwrap().iter_mut() {
+            if profile.id == follower_id {
+                profile.following_count += change;
+            }
+            if profile.id == followed_id {
+                profile.follower_count += change;
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -31,6 +45,8 @@ impl ProfileRepositoryTrait<MockTransaction> for MockProfileRepository {
             banner: None,
             profile_picture: None,
             user_id,
+            follower_count: 0,
+            following_count: 0,
         };
         db.push(profile.clone());
         Ok(profile)
@@ -50,6 +66,13 @@ impl ProfileRepositoryTrait<MockTransaction> for MockProfileRepository {
             .ok_or_else(|| ServerError::ResourceNotFound)
     }
 
+    async fn find_by_username(&self, _transaction: Option<&mut MockTransaction>, username: &str) -> Result<Profile, ServerError> {
+        let db = self.db.lock().unwrap();
+        db.iter().find(|profile| profile.username == username)
+            .cloned()
+            .ok_or_else(|| ServerError::ResourceNotFound)
+    }
+
     async fn update_profile_by_id(&self, _transaction: Option<&mut MockTransaction>, profile_id: IdType, display_name: Option<String>, bio: Option<String>, banner: Option<String>, profile_picture: Option<String>) -> Result<(), ServerError> {
         let mut db = self.db.lock().unwrap();
         let position = match db.iter().position(|profile| profile.id == profile_id) {
@@ -71,4 +94,30 @@ impl ProfileRepositoryTrait<MockTransaction> for MockProfileRepository {
     async fn get_total_profiles_count(&self, _transaction: Option<&mut MockTransaction>) -> Result<IdType, ServerError> {
         Ok(self.db.lock().unwrap().len() as IdType)
     }
+
+    async fn search(&self, _transaction: Option<&mut MockTransaction>, query: String, offset: i64, limit: i32) -> Result<Vec<Profile>, ServerError> {
+        let mut results: Vec<(usize, Profile)> = self.db.lock().unwrap()
+            .iter()
+            .filter_map(|profile| {
+                let fields = [Some(profile.username.as_str()), profile.display_name.as_deref(), profile.bio.as_deref()];
+                search_rank(&query, &fields).map(|rank| (rank, profile.clone()))
+            })
+            .collect();
+        results.sort_by_key(|(rank, profile)| Reverse((*rank, profile.id)));
+        Ok(results.into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|(_, profile)| profile)
+            .collect())
+    }
+    async fn delete_by_id(&self, _transaction: Option<&mut MockTransaction>, profile_id: IdType) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        match db.iter().position(|profile| profile.id == profile_id) {
+            Some(position) => {
+                db.remove(position);
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
 }
\ No newline at end of file
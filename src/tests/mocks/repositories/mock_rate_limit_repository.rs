@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use crate::repositories::traits::RateLimitRepositoryTrait;
+use crate::server_errors::ServerError;
+
+// In memory counters, expire like they would in the store
+#[derive(Clone)]
+pub struct MockRateLimitRepository {
+    counters: Arc<Mutex<HashMap<String, (u64, Instant)>>>,
+}
+
+impl MockRateLimitRepository {
+    pub fn new() -> Self {
+        Self {
+            counters: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+fn seconds_until(expires_at: Instant) -> usize {
+    expires_at.saturating_duration_since(Instant::now()).as_secs_f64().ceil() as usize
+}
+
+#[async_trait]
+impl RateLimitRepositoryTrait for MockRateLimitRepository {
+    async fn increment(&self, key: &str, window: usize) -> Result<(u64, usize), ServerError> {
+        let mut counters = self.counters.lock().unwrap();
+        let now = Instant::now();
+        let counter = counters.entry(key.to_string()).or_insert((0, now + Duration::from_secs(window as u64)));
+        if counter.1 <= now {
+            *counter = (0, now + Duration::from_secs(window as u64));
+        }
+        counter.0 += 1;
+        Ok((counter.0, seconds_until(counter.1)))
+    }
+
+    async fn time_to_live(&self, key: &str) -> Result<Option<usize>, ServerError> {
+        let counters = self.counters.lock().unwrap();
+        Ok(counters.get(key)
+            .filter(|(_, expires_at)| *expires_at > Instant::now())
+            .map(|(_, expires_at)| seconds_until(*expires_at)))
+    }
+
+    async fn set(&self, key: &str, time_until_expiration: usize) -> Result<(), ServerError> {
+        let mut counters = self.counters.lock().unwrap();
+        counters.insert(key.to_string(), (1, Instant::now() + Duration::from_secs(time_until_expiration as u64)));
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), ServerError> {
+        self.counters.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,25 @@
+// Stands in for full-text search, every word of the query has to occur in one of the fields.
+// Earlier fields weigh more, like the weights of the search vectors. None if the query doesn't match.
+pub fn search_rank(query: &str, fields: &[Option<&str>]) -> Option<usize> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    let mut rank = 0;
+    for word in words {
+        let mut found = false;
+        for (index, field) in fields.iter().enumerate() {
+            if let Some(field) = field {
+                if field.to_lowercase().split_whitespace().any(|field_word| field_word == word) {
+                    rank += fields.len() - index;
+                    found = true;
+                }
+            }
+        }
+        if !found {
+            return None;
+        }
+    }
+    Some(rank)
+}
\ No newline at end of file
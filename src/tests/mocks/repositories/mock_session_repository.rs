@@ -1,18 +1,53 @@
+use anyhow::anyhow;
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use crate::entities::dtos::session_dtos::Session;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::entities::dtos::session_dtos::{Session, SessionPolicies, SessionSummary};
+use crate::entities::types::IdType;
 use crate::repositories::traits::SessionRepositoryTrait;
 use crate::server_errors::ServerError;
 
 #[derive(Clone)]
 pub struct MockSessionRepository {
     connection: Arc<Mutex<Vec<Session>>>,
+    // Last expiration the sessions were extended to, by session id
+    expirations: Arc<Mutex<HashMap<String, usize>>>,
+    unavailable: Arc<AtomicBool>,
 }
 
 impl MockSessionRepository {
     pub fn new() -> Self {
         MockSessionRepository {
-            connection: Arc::new(Mutex::new(Vec::new()))
+            connection: Arc::new(Mutex::new(Vec::new())),
+            expirations: Arc::new(Mutex::new(HashMap::new())),
+            unavailable: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // Looks a session up without extending it
+    pub fn find_by_id(&self, session_id: &str) -> Result<Session, ServerError> {
+        self.check_available()?;
+        let db = self.connection.lock().unwrap();
+        match db.iter().find(|session| session.get_id() == session_id) {
+            Some(session) => Ok(session.clone()),
+            None => Err(ServerError::ResourceNotFound),
+        }
+    }
+
+    pub fn time_until_expiration(&self, session_id: &str) -> Option<usize> {
+        self.expirations.lock().unwrap().get(session_id).copied()
+    }
+
+    // Every request fails while the store is unavailable
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.unavailable.store(unavailable, Ordering::SeqCst);
+    }
+
+    fn check_available(&self) -> Result<(), ServerError> {
+        match self.unavailable.load(Ordering::SeqCst) {
+            true => Err(ServerError::InternalError(Arc::new(anyhow!("session store unavailable")))),
+            false => Ok(())
         }
     }
 }
@@ -20,20 +55,48 @@ impl MockSessionRepository {
 #[async_trait]
 impl SessionRepositoryTrait for MockSessionRepository {
     async fn create(&self, session: Session) -> Result<Session, ServerError> {
+        self.check_available()?;
         let mut db = self.connection.lock().unwrap();
         db.push(session.clone());
         Ok(session)
     }
 
-    async fn find_by_id(&self, session_id: &str, _time_until_expiration: Option<usize>) -> Result<Session, ServerError> {
+    async fn find_and_extend(&self, session_id: &str, policies: SessionPolicies) -> Result<Session, ServerError> {
+        let session = self.find_by_id(session_id)?;
+        let idle_timeout = policies.for_session(session.is_persistent()).idle_timeout;
+        self.expirations.lock().unwrap().insert(session_id.to_string(), idle_timeout);
+        Ok(session)
+    }
+
+    async fn find_all_by_user_id(&self, user_id: IdType) -> Result<Vec<SessionSummary>, ServerError> {
+        self.check_available()?;
         let db = self.connection.lock().unwrap();
-        match db.iter().find(|session| session.get_id() == session_id) {
-            Some(session) => Ok(session.clone()),
-            None => Err(ServerError::ResourceNotFound),
+        Ok(db.iter().filter(|session| session.get_user_id() == user_id).map(SessionSummary::from).collect())
+    }
+
+    async fn update_expiration(&self, session_id: &str, time_until_expiration: usize) -> Result<(), ServerError> {
+        self.check_available()?;
+        let db = self.connection.lock().unwrap();
+        match db.iter().any(|session| session.get_id() == session_id) {
+            true => {
+                self.expirations.lock().unwrap().insert(session_id.to_string(), time_until_expiration);
+                Ok(())
+            },
+            false => Err(ServerError::ResourceNotFound),
+        }
+    }
+
+    async fn update_last_seen(&self, session: &Session) -> Result<(), ServerError> {
+        self.check_available()?;
+        let mut db = self.connection.lock().unwrap();
+        if let Some(stored_session) = db.iter_mut().find(|stored_session| stored_session.get_id() == session.get_id()) {
+            stored_session.set_last_seen_at(session.get_last_seen_at());
         }
+        Ok(())
     }
 
     async fn remove_by_id(&self, session_id: &str) -> Result<(), ServerError> {
+        self.check_available()?;
         let mut db = self.connection.lock().unwrap();
         match db.iter().position(|session| session.get_id() == session_id) {
             Some(position) => {
@@ -43,4 +106,23 @@ impl SessionRepositoryTrait for MockSessionRepository {
             None => Err(ServerError::ResourceNotFound),
         }
     }
+
+    async fn remove_by_public_id(&self, public_id: &str) -> Result<(), ServerError> {
+        self.check_available()?;
+        let mut db = self.connection.lock().unwrap();
+        match db.iter().position(|session| session.get_public_id() == public_id) {
+            Some(position) => {
+                db.remove(position);
+                Ok(())
+            },
+            None => Err(ServerError::ResourceNotFound),
+        }
+    }
+
+    async fn remove_all_by_user_id(&self, user_id: IdType) -> Result<(), ServerError> {
+        self.check_available()?;
+        let mut db = self.connection.lock().unwrap();
+        db.retain(|session| session.get_user_id() != user_id);
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,72 @@
+use std::cmp::Reverse;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::entities::tag::TagCount;
+use crate::entities::types::IdType;
+use crate::repositories::traits::TagRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+
+// Stores the (figure id, tag name) pairs of the join table
+#[derive(Clone)]
+pub struct MockTagRepository {
+    db: Arc<Mutex<Vec<(IdType, String)>>>,
+    unavailable: Arc<AtomicBool>,
+}
+
+impl MockTagRepository {
+    pub fn new() -> Self {
+        MockTagRepository {
+            db: Arc::new(Mutex::new(Vec::new())),
+            unavailable: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // Replacing tags fails while unavailable, to fail a transaction after the figure was saved
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.unavailable.store(unavailable, Ordering::SeqCst);
+    }
+
+    pub fn find_by_figure_id(&self, figure_id: IdType) -> Vec<String> {
+        let mut tags: Vec<String> = self.db.lock().unwrap()
+            .iter()
+            .filter(|(id, _)| *id == figure_id)
+            .map(|(_, tag)| tag.clone())
+            .collect();
+        tags.sort();
+        tags
+    }
+
+    // Stands in for the cascading delete of the database
+    pub fn remove_by_figure_id(&self, figure_id: IdType) {
+        self.db.lock().unwrap().retain(|(id, _)| *id != figure_id);
+    }
+}
+
+#[async_trait]
+impl TagRepositoryTrait<MockTransaction> for MockTagRepository {
+    async fn replace_figure_tags(&self, _transaction: Option<&mut MockTransaction>, figure_id: IdType, tags: Vec<String>) -> Result<(), ServerError> {
+        if self.unavailable.load(Ordering::SeqCst) {
+            return Err(ServerError::InternalError(Arc::new(anyhow!("tag repository unavailable"))));
+        }
+        let mut db = self.db.lock().unwrap();
+        db.retain(|(id, _)| *id != figure_id);
+        db.extend(tags.into_iter().map(|tag| (figure_id, tag)));
+        Ok(())
+    }
+
+    async fn find_popular(&self, _transaction: Option<&mut MockTransaction>, limit: i32) -> Result<Vec<TagCount>, ServerError> {
+        let mut tags: Vec<TagCount> = Vec::new();
+        for (_, name) in self.db.lock().unwrap().iter() {
+            match tags.iter_mut().find(|tag| &tag.name == name) {
+                Some(tag) => tag.figure_count += 1,
+                None => tags.push(TagCount { name: name.clone(), figure_count: 1 })
+            }
+        }
+        tags.sort_by(|a, b| (Reverse(a.figure_count), &a.name).cmp(&(Reverse(b.figure_count), &b.name)));
+        tags.truncate(limit as usize);
+        Ok(tags)
+    }
+}
@@ -1,20 +1,32 @@
 use async_trait::async_trait;
+use std::cmp::Reverse;
+use chrono::Utc;
 use std::sync::{Arc, Mutex};
 use crate::entities::types::IdType;
-use crate::entities::user::User;
-use crate::repositories::traits::UserRepositoryTrait;
+use crate::entities::user::{Role, User, UserAndProfileFromQuery};
+use crate::repositories::traits::{ProfileRepositoryTrait, UserRepositoryTrait};
 use crate::server_errors::ServerError;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
 use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
 
 #[derive(Clone)]
 pub struct MockUserRepository {
-    db: Arc<Mutex<Vec<User>>>
+    db: Arc<Mutex<Vec<User>>>,
+    // User id and hash of every recovery code
+    recovery_codes: Arc<Mutex<Vec<(IdType, String)>>>,
+    // User id, issuer and subject of every external identity
+    external_identities: Arc<Mutex<Vec<(IdType, String, String)>>>,
+    // Used to join users with their profile
+    profile_repository: MockProfileRepository,
 }
 
 impl MockUserRepository {
-    pub fn new() -> Self {
+    pub fn new(profile_repository: MockProfileRepository) -> Self {
         MockUserRepository {
-            db: Arc::new(Mutex::new(Vec::new()))
+            db: Arc::new(Mutex::new(Vec::new())),
+            recovery_codes: Arc::new(Mutex::new(Vec::new())),
+            external_identities: Arc::new(Mutex::new(Vec::new())),
+            profile_repository,
         }
     }
 }
@@ -27,7 +39,12 @@ impl UserRepositoryTrait<MockTransaction> for MockUserRepository {
             id: db.len() as IdType,
             email,
             password: password_hash,
-            role: String::from("user"),
+            role: Role::User,
+            suspended: false,
+            verified_at: None,
+            totp_secret: None,
+            totp_enabled_at: None,
+            totp_last_used_step: None,
         };
         db.push(user.clone());
         Ok(user)
@@ -46,4 +63,147 @@ impl UserRepositoryTrait<MockTransaction> for MockUserRepository {
             .cloned()
             .ok_or_else(|| ServerError::ResourceNotFound)
     }
+
+    async fn find_starting_from_id_with_search(&self, _transaction: Option<&mut MockTransaction>, user_id: Option<IdType>, search: Option<String>, limit: i32) -> Result<Vec<UserAndProfileFromQuery>, ServerError> {
+        let mut users = self.db.lock().unwrap().clone();
+        users.retain(|user| match user_id {
+            Some(user_id) => user.id < user_id,
+            None => true
+        });
+        users.sort_by_key(|user| Reverse(user.id));
+
+        let search = search.map(|search| search.to_lowercase());
+        let mut result = Vec::new();
+        for user in users {
+            let profile = self.profile_repository.find_by_user_id(None, user.id).await?;
+            let matches = match &search {
+                Some(search) => user.email.to_lowercase().contains(search) || profile.username.to_lowercase().contains(search),
+                None => true
+            };
+            if matches {
+                result.push(UserAndProfileFromQuery { user, profile });
+            }
+        }
+        result.truncate(limit as usize);
+        Ok(result)
+    }
+
+    async fn update_password(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType, password_hash: String) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        match db.iter_mut().find(|user| user.id == user_id) {
+            Some(user) => {
+                user.password = password_hash;
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn update_verified_at(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        match db.iter_mut().find(|user| user.id == user_id) {
+            Some(user) => {
+                user.verified_at.get_or_insert_with(Utc::now);
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn update_suspended(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType, suspended: bool) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        match db.iter_mut().find(|user| user.id == user_id) {
+            Some(user) => {
+                user.suspended = suspended;
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn update_totp_secret(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType, secret: Option<String>) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        match db.iter_mut().find(|user| user.id == user_id) {
+            Some(user) => {
+                user.totp_secret = secret;
+                user.totp_enabled_at = None;
+                user.totp_last_used_step = None;
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn enable_totp(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType, step: i64) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        match db.iter_mut().find(|user| user.id == user_id && user.totp_secret.is_some()) {
+            Some(user) => {
+                user.totp_enabled_at = Some(Utc::now());
+                user.totp_last_used_step = Some(step);
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn update_totp_last_used_step(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType, step: i64) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        match db.iter_mut().find(|user| user.id == user_id && match user.totp_last_used_step {
+            Some(last_used_step) => last_used_step < step,
+            None => true
+        }) {
+            Some(user) => {
+                user.totp_last_used_step = Some(step);
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn replace_recovery_codes(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType, code_hashes: Vec<String>) -> Result<(), ServerError> {
+        let mut recovery_codes = self.recovery_codes.lock().unwrap();
+        recovery_codes.retain(|(id, _)| *id != user_id);
+        recovery_codes.extend(code_hashes.into_iter().map(|code_hash| (user_id, code_hash)));
+        Ok(())
+    }
+
+    async fn remove_recovery_code(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType, code_hash: &str) -> Result<(), ServerError> {
+        let mut recovery_codes = self.recovery_codes.lock().unwrap();
+        match recovery_codes.iter().position(|(id, hash)| *id == user_id && hash == code_hash) {
+            Some(index) => {
+                recovery_codes.remove(index);
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
+
+    async fn find_one_by_external_identity(&self, _transaction: Option<&mut MockTransaction>, issuer: &str, subject: &str) -> Result<User, ServerError> {
+        let user_id = self.external_identities.lock().unwrap().iter()
+            .find(|(_, identity_issuer, identity_subject)| identity_issuer == issuer && identity_subject == subject)
+            .map(|(user_id, _, _)| *user_id)
+            .ok_or(ServerError::ResourceNotFound)?;
+        self.find_one_by_id(None, user_id).await
+    }
+
+    async fn create_external_identity(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType, issuer: String, subject: String) -> Result<(), ServerError> {
+        self.external_identities.lock().unwrap().push((user_id, issuer, subject));
+        Ok(())
+    }
+
+    async fn has_external_identity(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType) -> Result<bool, ServerError> {
+        Ok(self.external_identities.lock().unwrap().iter().any(|(id, _, _)| *id == user_id))
+    }
+    async fn delete_by_id(&self, _transaction: Option<&mut MockTransaction>, user_id: IdType) -> Result<(), ServerError> {
+        let mut db = self.db.lock().unwrap();
+        match db.iter().position(|user| user.id == user_id) {
+            Some(position) => {
+                db.remove(position);
+                self.recovery_codes.lock().unwrap().retain(|(id, _)| *id != user_id);
+                self.external_identities.lock().unwrap().retain(|(id, _, _)| *id != user_id);
+                Ok(())
+            }
+            None => Err(ServerError::ResourceNotFound)
+        }
+    }
 }
\ No newline at end of file
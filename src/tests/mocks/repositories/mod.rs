@@ -1,4 +1,15 @@
 pub mod mock_user_repository;
 pub mod mock_transaction;
 pub mod mock_profile_repository;
-pub mod mock_session_repository;
\ No newline at end of file
+pub mod mock_session_repository;
+pub mod mock_figure_repository;
+pub mod mock_one_time_token_repository;
+pub mod mock_rate_limit_repository;
+pub mod mock_access_token_repository;
+pub mod mock_oidc_sign_in_repository;
+pub mod mock_tag_repository;
+pub mod mock_search;
+pub mod mock_like_repository;
+pub mod mock_comment_repository;
+pub mod mock_follow_repository;
+pub mod mock_object_deletion_repository;
\ No newline at end of file
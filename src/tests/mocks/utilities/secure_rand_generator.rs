@@ -15,7 +15,14 @@ impl FakeRandomGenerator {
 }
 
 impl RandomNumberGenerator for FakeRandomGenerator {
-    fn generate(&self) -> Result<u64, ServerError> {
-        Ok(self.state.fetch_add(1, Ordering::SeqCst))
+    // Every call fills the bytes with the next value of the state
+    fn fill_bytes(&self, bytes: &mut [u8]) -> Result<(), ServerError> {
+        bytes.fill(self.state.fetch_add(1, Ordering::SeqCst) as u8);
+        Ok(())
+    }
+
+    // Predictable but distinct tokens
+    fn generate_token(&self) -> Result<String, ServerError> {
+        Ok(format!("token-{}", self.state.fetch_add(1, Ordering::SeqCst)))
     }
 }
\ No newline at end of file
@@ -3,4 +3,14 @@ pub mod context;
 #[cfg(test)]
 pub mod services;
 #[cfg(test)]
-pub mod mocks;
\ No newline at end of file
+pub mod mocks;
+#[cfg(test)]
+pub mod utilities;
+#[cfg(test)]
+pub mod repositories;
+#[cfg(test)]
+pub mod test_require_role;
+#[cfg(test)]
+pub mod test_require_scope;
+#[cfg(test)]
+pub mod test_oidc;
\ No newline at end of file
@@ -0,0 +1,54 @@
+use crate::entities::comment::CommentDef;
+use crate::entities::figure::FigureDef;
+use crate::entities::profile::ProfileDef;
+use crate::entities::types::IdType;
+use crate::entities::user::UserDef;
+use crate::repositories::query_builder::{Comparison, FilteredQuery, Order};
+
+#[test]
+pub fn query_without_filters() {
+    let mut query = FilteredQuery::new("SELECT * FROM figure")
+        .filter_if_some(FigureDef::Id, Comparison::LessThan, None as Option<IdType>);
+
+    assert_eq!(query.sql(), "SELECT * FROM figure");
+}
+
+#[test]
+pub fn query_with_filters() {
+    let mut query = FilteredQuery::new("SELECT * FROM figure")
+        .filter_if_some(FigureDef::Id, Comparison::LessThan, Some(10 as IdType))
+        .filter(FigureDef::ProfileId, Comparison::Equal, 1 as IdType);
+
+    assert_eq!(query.sql(), "SELECT * FROM figure WHERE figure.id < $1 AND figure.profile_id = $2");
+}
+
+#[test]
+pub fn query_order_and_limit_come_last() {
+    // Order and limit given before the filter should still end up after the WHERE clause
+    let mut query = FilteredQuery::new("SELECT * FROM figure")
+        .order_by(FigureDef::Id, Order::Descending)
+        .limit(3)
+        .filter(FigureDef::ProfileId, Comparison::Equal, 1 as IdType);
+
+    assert_eq!(query.sql(), "SELECT * FROM figure WHERE figure.profile_id = $1 ORDER BY figure.id DESC LIMIT $2");
+}
+
+
+#[test]
+pub fn query_with_any_filter() {
+    let mut query = FilteredQuery::new("SELECT * FROM \"user\"")
+        .filter_any_if_some(&[&UserDef::Email, &ProfileDef::Username], Comparison::ILike, Some("%test%".to_string()))
+        .filter(UserDef::Id, Comparison::LessThan, 10 as IdType);
+
+    assert_eq!(query.sql(), "SELECT * FROM \"user\" WHERE (\"user\".email ILIKE $1 OR profile.username ILIKE $2) AND \"user\".id < $3");
+}
+
+#[test]
+pub fn query_with_null_filter_in_ascending_order() {
+    let mut query = FilteredQuery::new("SELECT * FROM comment")
+        .filter_null(CommentDef::ParentId)
+        .filter_if_some(CommentDef::Id, Comparison::GreaterThan, Some(10 as IdType))
+        .order_by(CommentDef::Id, Order::Ascending);
+
+    assert_eq!(query.sql(), "SELECT * FROM comment WHERE comment.parent_id IS NULL AND comment.id > $1 ORDER BY comment.id ASC");
+}
\ No newline at end of file
@@ -0,0 +1 @@
+mod test_access_tokens;
\ No newline at end of file
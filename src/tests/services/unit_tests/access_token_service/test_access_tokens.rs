@@ -0,0 +1,107 @@
+use crate::entities::access_token::Scope;
+use crate::entities::dtos::session_dtos::ClientInfo;
+use crate::entities::user::Role;
+use crate::repositories::traits::AccessTokenRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::access_token_service::AccessTokenService;
+use crate::services::traits::{AccessTokenServiceTrait, UserServiceTrait};
+use crate::tests::mocks::fixtures::{TestUserService, UserServiceBuilder};
+use crate::tests::mocks::repositories::mock_access_token_repository::MockAccessTokenRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+use crate::tests::mocks::repositories::mock_user_repository::MockUserRepository;
+use crate::tests::mocks::utilities::secure_rand_generator::FakeRandomGenerator;
+
+type TestAccessTokenService = AccessTokenService<MockTransaction, MockAccessTokenRepository, MockUserRepository, MockProfileRepository, FakeRandomGenerator>;
+
+// Signs up two users
+async fn setup() -> (TestAccessTokenService, TestUserService, MockAccessTokenRepository) {
+    let builder = UserServiceBuilder::new();
+
+    let access_token_repository = MockAccessTokenRepository::new();
+
+    let user_service = builder.build();
+    user_service.signup_user("first@test.test".to_string(), "test1234".to_string(), "first".to_string(), ClientInfo::default()).await.unwrap();
+    user_service.signup_user("second@test.test".to_string(), "test1234".to_string(), "second".to_string(), ClientInfo::default()).await.unwrap();
+
+    let access_token_service = AccessTokenService::new(access_token_repository.clone(), builder.user_repository, builder.profile_repository, FakeRandomGenerator::new());
+    (access_token_service, user_service, access_token_repository)
+}
+
+#[tokio::test]
+pub async fn create_access_token() {
+    let (access_token_service, _, access_token_repository) = setup().await;
+
+    let (token, access_token) = access_token_service.create_access_token(0, " script ".to_string(), vec![Scope::FiguresWrite, Scope::FiguresWrite]).await.unwrap();
+
+    assert_eq!(access_token.name, "script");
+    assert_eq!(access_token.scopes, vec![Scope::FiguresWrite]);
+    // Only the hash is stored
+    assert_ne!(access_token.token_hash, token);
+    assert_eq!(access_token_repository.find_by_token_hash(None, &access_token.token_hash).await.unwrap().user_id, 0);
+}
+
+#[tokio::test]
+pub async fn create_access_token_invalid() {
+    let (access_token_service, _, _) = setup().await;
+
+    let no_name_result = access_token_service.create_access_token(0, "  ".to_string(), vec![Scope::FiguresWrite]).await;
+    let no_scopes_result = access_token_service.create_access_token(0, "script".to_string(), Vec::new()).await;
+
+    assert!(matches!(no_name_result, Err(ServerError::InvalidAccessTokenName)));
+    assert!(matches!(no_scopes_result, Err(ServerError::MissingAccessTokenScopes)));
+}
+
+#[tokio::test]
+pub async fn find_session_by_token() {
+    let (access_token_service, _, access_token_repository) = setup().await;
+    let (token, access_token) = access_token_service.create_access_token(1, "script".to_string(), vec![Scope::ProfileWrite]).await.unwrap();
+
+    let session = access_token_service.find_session_by_token(token).await.unwrap();
+
+    assert_eq!(session.get_user_id(), 1);
+    assert_eq!(session.get_profile_id(), 1);
+    assert_eq!(session.get_role(), Role::User);
+    assert!(session.is_access_token());
+    assert!(session.has_scope(Scope::ProfileWrite));
+    assert!(!session.has_scope(Scope::FiguresWrite));
+    let used_access_token = access_token_repository.find_by_token_hash(None, &access_token.token_hash).await.unwrap();
+    assert!(used_access_token.last_used_at.is_some());
+}
+
+#[tokio::test]
+pub async fn find_session_by_unknown_token() {
+    let (access_token_service, _, _) = setup().await;
+
+    let result = access_token_service.find_session_by_token("unknown".to_string()).await;
+
+    assert!(matches!(result, Err(ServerError::ResourceNotFound)));
+}
+
+#[tokio::test]
+pub async fn find_session_of_suspended_user() {
+    let (access_token_service, user_service, _) = setup().await;
+    let (token, _) = access_token_service.create_access_token(0, "script".to_string(), vec![Scope::FiguresWrite]).await.unwrap();
+
+    user_service.set_user_suspended(0, true).await.unwrap();
+    let result = access_token_service.find_session_by_token(token).await;
+
+    assert!(matches!(result, Err(ServerError::AccountSuspended)));
+}
+
+#[tokio::test]
+pub async fn revoke_access_token() {
+    let (access_token_service, _, _) = setup().await;
+    let (token, access_token) = access_token_service.create_access_token(0, "script".to_string(), vec![Scope::FiguresWrite]).await.unwrap();
+    access_token_service.create_access_token(0, "other script".to_string(), vec![Scope::FiguresWrite]).await.unwrap();
+
+    // Tokens of other users can't be revoked
+    let other_user_result = access_token_service.revoke_access_token(1, access_token.id).await;
+    access_token_service.revoke_access_token(0, access_token.id).await.unwrap();
+
+    assert!(matches!(other_user_result, Err(ServerError::ResourceNotFound)));
+    assert!(access_token_service.find_session_by_token(token).await.is_err());
+    let remaining_access_tokens = access_token_service.find_access_tokens(0).await.unwrap();
+    assert_eq!(remaining_access_tokens.len(), 1);
+    assert_eq!(remaining_access_tokens[0].name, "other script");
+}
\ No newline at end of file
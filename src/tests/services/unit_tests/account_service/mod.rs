@@ -0,0 +1 @@
+mod test_delete_account;
\ No newline at end of file
@@ -0,0 +1,194 @@
+use bytes::Bytes;
+use chrono::{Duration, Utc};
+use crate::content_store::ContentStore;
+use crate::entities::dtos::session_dtos::{ClientInfo, Session};
+use crate::entities::figure::Figure;
+use crate::entities::user::Role;
+use crate::repositories::traits::{FigureRepositoryTrait, FollowRepositoryTrait, LikeRepositoryTrait, ProfileRepositoryTrait, SessionRepositoryTrait, UserRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::services::account_service::AccountService;
+use crate::services::traits::AccountServiceTrait;
+use crate::tests::mocks::fixtures::figure;
+use crate::tests::mocks::mock_content_store::MockContentStore;
+use crate::tests::mocks::repositories::mock_figure_repository::MockFigureRepository;
+use crate::tests::mocks::repositories::mock_follow_repository::MockFollowRepository;
+use crate::tests::mocks::repositories::mock_like_repository::MockLikeRepository;
+use crate::tests::mocks::repositories::mock_object_deletion_repository::MockObjectDeletionRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_tag_repository::MockTagRepository;
+use crate::tests::mocks::repositories::mock_session_repository::MockSessionRepository;
+use crate::tests::mocks::repositories::mock_transaction::{MockTransaction, MockTransactionCreator};
+use crate::tests::mocks::repositories::mock_user_repository::MockUserRepository;
+use crate::utilities::password::{hash_password, PasswordHashPolicy};
+
+type TestAccountService = AccountService<MockTransactionCreator, MockTransaction, MockUserRepository, MockProfileRepository, MockFigureRepository, MockLikeRepository, MockFollowRepository, MockObjectDeletionRepository, MockSessionRepository, MockContentStore>;
+
+struct TestSetup {
+    account_service: TestAccountService,
+    user_repository: MockUserRepository,
+    profile_repository: MockProfileRepository,
+    figure_repository: MockFigureRepository,
+    like_repository: MockLikeRepository,
+    follow_repository: MockFollowRepository,
+    object_deletion_repository: MockObjectDeletionRepository,
+    session_repository: MockSessionRepository,
+    content_store: MockContentStore,
+}
+
+// Two users with a session, a figure, a banner and a profile picture each, who like each other's figure and follow each other
+async fn setup() -> TestSetup {
+    let profile_repository = MockProfileRepository::new();
+    let user_repository = MockUserRepository::new(profile_repository.clone());
+    let like_repository = MockLikeRepository::new();
+    let figure_repository = MockFigureRepository::new(profile_repository.clone(), MockTagRepository::new(), like_repository.clone());
+    let session_repository = MockSessionRepository::new();
+    let content_store = MockContentStore::new();
+
+    for (email, username) in [("test@test.test", "test"), ("other@test.test", "other")] {
+        let user = user_repository.create(None, email.to_string(), hash_password("test1234".to_string(), PasswordHashPolicy::default()).await.unwrap()).await.unwrap();
+        let profile = profile_repository.create(None, username.to_string(), user.id).await.unwrap();
+
+        let banner = content_store.upload_image(&format!("banners/{}", username), Bytes::from_static(b"banner")).await.unwrap();
+        let profile_picture = content_store.upload_image(&format!("profile_pictures/{}", username), Bytes::from_static(b"picture")).await.unwrap();
+        profile_repository.update_profile_by_id(None, profile.id, None, None, Some(banner), Some(profile_picture)).await.unwrap();
+
+        let url = content_store.upload_image(username, Bytes::from_static(b"figure")).await.unwrap();
+        figure_repository.create(None, Figure { url, ..figure("title", profile.id) }).await.unwrap();
+
+        session_repository.create(Session::new(username.to_string(), user.id, profile.id, Role::User, ClientInfo::default(), None)).await.unwrap();
+    }
+    like_repository.like(None, 1, 0).await.unwrap();
+    like_repository.like(None, 0, 1).await.unwrap();
+    let follow_repository = MockFollowRepository::new(profile_repository.clone(), figure_repository.clone());
+    follow_repository.follow(None, 0, 1).await.unwrap();
+    follow_repository.follow(None, 1, 0).await.unwrap();
+
+    let object_deletion_repository = MockObjectDeletionRepository::new();
+    let account_service = AccountService::new(MockTransactionCreator::new(), user_repository.clone(), profile_repository.clone(),
+                                              figure_repository.clone(), like_repository.clone(), follow_repository.clone(),
+                                              object_deletion_repository.clone(), session_repository.clone(), content_store.clone());
+    TestSetup { account_service, user_repository, profile_repository, figure_repository, like_repository, follow_repository, object_deletion_repository, session_repository, content_store }
+}
+
+#[tokio::test]
+pub async fn delete_account() {
+    let setup = setup().await;
+
+    let result = setup.account_service.delete_account(0, Some("test1234".to_string()), None).await;
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(setup.user_repository.find_one_by_id(None, 0).await, Err(ServerError::ResourceNotFound));
+    assert_eq!(setup.profile_repository.find_by_user_id(None, 0).await, Err(ServerError::ResourceNotFound));
+    assert_eq!(setup.figure_repository.count_by_profile_id(None, 0).await, Ok(0));
+    assert_eq!(setup.session_repository.find_by_id("test"), Err(ServerError::ResourceNotFound));
+    assert!(!setup.content_store.contains("test"));
+    assert!(!setup.content_store.contains("banners/test"));
+    assert!(!setup.content_store.contains("profile_pictures/test"));
+    assert!(setup.object_deletion_repository.queued_names().is_empty());
+}
+
+#[tokio::test]
+pub async fn delete_account_queues_images_the_store_failed_to_delete() {
+    let setup = setup().await;
+
+    setup.content_store.set_unavailable(true);
+    let result = setup.account_service.delete_account(0, Some("test1234".to_string()), None).await;
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(setup.user_repository.find_one_by_id(None, 0).await, Err(ServerError::ResourceNotFound));
+    let mut queued_names = setup.object_deletion_repository.queued_names();
+    queued_names.sort();
+    assert_eq!(queued_names, vec!["banners/test", "profile_pictures/test", "test"]);
+}
+
+#[tokio::test]
+pub async fn delete_account_with_unavailable_session_store() {
+    let setup = setup().await;
+
+    setup.session_repository.set_unavailable(true);
+    let result = setup.account_service.delete_account(0, Some("test1234".to_string()), None).await;
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(setup.user_repository.find_one_by_id(None, 0).await, Err(ServerError::ResourceNotFound));
+    // The images are still removed
+    assert!(!setup.content_store.contains("test"));
+    assert!(setup.object_deletion_repository.queued_names().is_empty());
+}
+
+#[tokio::test]
+pub async fn delete_account_keeps_other_users() {
+    let setup = setup().await;
+
+    setup.account_service.delete_account(0, Some("test1234".to_string()), None).await.unwrap();
+
+    assert!(setup.user_repository.find_one_by_id(None, 1).await.is_ok());
+    assert!(setup.profile_repository.find_by_user_id(None, 1).await.is_ok());
+    assert_eq!(setup.figure_repository.count_by_profile_id(None, 1).await, Ok(1));
+    // The likes of the deleted profile and the likes on its figure are gone
+    assert_eq!(setup.figure_repository.find_by_id(None, 1).await.unwrap().like_count, 0);
+    assert!(setup.like_repository.find_by_profile_id(1).is_empty());
+    // As are the follows from and of the deleted profile
+    let other_profile = setup.profile_repository.find_by_user_id(None, 1).await.unwrap();
+    assert_eq!((other_profile.follower_count, other_profile.following_count), (0, 0));
+    assert_eq!(setup.follow_repository.count(), 0);
+    assert!(setup.session_repository.find_by_id("other").is_ok());
+    assert!(setup.content_store.contains("other"));
+    assert!(setup.content_store.contains("banners/other"));
+    assert!(setup.content_store.contains("profile_pictures/other"));
+}
+
+#[tokio::test]
+pub async fn delete_account_wrong_password() {
+    let setup = setup().await;
+
+    let result = setup.account_service.delete_account(0, Some("wrong-password".to_string()), None).await;
+
+    assert_eq!(result, Err(ServerError::WrongPassword));
+    assert!(setup.user_repository.find_one_by_id(None, 0).await.is_ok());
+    assert_eq!(setup.figure_repository.count_by_profile_id(None, 0).await, Ok(1));
+    assert!(setup.session_repository.find_by_id("test").is_ok());
+    assert!(setup.content_store.contains("test"));
+}
+
+// Makes the first user one created through an identity provider, whose password nobody knows
+async fn link_identity(setup: &TestSetup) {
+    setup.user_repository.update_password(None, 0, hash_password("unknown random password".to_string(), PasswordHashPolicy::default()).await.unwrap()).await.unwrap();
+    setup.user_repository.create_external_identity(None, 0, "https://issuer.test".to_string(), "subject".to_string()).await.unwrap();
+}
+
+#[tokio::test]
+pub async fn delete_account_with_identity_recently_signed_in() {
+    let setup = setup().await;
+    link_identity(&setup).await;
+
+    let result = setup.account_service.delete_account(0, None, Some(Utc::now() - Duration::minutes(1))).await;
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(setup.user_repository.find_one_by_id(None, 0).await, Err(ServerError::ResourceNotFound));
+    assert!(!setup.content_store.contains("test"));
+}
+
+#[tokio::test]
+pub async fn delete_account_with_identity_signed_in_long_ago() {
+    let setup = setup().await;
+    link_identity(&setup).await;
+
+    let result = setup.account_service.delete_account(0, None, Some(Utc::now() - Duration::hours(1))).await;
+
+    assert_eq!(result, Err(ServerError::WrongPassword));
+    assert!(setup.user_repository.find_one_by_id(None, 0).await.is_ok());
+}
+
+#[tokio::test]
+pub async fn delete_account_without_password_or_identity() {
+    let setup = setup().await;
+
+    // A recent sign in isn't enough for users who have a password
+    let result = setup.account_service.delete_account(0, None, Some(Utc::now())).await;
+    assert_eq!(result, Err(ServerError::WrongPassword));
+    // Nor is a linked identity for access tokens, which never signed in
+    link_identity(&setup).await;
+    let result = setup.account_service.delete_account(0, None, None).await;
+    assert_eq!(result, Err(ServerError::WrongPassword));
+    assert!(setup.user_repository.find_one_by_id(None, 0).await.is_ok());
+}
\ No newline at end of file
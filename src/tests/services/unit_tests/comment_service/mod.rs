@@ -0,0 +1 @@
+mod test_comments;
\ No newline at end of file
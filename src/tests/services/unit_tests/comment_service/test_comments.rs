@@ -0,0 +1,152 @@
+use crate::entities::dtos::comment_dto::CommentDTO;
+use crate::entities::types::IdType;
+use crate::repositories::traits::FigureRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::comment_service::CommentService;
+use crate::services::traits::CommentServiceTrait;
+use crate::tests::mocks::fixtures::{create_profiles, figure};
+use crate::tests::mocks::repositories::mock_comment_repository::MockCommentRepository;
+use crate::tests::mocks::repositories::mock_figure_repository::MockFigureRepository;
+use crate::tests::mocks::repositories::mock_like_repository::MockLikeRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_tag_repository::MockTagRepository;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+
+const OWNER: IdType = 0;
+const AUTHOR: IdType = 1;
+const OTHER: IdType = 2;
+
+// Three profiles and two figures of the owner
+async fn setup() -> (CommentService<MockTransaction, MockFigureRepository, MockCommentRepository>, MockCommentRepository) {
+    let profile_repository = MockProfileRepository::new();
+    create_profiles(&profile_repository, &["owner", "author", "other"]).await;
+
+    let figure_repository = MockFigureRepository::new(profile_repository.clone(), MockTagRepository::new(), MockLikeRepository::new());
+    for title in ["first", "second"] {
+        figure_repository.create(None, figure(title, OWNER)).await.unwrap();
+    }
+
+    let comment_repository = MockCommentRepository::new(profile_repository);
+    (CommentService::new(figure_repository, comment_repository.clone()), comment_repository)
+}
+
+fn ids(comments: &[CommentDTO]) -> Vec<IdType> {
+    comments.iter().map(|comment| comment.id).collect()
+}
+
+#[tokio::test]
+pub async fn create_comments_and_replies() {
+    let (comment_service, _) = setup().await;
+
+    let comment = comment_service.create_comment(0, AUTHOR, None, "  Nice cat  ".to_string()).await.unwrap();
+    let reply = comment_service.create_comment(0, OWNER, Some(comment.id), "Thanks".to_string()).await.unwrap();
+    let reply_to_reply = comment_service.create_comment(0, AUTHOR, Some(reply.id), "You're welcome".to_string()).await.unwrap();
+
+    assert_eq!(comment.content, "Nice cat");
+    assert_eq!(comment.profile.id, AUTHOR);
+    // Replying to a reply stays in the thread of the comment
+    assert_eq!(reply.parent_id, Some(comment.id));
+    assert_eq!(reply_to_reply.parent_id, Some(comment.id));
+
+    let comments = comment_service.find_comments(0, None, 10).await.unwrap();
+    let replies = comment_service.find_replies(comment.id, None, 10).await.unwrap();
+    assert_eq!(ids(&comments), vec![comment.id]);
+    assert_eq!(comments[0].reply_count, 2);
+    assert_eq!(ids(&replies), vec![reply.id, reply_to_reply.id]);
+}
+
+#[tokio::test]
+pub async fn browse_comments_paginated() {
+    let (comment_service, _) = setup().await;
+    for content in ["one", "two", "three"] {
+        comment_service.create_comment(0, AUTHOR, None, content.to_string()).await.unwrap();
+    }
+    for content in ["one", "two", "three"] {
+        comment_service.create_comment(0, OTHER, Some(0), content.to_string()).await.unwrap();
+    }
+
+    let first_page = comment_service.find_comments(0, None, 2).await.unwrap();
+    let second_page = comment_service.find_comments(0, Some(1), 2).await.unwrap();
+    let replies_page = comment_service.find_replies(0, Some(3), 2).await.unwrap();
+
+    // Comments newest first, replies oldest first
+    assert_eq!(ids(&first_page), vec![2, 1]);
+    assert_eq!(ids(&second_page), vec![0]);
+    assert_eq!(ids(&replies_page), vec![4, 5]);
+}
+
+#[tokio::test]
+pub async fn create_invalid_comment() {
+    let (comment_service, comment_repository) = setup().await;
+
+    let empty = comment_service.create_comment(0, AUTHOR, None, "   ".to_string()).await;
+    let too_long = comment_service.create_comment(0, AUTHOR, None, "a".repeat(1001)).await;
+    // Counted by graphemes, not bytes
+    let longest = comment_service.create_comment(0, AUTHOR, None, "👍🏽".repeat(1000)).await;
+
+    assert_eq!(empty.err(), Some(ServerError::InvalidComment));
+    assert_eq!(too_long.err(), Some(ServerError::InvalidComment));
+    assert!(longest.is_ok());
+    assert_eq!(comment_repository.count(), 1);
+}
+
+#[tokio::test]
+pub async fn create_comment_on_missing_figure_or_parent() {
+    let (comment_service, comment_repository) = setup().await;
+    let comment = comment_service.create_comment(0, AUTHOR, None, "first figure".to_string()).await.unwrap();
+
+    let missing_figure = comment_service.create_comment(10, AUTHOR, None, "comment".to_string()).await;
+    let missing_parent = comment_service.create_comment(0, AUTHOR, Some(10), "reply".to_string()).await;
+    // The parent has to be a comment on the same figure
+    let parent_of_other_figure = comment_service.create_comment(1, AUTHOR, Some(comment.id), "reply".to_string()).await;
+
+    assert_eq!(missing_figure.err(), Some(ServerError::ResourceNotFound));
+    assert_eq!(missing_parent.err(), Some(ServerError::ResourceNotFound));
+    assert_eq!(parent_of_other_figure.err(), Some(ServerError::ResourceNotFound));
+    assert_eq!(comment_repository.count(), 1);
+}
+
+#[tokio::test]
+pub async fn update_comment() {
+    let (comment_service, _) = setup().await;
+    let comment = comment_service.create_comment(0, AUTHOR, None, "Nice cat".to_string()).await.unwrap();
+
+    let by_owner = comment_service.update_comment(comment.id, OWNER, "Ugly cat".to_string()).await;
+    let invalid = comment_service.update_comment(comment.id, AUTHOR, "".to_string()).await;
+    let by_author = comment_service.update_comment(comment.id, AUTHOR, "Very nice cat".to_string()).await.unwrap();
+
+    // Not even the owner of the figure can edit the comment
+    assert_eq!(by_owner.err(), Some(ServerError::Forbidden));
+    assert_eq!(invalid.err(), Some(ServerError::InvalidComment));
+    assert_eq!(by_author.content, "Very nice cat");
+    assert!(comment.edited_at.is_none());
+    assert!(by_author.edited_at.is_some());
+}
+
+#[tokio::test]
+pub async fn delete_comments() {
+    let (comment_service, comment_repository) = setup().await;
+    let by_author = comment_service.create_comment(0, AUTHOR, None, "Nice cat".to_string()).await.unwrap();
+    let by_other = comment_service.create_comment(0, OTHER, None, "Nice cat".to_string()).await.unwrap();
+    comment_service.create_comment(0, OTHER, Some(by_author.id), "Agreed".to_string()).await.unwrap();
+
+    let by_stranger = comment_service.delete_comment(by_author.id, OTHER).await;
+    assert_eq!(by_stranger.err(), Some(ServerError::Forbidden));
+
+    // The author and the owner of the figure can delete, replies go with the comment
+    comment_service.delete_comment(by_author.id, AUTHOR).await.unwrap();
+    comment_service.delete_comment(by_other.id, OWNER).await.unwrap();
+    assert_eq!(comment_repository.count(), 0);
+    assert_eq!(comment_service.delete_comment(by_author.id, AUTHOR).await.err(), Some(ServerError::ResourceNotFound));
+}
+
+#[tokio::test]
+pub async fn delete_comment_as_moderator() {
+    let (comment_service, comment_repository) = setup().await;
+    let comment = comment_service.create_comment(0, AUTHOR, None, "Spam".to_string()).await.unwrap();
+
+    comment_service.delete_comment_as_moderator(comment.id).await.unwrap();
+
+    assert_eq!(comment_repository.count(), 0);
+    assert_eq!(comment_service.delete_comment_as_moderator(comment.id).await.err(), Some(ServerError::ResourceNotFound));
+}
\ No newline at end of file
@@ -0,0 +1 @@
+mod test_data_export;
\ No newline at end of file
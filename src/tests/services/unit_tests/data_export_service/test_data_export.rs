@@ -0,0 +1,141 @@
+use std::io::{Cursor, Read};
+use bytes::Bytes;
+use serde_json::Value;
+use zip::ZipArchive;
+use crate::content_store::ContentStore;
+use crate::entities::figure::Figure;
+use crate::repositories::traits::{FigureRepositoryTrait, ProfileRepositoryTrait, UserRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::services::account_mailer::AccountMailer;
+use crate::services::data_export_service::DataExportService;
+use crate::services::traits::DataExportServiceTrait;
+use crate::tests::mocks::fixtures::figure;
+use crate::tests::mocks::mock_content_store::MockContentStore;
+use crate::tests::mocks::mock_mailer::MockMailer;
+use crate::tests::mocks::repositories::mock_figure_repository::MockFigureRepository;
+use crate::tests::mocks::repositories::mock_one_time_token_repository::MockOneTimeTokenRepository;
+use crate::tests::mocks::repositories::mock_like_repository::MockLikeRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_tag_repository::MockTagRepository;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+use crate::tests::mocks::repositories::mock_user_repository::MockUserRepository;
+use crate::tests::mocks::utilities::secure_rand_generator::FakeRandomGenerator;
+use crate::utilities::token::hash_token;
+
+type TestDataExportService = DataExportService<MockTransaction, MockUserRepository, MockProfileRepository, MockFigureRepository, MockOneTimeTokenRepository, MockContentStore, MockMailer, FakeRandomGenerator>;
+
+// A user with two figures and a banner, returns the store of the images and the store of the archives
+async fn setup() -> (TestDataExportService, MockContentStore, MockContentStore, MockMailer) {
+    let profile_repository = MockProfileRepository::new();
+    let user_repository = MockUserRepository::new(profile_repository.clone());
+    let figure_repository = MockFigureRepository::new(profile_repository.clone(), MockTagRepository::new(), MockLikeRepository::new());
+    let content_store = MockContentStore::new();
+    let export_store = MockContentStore::new();
+    let mailer = MockMailer::new();
+
+    let user = user_repository.create(None, "test@test.test".to_string(), "password-hash".to_string()).await.unwrap();
+    let profile = profile_repository.create(None, "test".to_string(), user.id).await.unwrap();
+    let banner = content_store.upload_image("banners/test", Bytes::from_static(b"banner")).await.unwrap();
+    profile_repository.update_profile_by_id(None, profile.id, None, Some("bio".to_string()), Some(banner), None).await.unwrap();
+    for name in ["first", "second"] {
+        let url = content_store.upload_image(name, Bytes::from(name)).await.unwrap();
+        figure_repository.create(None, Figure { url, ..figure(name, profile.id) }).await.unwrap();
+    }
+
+    let data_export_service = DataExportService::new(user_repository, profile_repository, figure_repository, MockOneTimeTokenRepository::new(),
+                                                     content_store.clone(), export_store.clone(), AccountMailer::new(mailer.clone(), "https://frontend.test".to_string()), FakeRandomGenerator::new());
+    (data_export_service, content_store, export_store, mailer)
+}
+
+// Token from the link in the last sent mail
+fn token_from_mail(mailer: &MockMailer) -> String {
+    let mail = mailer.sent().pop().unwrap();
+    let start = mail.body.find("https://frontend.test/data-export?token=").unwrap() + "https://frontend.test/data-export?token=".len();
+    mail.body[start..].split_whitespace().next().unwrap().to_string()
+}
+
+fn archive_object_name(token: &str) -> String {
+    format!("exports/{}.zip", hash_token(token))
+}
+
+fn read_file(archive: &mut ZipArchive<Cursor<Bytes>>, name: &str) -> Vec<u8> {
+    let mut contents = Vec::new();
+    archive.by_name(name).unwrap().read_to_end(&mut contents).unwrap();
+    contents
+}
+
+#[tokio::test]
+pub async fn export_data() {
+    let (data_export_service, _, export_store, mailer) = setup().await;
+
+    data_export_service.export_data(0).await.unwrap();
+    let token = token_from_mail(&mailer);
+    let url = data_export_service.take_data_export(token.clone()).await.unwrap();
+    assert_eq!(url, format!("https://mock.storage/presigned/{}", archive_object_name(&token)));
+    let archive = export_store.get_object(&archive_object_name(&token)).await.unwrap();
+    let mut archive = ZipArchive::new(Cursor::new(archive)).unwrap();
+
+    let account: Value = serde_json::from_slice(&read_file(&mut archive, "account.json")).unwrap();
+    assert_eq!(account["email"], "test@test.test");
+    // Secrets of the account are never exported
+    assert!(account.get("password").is_none());
+
+    let profile: Value = serde_json::from_slice(&read_file(&mut archive, "profile.json")).unwrap();
+    assert_eq!(profile["username"], "test");
+    assert_eq!(profile["bio"], "bio");
+
+    let figures: Value = serde_json::from_slice(&read_file(&mut archive, "figures.json")).unwrap();
+    assert_eq!(figures.as_array().unwrap().len(), 2);
+
+    assert_eq!(read_file(&mut archive, "figures/0.jpg"), b"first");
+    assert_eq!(read_file(&mut archive, "figures/1.jpg"), b"second");
+    assert_eq!(read_file(&mut archive, "profile/banner.jpg"), b"banner");
+    assert!(archive.by_name("profile/profile_picture.jpg").is_err());
+}
+
+#[tokio::test]
+pub async fn data_export_is_kept_out_of_the_public_store() {
+    let (data_export_service, content_store, export_store, mailer) = setup().await;
+
+    data_export_service.export_data(0).await.unwrap();
+    let object_name = archive_object_name(&token_from_mail(&mailer));
+
+    assert!(export_store.contains(&object_name));
+    assert!(!content_store.contains(&object_name));
+}
+
+#[tokio::test]
+pub async fn data_export_link_is_single_use() {
+    let (data_export_service, _, _, mailer) = setup().await;
+
+    data_export_service.export_data(0).await.unwrap();
+    let token = token_from_mail(&mailer);
+
+    data_export_service.take_data_export(token.clone()).await.unwrap();
+    let result = data_export_service.take_data_export(token).await;
+
+    assert_eq!(result.err(), Some(ServerError::InvalidToken));
+}
+
+#[tokio::test]
+pub async fn data_export_link_survives_store_failure() {
+    let (data_export_service, _, export_store, mailer) = setup().await;
+
+    data_export_service.export_data(0).await.unwrap();
+    let token = token_from_mail(&mailer);
+
+    export_store.set_unavailable(true);
+    assert!(data_export_service.take_data_export(token.clone()).await.is_err());
+    export_store.set_unavailable(false);
+
+    assert!(data_export_service.take_data_export(token).await.is_ok());
+}
+
+#[tokio::test]
+pub async fn data_export_invalid_token() {
+    let (data_export_service, _, _, _) = setup().await;
+
+    let result = data_export_service.take_data_export("invalid".to_string()).await;
+
+    assert_eq!(result.err(), Some(ServerError::InvalidToken));
+}
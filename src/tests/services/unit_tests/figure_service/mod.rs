@@ -0,0 +1,3 @@
+mod test_update_figure;
+mod test_delete_figure;
+mod test_tags;
\ No newline at end of file
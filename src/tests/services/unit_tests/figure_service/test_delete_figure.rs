@@ -0,0 +1,86 @@
+use bytes::Bytes;
+use crate::content_store::{ContentStore, retry_object_deletions};
+use crate::entities::figure::Figure;
+use crate::repositories::traits::FigureRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::figure_service::FigureService;
+use crate::services::traits::FigureServiceTrait;
+use crate::tests::mocks::fixtures::{create_profiles, figure};
+use crate::tests::mocks::mock_content_store::MockContentStore;
+use crate::tests::mocks::repositories::mock_figure_repository::MockFigureRepository;
+use crate::tests::mocks::repositories::mock_like_repository::MockLikeRepository;
+use crate::tests::mocks::repositories::mock_object_deletion_repository::MockObjectDeletionRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_tag_repository::MockTagRepository;
+use crate::tests::mocks::repositories::mock_transaction::{MockTransaction, MockTransactionCreator};
+
+async fn setup() -> (FigureService<MockTransactionCreator, MockTransaction, MockFigureRepository, MockTagRepository, MockObjectDeletionRepository, MockContentStore>, MockFigureRepository, MockObjectDeletionRepository, MockContentStore) {
+    let profile_repository = MockProfileRepository::new();
+    create_profiles(&profile_repository, &["owner", "other"]).await;
+
+    let content_store = MockContentStore::new();
+    let url = content_store.upload_image("image", Bytes::from_static(b"image")).await.unwrap();
+
+    let tag_repository = MockTagRepository::new();
+    let figure_repository = MockFigureRepository::new(profile_repository, tag_repository.clone(), MockLikeRepository::new());
+    figure_repository.create(None, Figure { url, ..figure("title", 0) }).await.unwrap();
+
+    let object_deletion_repository = MockObjectDeletionRepository::new();
+    let figure_service = FigureService::new(MockTransactionCreator::new(), figure_repository.clone(), tag_repository, object_deletion_repository.clone(), content_store.clone());
+    (figure_service, figure_repository, object_deletion_repository, content_store)
+}
+
+#[tokio::test]
+pub async fn delete_figure_by_owner() {
+    let (figure_service, figure_repository, _, content_store) = setup().await;
+
+    let result = figure_service.delete_figure(0, 0).await;
+    let saved_figure = figure_repository.find_by_id(None, 0).await;
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(saved_figure, Err(ServerError::ResourceNotFound));
+    // The image should be removed along with the figure
+    assert!(!content_store.contains("image"));
+}
+
+#[tokio::test]
+pub async fn delete_figure_retries_failed_image_deletion() {
+    let (figure_service, figure_repository, object_deletion_repository, content_store) = setup().await;
+
+    content_store.set_unavailable(true);
+    let result = figure_service.delete_figure(0, 0).await;
+
+    // The figure is gone, its image stays queued until the store is back
+    assert_eq!(result, Ok(()));
+    assert_eq!(figure_repository.find_by_id(None, 0).await, Err(ServerError::ResourceNotFound));
+    assert_eq!(object_deletion_repository.queued_names(), vec!["image"]);
+
+    retry_object_deletions(&content_store, &object_deletion_repository).await.unwrap();
+    assert_eq!(object_deletion_repository.queued_names(), vec!["image"]);
+
+    content_store.set_unavailable(false);
+    retry_object_deletions(&content_store, &object_deletion_repository).await.unwrap();
+    assert!(!content_store.contains("image"));
+    assert!(object_deletion_repository.queued_names().is_empty());
+}
+
+#[tokio::test]
+pub async fn delete_figure_by_non_owner() {
+    let (figure_service, figure_repository, _, content_store) = setup().await;
+
+    let result = figure_service.delete_figure(0, 1).await;
+    let saved_figure = figure_repository.find_by_id(None, 0).await;
+
+    assert_eq!(result, Err(ServerError::Forbidden));
+    assert!(saved_figure.is_ok());
+    assert!(content_store.contains("image"));
+}
+
+#[tokio::test]
+pub async fn delete_figure_non_existing() {
+    let (figure_service, _, _, _) = setup().await;
+
+    let result = figure_service.delete_figure(1, 0).await;
+
+    assert_eq!(result, Err(ServerError::ResourceNotFound));
+}
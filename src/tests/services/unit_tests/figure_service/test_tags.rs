@@ -0,0 +1,102 @@
+use bytes::Bytes;
+use crate::entities::tag::TagCount;
+use crate::repositories::traits::{FigureRepositoryTrait, ProfileRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::services::figure_service::FigureService;
+use crate::services::traits::FigureServiceTrait;
+use crate::tests::mocks::mock_content_store::MockContentStore;
+use crate::tests::mocks::repositories::mock_figure_repository::MockFigureRepository;
+use crate::tests::mocks::repositories::mock_like_repository::MockLikeRepository;
+use crate::tests::mocks::repositories::mock_object_deletion_repository::MockObjectDeletionRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_tag_repository::MockTagRepository;
+use crate::tests::mocks::repositories::mock_transaction::{MockTransaction, MockTransactionCreator};
+
+async fn setup() -> (FigureService<MockTransactionCreator, MockTransaction, MockFigureRepository, MockTagRepository, MockObjectDeletionRepository, MockContentStore>, MockFigureRepository) {
+    let profile_repository = MockProfileRepository::new();
+    profile_repository.create(None, "owner".to_string(), 0).await.unwrap();
+
+    let tag_repository = MockTagRepository::new();
+    let figure_repository = MockFigureRepository::new(profile_repository, tag_repository.clone(), MockLikeRepository::new());
+    let figure_service = FigureService::new(MockTransactionCreator::new(), figure_repository.clone(), tag_repository, MockObjectDeletionRepository::new(), MockContentStore::new());
+    (figure_service, figure_repository)
+}
+
+fn image() -> (Bytes, u32, u32) {
+    (Bytes::from_static(b"image"), 10, 10)
+}
+
+fn tags(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|tag| tag.to_string()).collect()
+}
+
+#[tokio::test]
+pub async fn create_figure_with_tags() {
+    let (figure_service, figure_repository) = setup().await;
+
+    let figure = figure_service.create("title".to_string(), None, tags(&["Cats", " #naps ", "cats"]), image(), 0).await.unwrap();
+    let saved_figure = figure_repository.find_by_id(None, figure.id).await.unwrap();
+
+    // Normalized, without duplicates and sorted
+    assert_eq!(saved_figure.tags, tags(&["cats", "naps"]));
+}
+
+#[tokio::test]
+pub async fn create_figure_with_invalid_tags() {
+    let (figure_service, figure_repository) = setup().await;
+
+    let invalid_tag = figure_service.create("title".to_string(), None, tags(&["two words"]), image(), 0).await;
+    let too_long_tag = figure_service.create("title".to_string(), None, vec!["a".repeat(31)], image(), 0).await;
+    let too_many_tags = figure_service.create("title".to_string(), None, (0..11).map(|i| i.to_string()).collect(), image(), 0).await;
+
+    assert_eq!(invalid_tag.err(), Some(ServerError::InvalidTag));
+    assert_eq!(too_long_tag.err(), Some(ServerError::InvalidTag));
+    assert_eq!(too_many_tags.err(), Some(ServerError::TooManyTags));
+    assert_eq!(figure_repository.get_total_figures_count(None).await, Ok(0));
+}
+
+#[tokio::test]
+pub async fn update_figure_tags() {
+    let (figure_service, figure_repository) = setup().await;
+    let figure = figure_service.create("title".to_string(), None, tags(&["cats"]), image(), 0).await.unwrap();
+
+    let untouched = figure_service.update_figure(figure.id, 0, Some("new title".to_string()), None, None, None).await.unwrap();
+    let replaced = figure_service.update_figure(figure.id, 0, None, None, Some(tags(&["dogs"])), None).await.unwrap();
+    let cleared = figure_service.update_figure(figure.id, 0, None, None, Some(Vec::new()), None).await.unwrap();
+
+    assert_eq!(untouched.tags, tags(&["cats"]));
+    assert_eq!(replaced.tags, tags(&["dogs"]));
+    assert!(cleared.tags.is_empty());
+    assert!(figure_repository.find_by_id(None, figure.id).await.unwrap().tags.is_empty());
+}
+
+#[tokio::test]
+pub async fn browse_figures_by_tag() {
+    let (figure_service, _) = setup().await;
+    for figure_tags in [&["cats"][..], &["dogs"], &["cats", "dogs"], &["cats"]] {
+        figure_service.create("title".to_string(), None, tags(figure_tags), image(), 0).await.unwrap();
+    }
+
+    let first_page = figure_service.find_figures_starting_from_id_with_tag(None, "Cats".to_string(), 2).await.unwrap();
+    let second_page = figure_service.find_figures_starting_from_id_with_tag(Some(2), "cats".to_string(), 2).await.unwrap();
+
+    assert_eq!(first_page.iter().map(|figure| figure.id).collect::<Vec<_>>(), vec![3, 2]);
+    assert_eq!(second_page.iter().map(|figure| figure.id).collect::<Vec<_>>(), vec![0]);
+}
+
+#[tokio::test]
+pub async fn popular_tags() {
+    let (figure_service, figure_repository) = setup().await;
+    for figure_tags in [&["cats"][..], &["dogs"], &["cats", "dogs"], &["cats", "birds"]] {
+        figure_service.create("title".to_string(), None, tags(figure_tags), image(), 0).await.unwrap();
+    }
+    figure_repository.delete_figure_by_id(None, 3).await.unwrap();
+
+    let popular_tags = figure_service.find_popular_tags(10).await.unwrap();
+
+    // Tags of deleted figures no longer count, ties are sorted by name
+    assert_eq!(popular_tags, vec![
+        TagCount { name: "cats".to_string(), figure_count: 2 },
+        TagCount { name: "dogs".to_string(), figure_count: 2 },
+    ]);
+}
@@ -0,0 +1,103 @@
+use bytes::Bytes;
+use crate::content_store::ContentStore;
+use crate::entities::figure::Figure;
+use crate::repositories::traits::FigureRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::figure_service::FigureService;
+use crate::services::traits::FigureServiceTrait;
+use crate::tests::mocks::fixtures::{create_profiles, figure};
+use crate::tests::mocks::mock_content_store::MockContentStore;
+use crate::tests::mocks::repositories::mock_figure_repository::MockFigureRepository;
+use crate::tests::mocks::repositories::mock_like_repository::MockLikeRepository;
+use crate::tests::mocks::repositories::mock_object_deletion_repository::MockObjectDeletionRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_tag_repository::MockTagRepository;
+use crate::tests::mocks::repositories::mock_transaction::{MockTransaction, MockTransactionCreator};
+
+async fn setup() -> (FigureService<MockTransactionCreator, MockTransaction, MockFigureRepository, MockTagRepository, MockObjectDeletionRepository, MockContentStore>, MockFigureRepository, MockTagRepository, MockContentStore) {
+    let profile_repository = MockProfileRepository::new();
+    create_profiles(&profile_repository, &["owner", "other"]).await;
+
+    let content_store = MockContentStore::new();
+    let url = content_store.upload_image("original", Bytes::from_static(b"original")).await.unwrap();
+
+    let tag_repository = MockTagRepository::new();
+    let figure_repository = MockFigureRepository::new(profile_repository, tag_repository.clone(), MockLikeRepository::new());
+    figure_repository.create(None, Figure { description: Some("description".to_string()), url, ..figure("title", 0) }).await.unwrap();
+
+    let figure_service = FigureService::new(MockTransactionCreator::new(), figure_repository.clone(), tag_repository.clone(), MockObjectDeletionRepository::new(), content_store.clone());
+    (figure_service, figure_repository, tag_repository, content_store)
+}
+
+#[tokio::test]
+pub async fn update_figure_by_owner() {
+    let (figure_service, figure_repository, _, _) = setup().await;
+
+    let result = figure_service.update_figure(0, 0, Some("new title".to_string()), None, None, None).await.unwrap();
+    let saved_figure = figure_repository.find_by_id(None, 0).await.unwrap();
+
+    assert_eq!(result.title, "new title");
+    // Description wasn't given, so it should be left untouched
+    assert_eq!(result.description, Some("description".to_string()));
+    assert_eq!(saved_figure, result);
+}
+
+#[tokio::test]
+pub async fn update_figure_replaces_image() {
+    let (figure_service, figure_repository, _, content_store) = setup().await;
+
+    let result = figure_service.update_figure(0, 0, None, None, None, Some((Bytes::from_static(b"image"), 20, 30))).await.unwrap();
+    let saved_figure = figure_repository.find_by_id(None, 0).await.unwrap();
+    let object_name = saved_figure.url.trim_start_matches("https://mock.storage/");
+
+    assert_ne!(saved_figure.url, "https://mock.storage/original");
+    assert!(content_store.contains(object_name));
+    // The replaced image should be cleaned up
+    assert!(!content_store.contains("original"));
+    assert_eq!((saved_figure.width, saved_figure.height), (20, 30));
+    assert_eq!(saved_figure, result);
+}
+
+#[tokio::test]
+pub async fn update_figure_by_non_owner() {
+    let (figure_service, figure_repository, _, _) = setup().await;
+
+    let result = figure_service.update_figure(0, 1, Some("new title".to_string()), None, None, None).await;
+    let saved_figure = figure_repository.find_by_id(None, 0).await.unwrap();
+
+    assert_eq!(result, Err(ServerError::Forbidden));
+    assert_eq!(saved_figure.title, "title");
+}
+
+#[tokio::test]
+pub async fn update_figure_non_existing() {
+    let (figure_service, _, _, _) = setup().await;
+
+    let result = figure_service.update_figure(1, 0, Some("new title".to_string()), None, None, None).await;
+
+    assert_eq!(result, Err(ServerError::ResourceNotFound));
+}
+
+#[tokio::test]
+pub async fn update_figure_discards_upload_when_saving_fails() {
+    let (figure_service, _, tag_repository, content_store) = setup().await;
+
+    tag_repository.set_unavailable(true);
+    let result = figure_service.update_figure(0, 0, None, None, Some(vec!["cats".to_string()]), Some((Bytes::from_static(b"image"), 20, 30))).await;
+
+    assert!(result.is_err());
+    // The figure still points to the original image, only the new one is removed
+    assert!(content_store.contains("original"));
+    assert_eq!(content_store.object_count(), 1);
+}
+
+#[tokio::test]
+pub async fn create_figure_discards_upload_when_saving_fails() {
+    let (figure_service, _, tag_repository, content_store) = setup().await;
+
+    tag_repository.set_unavailable(true);
+    let result = figure_service.create("title".to_string(), None, vec!["cats".to_string()], (Bytes::from_static(b"image"), 10, 10), 0).await;
+
+    assert!(result.is_err());
+    assert_eq!(content_store.object_count(), 1);
+}
\ No newline at end of file
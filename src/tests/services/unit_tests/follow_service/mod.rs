@@ -0,0 +1 @@
+mod test_follows;
\ No newline at end of file
@@ -0,0 +1,93 @@
+use crate::entities::dtos::profile_dto::FollowProfileDTO;
+use crate::entities::types::IdType;
+use crate::repositories::traits::FigureRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::follow_service::FollowService;
+use crate::services::traits::FollowServiceTrait;
+use crate::tests::mocks::fixtures::{create_profiles, figure};
+use crate::tests::mocks::repositories::mock_figure_repository::MockFigureRepository;
+use crate::tests::mocks::repositories::mock_follow_repository::MockFollowRepository;
+use crate::tests::mocks::repositories::mock_like_repository::MockLikeRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_tag_repository::MockTagRepository;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+
+// Four profiles, the figures of the last three alternate between them
+async fn setup() -> FollowService<MockTransaction, MockProfileRepository, MockFollowRepository> {
+    let profile_repository = MockProfileRepository::new();
+    create_profiles(&profile_repository, &["me", "first", "second", "third"]).await;
+
+    let figure_repository = MockFigureRepository::new(profile_repository.clone(), MockTagRepository::new(), MockLikeRepository::new());
+    for profile_id in [1, 2, 3, 1, 2, 3] {
+        figure_repository.create(None, figure("title", profile_id)).await.unwrap();
+    }
+
+    FollowService::new(profile_repository.clone(), MockFollowRepository::new(profile_repository, figure_repository))
+}
+
+fn profile_ids(profiles: &[FollowProfileDTO]) -> Vec<IdType> {
+    profiles.iter().map(|profile| profile.profile.id).collect()
+}
+
+#[tokio::test]
+pub async fn follow_and_unfollow_profile() {
+    let follow_service = setup().await;
+
+    let followed = follow_service.follow_profile(0, 1).await.unwrap();
+    let followed_again = follow_service.follow_profile(0, 1).await.unwrap();
+    follow_service.follow_profile(2, 1).await.unwrap();
+    let unfollowed = follow_service.unfollow_profile(0, 1).await.unwrap();
+    let unfollowed_again = follow_service.unfollow_profile(0, 1).await.unwrap();
+
+    // Following and unfollowing twice counts once
+    assert_eq!(followed.follower_count, 1);
+    assert_eq!(followed_again.follower_count, 1);
+    assert_eq!(unfollowed.follower_count, 1);
+    assert_eq!(unfollowed_again.follower_count, 1);
+    let following = follow_service.find_following(2, None, 10).await.unwrap();
+    assert_eq!(following[0].profile.follower_count, 1);
+    assert_eq!(follow_service.find_followers(1, None, 10).await.unwrap()[0].profile.following_count, 1);
+}
+
+#[tokio::test]
+pub async fn follow_invalid_profile() {
+    let follow_service = setup().await;
+
+    assert_eq!(follow_service.follow_profile(0, 0).await.err(), Some(ServerError::CannotFollowYourself));
+    assert_eq!(follow_service.follow_profile(0, 10).await.err(), Some(ServerError::ResourceNotFound));
+    assert_eq!(follow_service.unfollow_profile(0, 10).await.err(), Some(ServerError::ResourceNotFound));
+    assert_eq!(follow_service.find_followers(10, None, 10).await.err(), Some(ServerError::ResourceNotFound));
+}
+
+#[tokio::test]
+pub async fn browse_followers_and_following() {
+    let follow_service = setup().await;
+    for (follower_id, followed_id) in [(0, 1), (2, 1), (3, 1), (0, 3)] {
+        follow_service.follow_profile(follower_id, followed_id).await.unwrap();
+    }
+
+    let first_page = follow_service.find_followers(1, None, 2).await.unwrap();
+    let second_page = follow_service.find_followers(1, Some(first_page[1].follow_id), 2).await.unwrap();
+    let following = follow_service.find_following(0, None, 10).await.unwrap();
+
+    // Most recently followed first
+    assert_eq!(profile_ids(&first_page), vec![3, 2]);
+    assert_eq!(profile_ids(&second_page), vec![0]);
+    assert_eq!(profile_ids(&following), vec![3, 1]);
+}
+
+#[tokio::test]
+pub async fn browse_feed() {
+    let follow_service = setup().await;
+    follow_service.follow_profile(0, 1).await.unwrap();
+    follow_service.follow_profile(0, 3).await.unwrap();
+
+    let first_page = follow_service.find_feed(0, None, 3).await.unwrap();
+    let second_page = follow_service.find_feed(0, Some(2), 3).await.unwrap();
+    let without_follows = follow_service.find_feed(1, None, 3).await.unwrap();
+
+    // Only figures of followed profiles, newest first
+    assert_eq!(first_page.iter().map(|figure| figure.id).collect::<Vec<_>>(), vec![5, 3, 2]);
+    assert_eq!(second_page.iter().map(|figure| figure.id).collect::<Vec<_>>(), vec![0]);
+    assert!(without_follows.is_empty());
+}
\ No newline at end of file
@@ -0,0 +1 @@
+mod test_likes;
\ No newline at end of file
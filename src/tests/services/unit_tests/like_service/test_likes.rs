@@ -0,0 +1,89 @@
+use crate::entities::dtos::figure_dto::FigureDTO;
+use crate::entities::types::IdType;
+use crate::repositories::traits::FigureRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::like_service::LikeService;
+use crate::services::traits::LikeServiceTrait;
+use crate::tests::mocks::fixtures::{create_profiles, figure};
+use crate::tests::mocks::repositories::mock_figure_repository::MockFigureRepository;
+use crate::tests::mocks::repositories::mock_like_repository::MockLikeRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_tag_repository::MockTagRepository;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+
+// Two profiles and three figures of the first profile
+async fn setup() -> (LikeService<MockTransaction, MockFigureRepository, MockLikeRepository>, MockFigureRepository) {
+    let profile_repository = MockProfileRepository::new();
+    create_profiles(&profile_repository, &["owner", "fan"]).await;
+
+    let like_repository = MockLikeRepository::new();
+    let figure_repository = MockFigureRepository::new(profile_repository, MockTagRepository::new(), like_repository.clone());
+    for title in ["first", "second", "third"] {
+        figure_repository.create(None, figure(title, 0)).await.unwrap();
+    }
+
+    (LikeService::new(figure_repository.clone(), like_repository), figure_repository)
+}
+
+fn liked_by_me(figures: &[FigureDTO]) -> Vec<IdType> {
+    figures.iter().filter(|figure| figure.liked_by_me).map(|figure| figure.id).collect()
+}
+
+#[tokio::test]
+pub async fn like_and_unlike_figure() {
+    let (like_service, _) = setup().await;
+
+    let liked = like_service.like_figure(0, 1).await.unwrap();
+    let liked_again = like_service.like_figure(0, 1).await.unwrap();
+    let liked_by_other = like_service.like_figure(0, 0).await.unwrap();
+    let unliked = like_service.unlike_figure(0, 1).await.unwrap();
+    let unliked_again = like_service.unlike_figure(0, 1).await.unwrap();
+
+    // Liking and unliking twice counts once
+    assert_eq!((liked.like_count, liked.liked_by_me), (1, true));
+    assert_eq!(liked_again.like_count, 1);
+    assert_eq!(liked_by_other.like_count, 2);
+    assert_eq!((unliked.like_count, unliked.liked_by_me), (1, false));
+    assert_eq!(unliked_again.like_count, 1);
+}
+
+#[tokio::test]
+pub async fn like_missing_figure() {
+    let (like_service, _) = setup().await;
+
+    assert_eq!(like_service.like_figure(10, 1).await.err(), Some(ServerError::ResourceNotFound));
+}
+
+#[tokio::test]
+pub async fn mark_liked_figures() {
+    let (like_service, figure_repository) = setup().await;
+    like_service.like_figure(1, 1).await.unwrap();
+    let figures = || async { figure_repository.find_starting_from_id_with_profile_id(None, None, None, 10).await.unwrap() };
+
+    let mut by_fan = figures().await;
+    like_service.mark_liked_figures(Some(1), &mut by_fan).await.unwrap();
+    let mut by_owner = figures().await;
+    like_service.mark_liked_figures(Some(0), &mut by_owner).await.unwrap();
+    let mut without_session = figures().await;
+    like_service.mark_liked_figures(None, &mut without_session).await.unwrap();
+
+    assert_eq!(liked_by_me(&by_fan), vec![1]);
+    assert!(liked_by_me(&by_owner).is_empty());
+    assert!(liked_by_me(&without_session).is_empty());
+}
+
+#[tokio::test]
+pub async fn browse_liked_figures() {
+    let (like_service, _) = setup().await;
+    for figure_id in [1, 0, 2] {
+        like_service.like_figure(figure_id, 1).await.unwrap();
+    }
+
+    let first_page = like_service.find_liked_figures(1, None, 2, Some(1)).await.unwrap();
+    let second_page = like_service.find_liked_figures(1, Some(first_page[1].like_id), 2, None).await.unwrap();
+
+    // Most recently liked first, regardless of the figure id
+    assert_eq!(first_page.iter().map(|liked| liked.figure.id).collect::<Vec<_>>(), vec![2, 0]);
+    assert!(first_page.iter().all(|liked| liked.figure.liked_by_me));
+    assert_eq!(second_page.iter().map(|liked| (liked.figure.id, liked.figure.liked_by_me)).collect::<Vec<_>>(), vec![(1, false)]);
+}
\ No newline at end of file
@@ -1 +1,12 @@
 mod user_service;
+mod figure_service;
+
+mod rate_limit_service;
+mod access_token_service;
+mod oidc_service;
+mod account_service;
+mod data_export_service;
+mod search_service;
+mod like_service;
+mod comment_service;
+mod follow_service;
\ No newline at end of file
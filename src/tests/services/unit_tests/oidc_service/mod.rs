@@ -0,0 +1 @@
+mod test_oidc_sign_in;
\ No newline at end of file
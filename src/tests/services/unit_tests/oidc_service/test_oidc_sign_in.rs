@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use url::Url;
+use crate::oidc::OidcProvider;
+use crate::server_errors::ServerError;
+use crate::services::oidc_service::OidcService;
+use crate::services::traits::OidcServiceTrait;
+use crate::tests::mocks::repositories::mock_oidc_sign_in_repository::MockOidcSignInRepository;
+use crate::tests::mocks::stand_in_identity_provider::StandInIdentityProvider;
+use crate::tests::mocks::utilities::secure_rand_generator::FakeRandomGenerator;
+
+type TestOidcService = OidcService<MockOidcSignInRepository, OidcProvider, FakeRandomGenerator>;
+
+async fn setup() -> (TestOidcService, StandInIdentityProvider) {
+    let identity_provider = StandInIdentityProvider::start().await;
+    let oidc_service = OidcService::new(MockOidcSignInRepository::new(), Some(OidcProvider::new(identity_provider.config())), FakeRandomGenerator::new());
+    (oidc_service, identity_provider)
+}
+
+fn query_parameter(url: &str, name: &str) -> String {
+    let parameters: HashMap<String, String> = Url::parse(url).unwrap().query_pairs().into_owned().collect();
+    parameters[name].clone()
+}
+
+#[tokio::test]
+pub async fn sign_in() {
+    let (oidc_service, identity_provider) = setup().await;
+
+    let (state, authorization_url) = oidc_service.start_sign_in(true).await.unwrap();
+    assert!(authorization_url.starts_with(&format!("{}/authorize?", identity_provider.issuer)));
+    assert_eq!(query_parameter(&authorization_url, "state"), state);
+    let code = identity_provider.authorize(&authorization_url, "subject", "test@test.test", true);
+
+    let (identity, remember_me) = oidc_service.complete_sign_in(state, code).await.unwrap();
+    assert_eq!(identity.issuer, identity_provider.issuer);
+    assert_eq!(identity.subject, "subject");
+    assert_eq!(identity.email, Some("test@test.test".to_string()));
+    assert!(identity.email_verified);
+    assert!(remember_me);
+}
+
+#[tokio::test]
+pub async fn sign_in_state_is_single_use() {
+    let (oidc_service, identity_provider) = setup().await;
+    let (state, authorization_url) = oidc_service.start_sign_in(false).await.unwrap();
+    let code = identity_provider.authorize(&authorization_url, "subject", "test@test.test", true);
+    oidc_service.complete_sign_in(state.clone(), code).await.unwrap();
+
+    let code = identity_provider.authorize(&authorization_url, "subject", "test@test.test", true);
+    assert_eq!(oidc_service.complete_sign_in(state, code).await, Err(ServerError::InvalidOidcSignIn));
+}
+
+#[tokio::test]
+pub async fn sign_in_with_unknown_state_or_code() {
+    let (oidc_service, identity_provider) = setup().await;
+    let (state, authorization_url) = oidc_service.start_sign_in(false).await.unwrap();
+    let code = identity_provider.authorize(&authorization_url, "subject", "test@test.test", true);
+    assert_eq!(oidc_service.complete_sign_in("other".to_string(), code).await, Err(ServerError::InvalidOidcSignIn));
+
+    // Rejected by the token endpoint
+    assert_eq!(oidc_service.complete_sign_in(state, "unknown".to_string()).await, Err(ServerError::InvalidOidcSignIn));
+}
+
+#[tokio::test]
+pub async fn sign_in_with_code_of_other_sign_in() {
+    let (oidc_service, identity_provider) = setup().await;
+    let (state, _) = oidc_service.start_sign_in(false).await.unwrap();
+    let (_, other_authorization_url) = oidc_service.start_sign_in(false).await.unwrap();
+
+    // The verifier of the sign in doesn't match the challenge the code was issued for
+    let code = identity_provider.authorize(&other_authorization_url, "subject", "test@test.test", true);
+    assert_eq!(oidc_service.complete_sign_in(state, code).await, Err(ServerError::InvalidOidcSignIn));
+}
+
+#[tokio::test]
+pub async fn sign_in_without_provider() {
+    let oidc_service: TestOidcService = OidcService::new(MockOidcSignInRepository::new(), None, FakeRandomGenerator::new());
+    assert_eq!(oidc_service.start_sign_in(false).await, Err(ServerError::OidcNotConfigured));
+    assert_eq!(oidc_service.complete_sign_in("state".to_string(), "code".to_string()).await, Err(ServerError::OidcNotConfigured));
+}
\ No newline at end of file
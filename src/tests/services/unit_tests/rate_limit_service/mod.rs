@@ -0,0 +1,2 @@
+mod test_rate_limit;
+mod test_sign_in_lockout;
\ No newline at end of file
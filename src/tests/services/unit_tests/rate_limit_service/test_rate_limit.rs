@@ -0,0 +1,45 @@
+use axum::http::header::RETRY_AFTER;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use crate::entities::rate_limit::RateLimit;
+use crate::server_errors::ServerError;
+use crate::services::rate_limit_service::RateLimitService;
+use crate::services::traits::RateLimitServiceTrait;
+use crate::tests::mocks::repositories::mock_rate_limit_repository::MockRateLimitRepository;
+
+const LIMIT: RateLimit = RateLimit::new(3, 60);
+
+#[tokio::test]
+pub async fn hit_within_limit() {
+    let rate_limit_service = RateLimitService::new(MockRateLimitRepository::new());
+
+    for _ in 0..3 {
+        assert_eq!(rate_limit_service.hit("signin", "ip:127.0.0.1", LIMIT).await, Ok(()));
+    }
+}
+
+#[tokio::test]
+pub async fn hit_over_limit() {
+    let rate_limit_service = RateLimitService::new(MockRateLimitRepository::new());
+    for _ in 0..3 {
+        rate_limit_service.hit("signin", "ip:127.0.0.1", LIMIT).await.unwrap();
+    }
+
+    let result = rate_limit_service.hit("signin", "ip:127.0.0.1", LIMIT).await;
+
+    match result {
+        Err(ServerError::TooManyRequests(retry_after)) => assert!(retry_after > 0 && retry_after <= 60),
+        _ => panic!("Expected too many requests, got {:?}", result)
+    }
+    // Other clients and scopes have their own counters
+    assert_eq!(rate_limit_service.hit("signin", "ip:127.0.0.2", LIMIT).await, Ok(()));
+    assert_eq!(rate_limit_service.hit("signup", "ip:127.0.0.1", LIMIT).await, Ok(()));
+}
+
+#[test]
+pub fn too_many_requests_response() {
+    let response = ServerError::TooManyRequests(30).into_response();
+
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "30");
+}
\ No newline at end of file
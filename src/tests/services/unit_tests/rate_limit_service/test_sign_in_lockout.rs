@@ -0,0 +1,81 @@
+use crate::server_errors::ServerError;
+use crate::services::rate_limit_service::RateLimitService;
+use crate::services::traits::RateLimitServiceTrait;
+use crate::tests::mocks::repositories::mock_rate_limit_repository::MockRateLimitRepository;
+
+const IP: &str = "203.0.113.1";
+
+async fn fail_sign_ins(rate_limit_service: &RateLimitService<MockRateLimitRepository>, email: &str, times: usize) {
+    for _ in 0..times {
+        rate_limit_service.record_failed_sign_in(email, IP).await.unwrap();
+    }
+}
+
+#[tokio::test]
+pub async fn no_lockout_below_threshold() {
+    let rate_limit_service = RateLimitService::new(MockRateLimitRepository::new());
+
+    fail_sign_ins(&rate_limit_service, "test@test.test", 4).await;
+
+    assert_eq!(rate_limit_service.check_sign_in_lockout("test@test.test", IP).await, Ok(()));
+}
+
+#[tokio::test]
+pub async fn lockout_grows_with_failures() {
+    let rate_limit_service = RateLimitService::new(MockRateLimitRepository::new());
+
+    fail_sign_ins(&rate_limit_service, "test@test.test", 5).await;
+    let result = rate_limit_service.check_sign_in_lockout("test@test.test", IP).await;
+    match result {
+        Err(ServerError::TooManyRequests(retry_after)) => assert_eq!(retry_after, 60),
+        _ => panic!("Expected a lockout, got {:?}", result)
+    }
+
+    fail_sign_ins(&rate_limit_service, "test@test.test", 2).await;
+    let result = rate_limit_service.check_sign_in_lockout("test@test.test", IP).await;
+    match result {
+        Err(ServerError::TooManyRequests(retry_after)) => assert_eq!(retry_after, 240),
+        _ => panic!("Expected a lockout, got {:?}", result)
+    }
+
+    // Capped at an hour
+    fail_sign_ins(&rate_limit_service, "test@test.test", 20).await;
+    let result = rate_limit_service.check_sign_in_lockout("test@test.test", IP).await;
+    match result {
+        Err(ServerError::TooManyRequests(retry_after)) => assert_eq!(retry_after, 3600),
+        _ => panic!("Expected a lockout, got {:?}", result)
+    }
+}
+
+#[tokio::test]
+pub async fn lockout_is_per_account() {
+    let rate_limit_service = RateLimitService::new(MockRateLimitRepository::new());
+
+    fail_sign_ins(&rate_limit_service, "Test@Test.test", 5).await;
+
+    // Emails are compared case insensitively
+    assert!(rate_limit_service.check_sign_in_lockout("test@test.test", IP).await.is_err());
+    assert_eq!(rate_limit_service.check_sign_in_lockout("other@test.test", IP).await, Ok(()));
+}
+
+#[tokio::test]
+pub async fn lockout_is_per_ip() {
+    let rate_limit_service = RateLimitService::new(MockRateLimitRepository::new());
+
+    fail_sign_ins(&rate_limit_service, "test@test.test", 5).await;
+
+    // Guessing from another ip can't lock the owner out
+    assert!(rate_limit_service.check_sign_in_lockout("test@test.test", IP).await.is_err());
+    assert_eq!(rate_limit_service.check_sign_in_lockout("test@test.test", "198.51.100.1").await, Ok(()));
+}
+
+#[tokio::test]
+pub async fn successful_sign_in_resets_failures() {
+    let rate_limit_service = RateLimitService::new(MockRateLimitRepository::new());
+    fail_sign_ins(&rate_limit_service, "test@test.test", 4).await;
+
+    rate_limit_service.reset_failed_sign_ins("test@test.test", IP).await.unwrap();
+    fail_sign_ins(&rate_limit_service, "test@test.test", 4).await;
+
+    assert_eq!(rate_limit_service.check_sign_in_lockout("test@test.test", IP).await, Ok(()));
+}
\ No newline at end of file
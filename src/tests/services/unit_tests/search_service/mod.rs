@@ -0,0 +1 @@
+mod test_search;
\ No newline at end of file
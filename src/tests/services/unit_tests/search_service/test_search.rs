@@ -0,0 +1,67 @@
+use crate::entities::figure::Figure;
+use crate::repositories::traits::{FigureRepositoryTrait, ProfileRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::services::search_service::SearchService;
+use crate::services::traits::SearchServiceTrait;
+use crate::tests::mocks::fixtures::figure;
+use crate::tests::mocks::repositories::mock_figure_repository::MockFigureRepository;
+use crate::tests::mocks::repositories::mock_like_repository::MockLikeRepository;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_tag_repository::MockTagRepository;
+use crate::tests::mocks::repositories::mock_transaction::MockTransaction;
+
+// Figures and profiles that mention cats, in the title or bio or only in the description
+async fn setup() -> SearchService<MockTransaction, MockFigureRepository, MockProfileRepository> {
+    let profile_repository = MockProfileRepository::new();
+    profile_repository.create(None, "catlover".to_string(), 0).await.unwrap();
+    let profile = profile_repository.create(None, "other".to_string(), 1).await.unwrap();
+    profile_repository.update_profile_by_id(None, profile.id, Some("Other".to_string()), Some("I like cats".to_string()), None, None).await.unwrap();
+
+    let figure_repository = MockFigureRepository::new(profile_repository.clone(), MockTagRepository::new(), MockLikeRepository::new());
+    for (title, description) in [("Sleeping cats", None), ("Dogs", Some("Chasing cats")), ("Birds", None), ("Lazy cats", Some("cats again"))] {
+        figure_repository.create(None, Figure { description: description.map(String::from), ..figure(title, 0) }).await.unwrap();
+    }
+
+    SearchService::new(figure_repository, profile_repository)
+}
+
+#[tokio::test]
+pub async fn search_figures_ranked() {
+    let search_service = setup().await;
+
+    let figures = search_service.search_figures("Cats".to_string(), 0, 10).await.unwrap();
+
+    // Matches in the title rank above matches in the description only
+    assert_eq!(figures.iter().map(|figure| figure.title.as_str()).collect::<Vec<_>>(), vec!["Lazy cats", "Sleeping cats", "Dogs"]);
+}
+
+#[tokio::test]
+pub async fn search_figures_paginated() {
+    let search_service = setup().await;
+
+    let first_page = search_service.search_figures("cats".to_string(), 0, 2).await.unwrap();
+    let second_page = search_service.search_figures("cats".to_string(), 2, 2).await.unwrap();
+
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(second_page.iter().map(|figure| figure.title.as_str()).collect::<Vec<_>>(), vec!["Dogs"]);
+}
+
+#[tokio::test]
+pub async fn search_profiles() {
+    let search_service = setup().await;
+
+    let profiles = search_service.search_profiles("cats".to_string(), 0, 10).await.unwrap();
+
+    assert_eq!(profiles.iter().map(|profile| profile.username.as_str()).collect::<Vec<_>>(), vec!["other"]);
+}
+
+#[tokio::test]
+pub async fn search_invalid_query() {
+    let search_service = setup().await;
+
+    let empty_query = search_service.search_figures("   ".to_string(), 0, 10).await;
+    let long_query = search_service.search_profiles("a".repeat(101), 0, 10).await;
+
+    assert_eq!(empty_query.err(), Some(ServerError::InvalidSearchQuery));
+    assert_eq!(long_query.err(), Some(ServerError::InvalidSearchQuery));
+}
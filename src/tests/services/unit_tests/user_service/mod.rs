@@ -1 +1,10 @@
 mod test_signup;
+mod test_admin;
+mod test_change_password;
+mod test_password_reset;
+mod test_email_verification;
+mod test_sessions;
+mod test_session_policy;
+mod test_two_factor;
+mod test_external_identity;
+mod test_password_hash_upgrade;
\ No newline at end of file
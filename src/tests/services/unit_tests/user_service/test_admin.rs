@@ -0,0 +1,86 @@
+use crate::entities::dtos::session_dtos::{Authentication, ClientInfo};
+use crate::repositories::traits::UserRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::traits::UserServiceTrait;
+use crate::tests::mocks::fixtures::{TestUserService, UserServiceBuilder};
+use crate::tests::mocks::repositories::mock_session_repository::MockSessionRepository;
+use crate::tests::mocks::repositories::mock_user_repository::MockUserRepository;
+
+// Signs up "first" (user 0) and "second" (user 1), each with one session
+async fn setup() -> (TestUserService, MockUserRepository, MockSessionRepository, Vec<String>) {
+    let builder = UserServiceBuilder::new();
+    let user_service = builder.build();
+    let (_, first_session) = user_service.signup_user("first@test.test".to_string(), "test1234".to_string(), "first".to_string(), ClientInfo::default()).await.unwrap();
+    let (_, second_session) = user_service.signup_user("second@test.test".to_string(), "test1234".to_string(), "second".to_string(), ClientInfo::default()).await.unwrap();
+    (user_service, builder.user_repository, builder.session_repository, vec![first_session.get_id(), second_session.get_id()])
+}
+
+#[tokio::test]
+pub async fn suspend_user() {
+    let (user_service, user_repository, session_repository, session_ids) = setup().await;
+
+    user_service.set_user_suspended(0, true).await.unwrap();
+    let saved_user = user_repository.find_one_by_id(None, 0).await.unwrap();
+    let signin_result = user_service.authenticate_user("first@test.test".to_string(), "test1234".to_string(), ClientInfo::default(), false).await;
+
+    assert!(saved_user.suspended);
+    // Existing sessions should be gone and new ones can't be created
+    assert_eq!(session_repository.find_by_id(&session_ids[0]), Err(ServerError::ResourceNotFound));
+    assert_eq!(signin_result, Err(ServerError::AccountSuspended));
+    // Other users shouldn't be affected
+    assert!(session_repository.find_by_id(&session_ids[1]).is_ok());
+}
+
+#[tokio::test]
+pub async fn unsuspend_user() {
+    let (user_service, user_repository, _, _) = setup().await;
+
+    user_service.set_user_suspended(0, true).await.unwrap();
+    user_service.set_user_suspended(0, false).await.unwrap();
+    let saved_user = user_repository.find_one_by_id(None, 0).await.unwrap();
+    let signin_result = user_service.authenticate_user("first@test.test".to_string(), "test1234".to_string(), ClientInfo::default(), false).await;
+
+    assert!(!saved_user.suspended);
+    assert!(signin_result.is_ok());
+}
+
+#[tokio::test]
+pub async fn suspend_non_existing_user() {
+    let (user_service, _, _, _) = setup().await;
+
+    let result = user_service.set_user_suspended(2, true).await;
+
+    assert_eq!(result, Err(ServerError::ResourceNotFound));
+}
+
+#[tokio::test]
+pub async fn invalidate_user_sessions() {
+    let (user_service, _, session_repository, session_ids) = setup().await;
+    // Second session for the first user
+    let other_session = match user_service.authenticate_user("first@test.test".to_string(), "test1234".to_string(), ClientInfo::default(), false).await.unwrap() {
+        Authentication::SignedIn(_, session) => session,
+        Authentication::TwoFactorRequired(_) => panic!("Two factor authentication isn't enabled")
+    };
+
+    user_service.invalidate_user_sessions(0).await.unwrap();
+
+    assert_eq!(session_repository.find_by_id(&session_ids[0]), Err(ServerError::ResourceNotFound));
+    assert_eq!(session_repository.find_by_id(&other_session.get_id()), Err(ServerError::ResourceNotFound));
+    assert!(session_repository.find_by_id(&session_ids[1]).is_ok());
+}
+
+#[tokio::test]
+pub async fn find_users() {
+    let (user_service, _, _, _) = setup().await;
+
+    let all_users = user_service.find_users_starting_from_id(None, None, 10).await.unwrap();
+    let by_username = user_service.find_users_starting_from_id(None, Some("SEC".to_string()), 10).await.unwrap();
+    let by_email = user_service.find_users_starting_from_id(None, Some("first@".to_string()), 10).await.unwrap();
+    let next_page = user_service.find_users_starting_from_id(Some(1), None, 10).await.unwrap();
+
+    // Newest users come first
+    assert_eq!(all_users.iter().map(|user| user.user.id).collect::<Vec<_>>(), vec![1, 0]);
+    assert_eq!(by_username.iter().map(|user| user.profile.username.as_str()).collect::<Vec<_>>(), vec!["second"]);
+    assert_eq!(by_email.iter().map(|user| user.user.id).collect::<Vec<_>>(), vec![0]);
+    assert_eq!(next_page.iter().map(|user| user.user.id).collect::<Vec<_>>(), vec![0]);
+}
\ No newline at end of file
@@ -0,0 +1,72 @@
+use crate::entities::dtos::session_dtos::{Authentication, ClientInfo};
+use crate::repositories::traits::UserRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::traits::UserServiceTrait;
+use crate::tests::mocks::fixtures::{TestUserService, UserServiceBuilder};
+use crate::tests::mocks::repositories::mock_session_repository::MockSessionRepository;
+use crate::tests::mocks::repositories::mock_user_repository::MockUserRepository;
+
+// Signs up a user with two sessions
+async fn setup() -> (TestUserService, MockUserRepository, MockSessionRepository, Vec<String>) {
+    let builder = UserServiceBuilder::new();
+    let user_service = builder.build();
+    let (_, first_session) = user_service.signup_user("test@test.test".to_string(), "test1234".to_string(), "test".to_string(), ClientInfo::default()).await.unwrap();
+    let second_session = match user_service.authenticate_user("test@test.test".to_string(), "test1234".to_string(), ClientInfo::default(), false).await.unwrap() {
+        Authentication::SignedIn(_, session) => session,
+        Authentication::TwoFactorRequired(_) => panic!("Two factor authentication isn't enabled")
+    };
+    (user_service, builder.user_repository, builder.session_repository, vec![first_session.get_id(), second_session.get_id()])
+}
+
+#[tokio::test]
+pub async fn change_password() {
+    let (user_service, _, session_repository, session_ids) = setup().await;
+
+    let new_session = user_service.change_password(0, "test1234".to_string(), "new-password".to_string(), ClientInfo::default(), false).await.unwrap();
+
+    // All previous sessions should be removed, only the new one remains
+    assert_eq!(session_repository.find_by_id(&session_ids[0]), Err(ServerError::ResourceNotFound));
+    assert_eq!(session_repository.find_by_id(&session_ids[1]), Err(ServerError::ResourceNotFound));
+    assert!(session_repository.find_by_id(&new_session.get_id()).is_ok());
+    assert_eq!(new_session.get_user_id(), 0);
+
+    let old_password_result = user_service.authenticate_user("test@test.test".to_string(), "test1234".to_string(), ClientInfo::default(), false).await;
+    let new_password_result = user_service.authenticate_user("test@test.test".to_string(), "new-password".to_string(), ClientInfo::default(), false).await;
+    assert_eq!(old_password_result, Err(ServerError::WrongPassword));
+    assert!(new_password_result.is_ok());
+    assert!(!new_session.is_persistent());
+}
+
+#[tokio::test]
+pub async fn change_password_keeps_remember_me() {
+    let (user_service, _, _, _) = setup().await;
+
+    let new_session = user_service.change_password(0, "test1234".to_string(), "new-password".to_string(), ClientInfo::default(), true).await.unwrap();
+
+    assert!(new_session.is_persistent());
+}
+
+#[tokio::test]
+pub async fn change_password_wrong_current_password() {
+    let (user_service, user_repository, session_repository, session_ids) = setup().await;
+    let password_hash = user_repository.find_one_by_id(None, 0).await.unwrap().password;
+
+    let result = user_service.change_password(0, "wrong-password".to_string(), "new-password".to_string(), ClientInfo::default(), false).await;
+
+    assert_eq!(result, Err(ServerError::WrongPassword));
+    assert_eq!(user_repository.find_one_by_id(None, 0).await.unwrap().password, password_hash);
+    // Sessions should be left alone
+    assert!(session_repository.find_by_id(&session_ids[0]).is_ok());
+    assert!(session_repository.find_by_id(&session_ids[1]).is_ok());
+}
+
+#[tokio::test]
+pub async fn change_password_new_password_too_short() {
+    let (user_service, user_repository, _, _) = setup().await;
+    let password_hash = user_repository.find_one_by_id(None, 0).await.unwrap().password;
+
+    let result = user_service.change_password(0, "test1234".to_string(), "1234567".to_string(), ClientInfo::default(), false).await;
+
+    assert_eq!(result, Err(ServerError::PasswordTooShort));
+    assert_eq!(user_repository.find_one_by_id(None, 0).await.unwrap().password, password_hash);
+}
\ No newline at end of file
@@ -0,0 +1,84 @@
+use crate::entities::dtos::session_dtos::ClientInfo;
+use crate::repositories::traits::UserRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::traits::UserServiceTrait;
+use crate::tests::mocks::fixtures::{TestUserService, UserServiceBuilder};
+use crate::tests::mocks::mock_mailer::MockMailer;
+use crate::tests::mocks::repositories::mock_user_repository::MockUserRepository;
+
+async fn setup() -> (TestUserService, MockUserRepository, MockMailer) {
+    let builder = UserServiceBuilder::new();
+    let user_service = builder.build();
+    user_service.signup_user("test@test.test".to_string(), "test1234".to_string(), "test".to_string(), ClientInfo::default()).await.unwrap();
+    (user_service, builder.user_repository, builder.mailer)
+}
+
+// Token from the link in the last sent mail
+fn token_from_mail(mailer: &MockMailer) -> String {
+    let mail = mailer.sent().pop().unwrap();
+    let start = mail.body.find("https://frontend.test/verify-email?token=").unwrap() + "https://frontend.test/verify-email?token=".len();
+    mail.body[start..].split_whitespace().next().unwrap().to_string()
+}
+
+#[tokio::test]
+pub async fn signup_sends_verification() {
+    let (user_service, user_repository, mailer) = setup().await;
+
+    let mail = mailer.sent().pop().unwrap();
+
+    assert_eq!(mail.to, "test@test.test");
+    assert_eq!(mail.subject, "Verify your email address");
+    assert_eq!(user_repository.find_one_by_id(None, 0).await.unwrap().verified_at, None);
+    assert_eq!(user_service.is_email_verified(0).await, Ok(false));
+}
+
+#[tokio::test]
+pub async fn verify_email() {
+    let (user_service, user_repository, mailer) = setup().await;
+
+    user_service.verify_email(token_from_mail(&mailer)).await.unwrap();
+
+    assert!(user_repository.find_one_by_id(None, 0).await.unwrap().verified_at.is_some());
+    assert_eq!(user_service.is_email_verified(0).await, Ok(true));
+}
+
+#[tokio::test]
+pub async fn verify_email_token_is_single_use() {
+    let (user_service, _, mailer) = setup().await;
+    let token = token_from_mail(&mailer);
+
+    user_service.verify_email(token.clone()).await.unwrap();
+    let result = user_service.verify_email(token).await;
+
+    assert_eq!(result, Err(ServerError::InvalidToken));
+}
+
+#[tokio::test]
+pub async fn verify_email_invalid_token() {
+    let (user_service, _, _) = setup().await;
+
+    let result = user_service.verify_email("invalid".to_string()).await;
+
+    assert_eq!(result, Err(ServerError::InvalidToken));
+    assert_eq!(user_service.is_email_verified(0).await, Ok(false));
+}
+
+#[tokio::test]
+pub async fn resend_email_verification() {
+    let (user_service, _, mailer) = setup().await;
+
+    user_service.send_email_verification(0).await.unwrap();
+    // The resent mail holds a new token that can be used
+    let tokens: Vec<String> = mailer.sent().iter()
+        .map(|mail| {
+            let start = mail.body.find("?token=").unwrap() + "?token=".len();
+            mail.body[start..].to_string()
+        })
+        .collect();
+    user_service.verify_email(tokens[1].clone()).await.unwrap();
+
+    assert_eq!(tokens.len(), 2);
+    assert_ne!(tokens[0], tokens[1]);
+    assert_eq!(user_service.is_email_verified(0).await, Ok(true));
+    assert_eq!(user_service.send_email_verification(0).await, Err(ServerError::EmailAlreadyVerified));
+}
\ No newline at end of file
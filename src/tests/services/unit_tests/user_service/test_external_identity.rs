@@ -0,0 +1,114 @@
+use crate::entities::dtos::profile_dto::ProfileDTO;
+use crate::entities::dtos::session_dtos::{Authentication, ClientInfo};
+use crate::entities::external_identity::ExternalIdentity;
+use crate::repositories::traits::{ProfileRepositoryTrait, UserRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::services::traits::UserServiceTrait;
+use crate::tests::mocks::fixtures::{TestUserService, UserServiceBuilder};
+use crate::tests::mocks::mock_mailer::MockMailer;
+use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
+use crate::tests::mocks::repositories::mock_user_repository::MockUserRepository;
+
+// Signs up a user with the email test@test.test and the username test
+async fn setup() -> (TestUserService, MockUserRepository, MockProfileRepository, MockMailer) {
+    let builder = UserServiceBuilder::new();
+    let user_service = builder.build();
+    user_service.signup_user("test@test.test".to_string(), "test1234".to_string(), "test".to_string(), ClientInfo::default()).await.unwrap();
+    (user_service, builder.user_repository, builder.profile_repository, builder.mailer)
+}
+
+fn identity(subject: &str, email: &str, email_verified: bool, preferred_username: Option<&str>) -> ExternalIdentity {
+    ExternalIdentity {
+        issuer: "https://idp.test".to_string(),
+        subject: subject.to_string(),
+        email: Some(email.to_string()),
+        email_verified,
+        preferred_username: preferred_username.map(str::to_string),
+    }
+}
+
+async fn sign_in(user_service: &TestUserService, identity: ExternalIdentity) -> Result<ProfileDTO, ServerError> {
+    match user_service.sign_in_with_external_identity(identity, ClientInfo::default(), false).await? {
+        Authentication::SignedIn(profile, _session) => Ok(profile),
+        Authentication::TwoFactorRequired(_) => panic!("Two factor authentication shouldn't be required")
+    }
+}
+
+#[tokio::test]
+pub async fn sign_in_creates_user() {
+    let (user_service, user_repository, profile_repository, mailer) = setup().await;
+
+    let profile = sign_in(&user_service, identity("subject", "New@Test.test", true, Some("new.user"))).await.unwrap();
+    assert_eq!(profile.username, "newuser");
+    let user = user_repository.find_one_by_email(None, "new@test.test".to_string()).await.unwrap();
+    assert_eq!(profile_repository.find_by_user_id(None, user.id).await.unwrap().id, profile.id);
+    // Verified by the provider, so no verification mail is sent
+    assert!(user.verified_at.is_some());
+    assert_eq!(mailer.sent().len(), 1);
+
+    // Signing in again finds the linked user, even when the email changed at the provider
+    let again = sign_in(&user_service, identity("subject", "changed@test.test", true, None)).await.unwrap();
+    assert_eq!(again.id, profile.id);
+    assert_eq!(profile_repository.get_total_profiles_count(None).await.unwrap(), 2);
+}
+
+#[tokio::test]
+pub async fn sign_in_creates_user_with_unverified_email() {
+    let (user_service, user_repository, _profile_repository, mailer) = setup().await;
+
+    let profile = sign_in(&user_service, identity("subject", "new@test.test", false, None)).await.unwrap();
+    assert_eq!(profile.username, "new");
+    let user = user_repository.find_one_by_email(None, "new@test.test".to_string()).await.unwrap();
+    assert!(user.verified_at.is_none());
+    assert_eq!(mailer.sent().last().unwrap().to, "new@test.test");
+}
+
+#[tokio::test]
+pub async fn sign_in_with_taken_username() {
+    let (user_service, _user_repository, _profile_repository, _mailer) = setup().await;
+
+    let profile = sign_in(&user_service, identity("subject", "new@test.test", true, Some("test"))).await.unwrap();
+    assert!(profile.username.starts_with("test-"));
+    assert_eq!(profile.username.len(), 9);
+}
+
+#[tokio::test]
+pub async fn sign_in_links_user_with_verified_email() {
+    let (user_service, user_repository, profile_repository, _mailer) = setup().await;
+    user_repository.update_verified_at(None, 0).await.unwrap();
+
+    let profile = sign_in(&user_service, identity("subject", "test@test.test", true, None)).await.unwrap();
+    assert_eq!(profile.id, profile_repository.find_by_user_id(None, 0).await.unwrap().id);
+    assert_eq!(user_repository.find_one_by_external_identity(None, "https://idp.test", "subject").await.unwrap().id, 0);
+}
+
+#[tokio::test]
+pub async fn sign_in_with_unverified_email_of_user() {
+    let (user_service, user_repository, _profile_repository, _mailer) = setup().await;
+
+    // The user didn't verify the email
+    assert_eq!(sign_in(&user_service, identity("subject", "test@test.test", true, None)).await, Err(ServerError::EmailAlreadyInUse));
+    // The provider didn't verify the email
+    user_repository.update_verified_at(None, 0).await.unwrap();
+    assert_eq!(sign_in(&user_service, identity("subject", "test@test.test", false, None)).await, Err(ServerError::EmailAlreadyInUse));
+    assert_eq!(user_repository.find_one_by_external_identity(None, "https://idp.test", "subject").await, Err(ServerError::ResourceNotFound));
+}
+
+#[tokio::test]
+pub async fn sign_in_without_email() {
+    let (user_service, _user_repository, _profile_repository, _mailer) = setup().await;
+    let mut identity = identity("subject", "", true, None);
+    identity.email = None;
+
+    assert_eq!(sign_in(&user_service, identity).await, Err(ServerError::InvalidEmail));
+}
+
+#[tokio::test]
+pub async fn sign_in_of_suspended_user() {
+    let (user_service, user_repository, _profile_repository, _mailer) = setup().await;
+    user_repository.update_verified_at(None, 0).await.unwrap();
+    sign_in(&user_service, identity("subject", "test@test.test", true, None)).await.unwrap();
+    user_service.set_user_suspended(0, true).await.unwrap();
+
+    assert_eq!(sign_in(&user_service, identity("subject", "test@test.test", true, None)).await, Err(ServerError::AccountSuspended));
+}
\ No newline at end of file
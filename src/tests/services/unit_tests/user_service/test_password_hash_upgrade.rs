@@ -0,0 +1,59 @@
+use crate::entities::dtos::session_dtos::ClientInfo;
+use crate::repositories::traits::{ProfileRepositoryTrait, UserRepositoryTrait};
+use crate::server_errors::ServerError;
+use crate::services::traits::UserServiceTrait;
+use crate::tests::mocks::fixtures::{TestUserService, UserServiceBuilder};
+use crate::tests::mocks::repositories::mock_user_repository::MockUserRepository;
+use crate::utilities::password::{hash_password, PasswordHashPolicy};
+
+const WEAK_POLICY: PasswordHashPolicy = PasswordHashPolicy {
+    memory_cost: 1024,
+    time_cost: 1,
+    parallelism: 1,
+};
+
+// A user whose password was hashed with the given policy, signing in with the default policy
+async fn setup(policy: PasswordHashPolicy) -> (TestUserService, MockUserRepository, String) {
+    let builder = UserServiceBuilder::new().with_password_hash_policy(PasswordHashPolicy::default());
+
+    let password_hash = hash_password("test1234".to_string(), policy).await.unwrap();
+    let user = builder.user_repository.create(None, "test@test.test".to_string(), password_hash.clone()).await.unwrap();
+    builder.profile_repository.create(None, "test".to_string(), user.id).await.unwrap();
+
+    let user_service = builder.build();
+    (user_service, builder.user_repository, password_hash)
+}
+
+#[tokio::test]
+pub async fn sign_in_upgrades_weak_hash() {
+    let (user_service, user_repository, weak_hash) = setup(WEAK_POLICY).await;
+
+    user_service.authenticate_user("test@test.test".to_string(), "test1234".to_string(), ClientInfo::default(), false).await.unwrap();
+    let saved_user = user_repository.find_one_by_id(None, 0).await.unwrap();
+
+    assert_ne!(saved_user.password, weak_hash);
+    assert!(!PasswordHashPolicy::default().needs_rehash(&saved_user.password));
+    // The password still works with the new hash
+    assert!(user_service.authenticate_user("test@test.test".to_string(), "test1234".to_string(), ClientInfo::default(), false).await.is_ok());
+}
+
+#[tokio::test]
+pub async fn sign_in_keeps_hash_meeting_policy() {
+    let (user_service, user_repository, password_hash) = setup(PasswordHashPolicy::default()).await;
+
+    user_service.authenticate_user("test@test.test".to_string(), "test1234".to_string(), ClientInfo::default(), false).await.unwrap();
+    let saved_user = user_repository.find_one_by_id(None, 0).await.unwrap();
+
+    assert_eq!(saved_user.password, password_hash);
+}
+
+#[tokio::test]
+pub async fn failed_sign_in_keeps_weak_hash() {
+    let (user_service, user_repository, weak_hash) = setup(WEAK_POLICY).await;
+
+    let result = user_service.authenticate_user("test@test.test".to_string(), "wrong-password".to_string(), ClientInfo::default(), false).await;
+    let saved_user = user_repository.find_one_by_id(None, 0).await.unwrap();
+
+    assert_eq!(result.err(), Some(ServerError::WrongPassword));
+    assert_eq!(saved_user.password, weak_hash);
+}
\ No newline at end of file
@@ -0,0 +1,86 @@
+use crate::entities::dtos::session_dtos::ClientInfo;
+use crate::repositories::traits::UserRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::traits::UserServiceTrait;
+use crate::tests::mocks::fixtures::{TestUserService, UserServiceBuilder};
+use crate::tests::mocks::mock_mailer::MockMailer;
+use crate::tests::mocks::repositories::mock_session_repository::MockSessionRepository;
+use crate::tests::mocks::repositories::mock_user_repository::MockUserRepository;
+
+// Signs up a user with one session
+async fn setup() -> (TestUserService, MockUserRepository, MockSessionRepository, MockMailer, String) {
+    let builder = UserServiceBuilder::new();
+    let user_service = builder.build();
+    let (_, session) = user_service.signup_user("test@test.test".to_string(), "test1234".to_string(), "test".to_string(), ClientInfo::default()).await.unwrap();
+    (user_service, builder.user_repository, builder.session_repository, builder.mailer, session.get_id())
+}
+
+// Token from the link in the last sent mail
+fn token_from_mail(mailer: &MockMailer) -> String {
+    let mail = mailer.sent().pop().unwrap();
+    let start = mail.body.find("https://frontend.test/reset-password?token=").unwrap() + "https://frontend.test/reset-password?token=".len();
+    mail.body[start..].split_whitespace().next().unwrap().to_string()
+}
+
+#[tokio::test]
+pub async fn reset_password() {
+    let (user_service, _, session_repository, mailer, session_id) = setup().await;
+
+    user_service.request_password_reset("test@test.test".to_string()).await.unwrap();
+    let token = token_from_mail(&mailer);
+    user_service.reset_password(token, "new-password".to_string()).await.unwrap();
+
+    let mail = mailer.sent().pop().unwrap();
+    assert_eq!(mail.to, "test@test.test");
+    assert_eq!(mail.subject, "Reset your password");
+    // Sessions should be removed as they could belong to whoever had access to the account
+    assert_eq!(session_repository.find_by_id(&session_id), Err(ServerError::ResourceNotFound));
+    assert!(user_service.authenticate_user("test@test.test".to_string(), "new-password".to_string(), ClientInfo::default(), false).await.is_ok());
+}
+
+#[tokio::test]
+pub async fn reset_password_token_is_single_use() {
+    let (user_service, _, _, mailer, _) = setup().await;
+
+    user_service.request_password_reset("test@test.test".to_string()).await.unwrap();
+    let token = token_from_mail(&mailer);
+    user_service.reset_password(token.clone(), "new-password".to_string()).await.unwrap();
+    let result = user_service.reset_password(token, "other-password".to_string()).await;
+
+    assert_eq!(result, Err(ServerError::InvalidToken));
+}
+
+#[tokio::test]
+pub async fn request_password_reset_unknown_email() {
+    let (user_service, _, _, mailer, _) = setup().await;
+
+    let result = user_service.request_password_reset("unknown@test.test".to_string()).await;
+
+    // Same result as for an existing email, but nothing is sent besides the verification mail of the signup
+    assert_eq!(result, Ok(()));
+    assert_eq!(mailer.sent().len(), 1);
+}
+
+#[tokio::test]
+pub async fn reset_password_invalid_token() {
+    let (user_service, user_repository, _, _, _) = setup().await;
+    let password_hash = user_repository.find_one_by_id(None, 0).await.unwrap().password;
+
+    let result = user_service.reset_password("invalid".to_string(), "new-password".to_string()).await;
+
+    assert_eq!(result, Err(ServerError::InvalidToken));
+    assert_eq!(user_repository.find_one_by_id(None, 0).await.unwrap().password, password_hash);
+}
+
+#[tokio::test]
+pub async fn reset_password_too_short_keeps_token() {
+    let (user_service, _, _, mailer, _) = setup().await;
+
+    user_service.request_password_reset("test@test.test".to_string()).await.unwrap();
+    let token = token_from_mail(&mailer);
+    let result = user_service.reset_password(token.clone(), "1234567".to_string()).await;
+
+    assert_eq!(result, Err(ServerError::PasswordTooShort));
+    // The token can still be used with a valid password
+    assert_eq!(user_service.reset_password(token, "new-password".to_string()).await, Ok(()));
+}
\ No newline at end of file
@@ -0,0 +1,143 @@
+use chrono::{Duration, Utc};
+use crate::entities::dtos::session_dtos::{Authentication, ClientInfo, Session, SessionPolicies, SessionPolicy};
+use crate::entities::user::Role;
+use crate::repositories::traits::SessionRepositoryTrait;
+use crate::server_errors::ServerError;
+use crate::services::traits::UserServiceTrait;
+use crate::tests::mocks::fixtures::{TestUserService, UserServiceBuilder};
+use crate::tests::mocks::repositories::mock_session_repository::MockSessionRepository;
+
+const POLICIES: SessionPolicies = SessionPolicies {
+    default: SessionPolicy {
+        idle_timeout: 3600,
+        max_lifetime: 86400,
+    },
+    remember_me: SessionPolicy {
+        idle_timeout: 86400,
+        max_lifetime: 86400 * 7,
+    },
+};
+
+// Signs up a user
+async fn setup() -> (TestUserService, MockSessionRepository) {
+    let builder = UserServiceBuilder::new().with_session_policies(POLICIES);
+    let user_service = builder.build();
+    user_service.signup_user("test@test.test".to_string(), "test1234".to_string(), "test".to_string(), ClientInfo::default()).await.unwrap();
+    (user_service, builder.session_repository)
+}
+
+#[tokio::test]
+pub async fn sign_in_uses_default_policy() {
+    let (user_service, _) = setup().await;
+
+    let session = match user_service.authenticate_user("test@test.test".to_string(), "test1234".to_string(), ClientInfo::default(), false).await.unwrap() {
+        Authentication::SignedIn(_, session) => session,
+        Authentication::TwoFactorRequired(_) => panic!("Two factor authentication isn't enabled")
+    };
+
+    assert!(!session.is_persistent());
+    assert_eq!(session.get_time_until_expiration(), Some(3600));
+    assert_eq!(session.get_expires_at(), Some(session.get_created_at() + Duration::seconds(86400)));
+}
+
+#[tokio::test]
+pub async fn sign_in_with_remember_me_uses_remember_me_policy() {
+    let (user_service, _) = setup().await;
+
+    let session = match user_service.authenticate_user("test@test.test".to_string(), "test1234".to_string(), ClientInfo::default(), true).await.unwrap() {
+        Authentication::SignedIn(_, session) => session,
+        Authentication::TwoFactorRequired(_) => panic!("Two factor authentication isn't enabled")
+    };
+
+    assert!(session.is_persistent());
+    assert_eq!(session.get_time_until_expiration(), Some(86400));
+    assert_eq!(session.get_expires_at(), Some(session.get_created_at() + Duration::seconds(86400 * 7)));
+}
+
+#[tokio::test]
+pub async fn find_session_past_max_lifetime() {
+    let (user_service, session_repository) = setup().await;
+    let created_at = Utc::now() - Duration::seconds(86400 * 2);
+    let session = Session::new("expired".to_string(), 0, 0, Role::User, ClientInfo::default(), None)
+        .with_timestamps(created_at, Utc::now())
+        .with_expiry(false, Some(created_at + Duration::seconds(86400)));
+    session_repository.create(session).await.unwrap();
+
+    let result = user_service.find_session("expired".to_string()).await;
+
+    assert_eq!(result, Err(ServerError::ResourceNotFound));
+    // Expired sessions should be removed from the store
+    assert_eq!(session_repository.find_by_id("expired"), Err(ServerError::ResourceNotFound));
+}
+
+#[tokio::test]
+pub async fn find_session_without_expiry_uses_creation_time() {
+    let (user_service, session_repository) = setup().await;
+    // Stored before sessions had a max lifetime
+    let created_at = Utc::now() - Duration::seconds(86400 * 2);
+    let session = Session::new("old".to_string(), 0, 0, Role::User, ClientInfo::default(), None)
+        .with_timestamps(created_at, Utc::now());
+    session_repository.create(session).await.unwrap();
+
+    let result = user_service.find_session("old".to_string()).await;
+
+    assert_eq!(result, Err(ServerError::ResourceNotFound));
+}
+
+#[tokio::test]
+pub async fn find_session_updates_last_seen() {
+    let (user_service, session_repository) = setup().await;
+    let last_seen_at = Utc::now() - Duration::minutes(10);
+    let session = Session::new("active".to_string(), 0, 0, Role::User, ClientInfo::default(), None)
+        .with_timestamps(last_seen_at, last_seen_at)
+        .with_expiry(false, Some(last_seen_at + Duration::seconds(86400)));
+    session_repository.create(session).await.unwrap();
+
+    let session = user_service.find_session("active".to_string()).await.unwrap();
+
+    assert!(session.get_last_seen_at() > last_seen_at);
+    assert_eq!(session_repository.find_by_id("active").unwrap().get_last_seen_at(), session.get_last_seen_at());
+}
+
+#[tokio::test]
+pub async fn find_session_extends_by_idle_timeout_of_its_policy() {
+    let (user_service, session_repository) = setup().await;
+    let now = Utc::now();
+    for (session_id, persistent) in [("default", false), ("remember_me", true)] {
+        let session = Session::new(session_id.to_string(), 0, 0, Role::User, ClientInfo::default(), None)
+            .with_timestamps(now, now)
+            .with_expiry(persistent, Some(now + Duration::seconds(86400 * 7)));
+        session_repository.create(session).await.unwrap();
+    }
+
+    user_service.find_session("default".to_string()).await.unwrap();
+    user_service.find_session("remember_me".to_string()).await.unwrap();
+
+    assert_eq!(session_repository.time_until_expiration("default"), Some(3600));
+    assert_eq!(session_repository.time_until_expiration("remember_me"), Some(86400));
+}
+
+#[tokio::test]
+pub async fn find_session_close_to_max_lifetime() {
+    let (user_service, session_repository) = setup().await;
+    let created_at = Utc::now() - Duration::seconds(86400 - 600);
+    let session = Session::new("ending".to_string(), 0, 0, Role::User, ClientInfo::default(), None)
+        .with_timestamps(created_at, Utc::now())
+        .with_expiry(false, Some(created_at + Duration::seconds(86400)));
+    session_repository.create(session).await.unwrap();
+
+    user_service.find_session("ending".to_string()).await.unwrap();
+
+    // Not extended past the max lifetime
+    let time_until_expiration = session_repository.time_until_expiration("ending").unwrap();
+    assert!(time_until_expiration <= 600 && time_until_expiration > 590);
+}
+
+#[test]
+pub fn time_until_expiration_is_capped_by_max_lifetime() {
+    let now = Utc::now();
+
+    assert_eq!(POLICIES.default.time_until_expiration(now + Duration::seconds(86400), now), Some(3600));
+    assert_eq!(POLICIES.default.time_until_expiration(now + Duration::seconds(60), now), Some(60));
+    assert_eq!(POLICIES.default.time_until_expiration(now, now), None);
+}
\ No newline at end of file
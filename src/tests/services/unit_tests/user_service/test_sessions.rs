@@ -0,0 +1,75 @@
+use crate::entities::dtos::session_dtos::{Authentication, ClientInfo};
+use crate::server_errors::ServerError;
+use crate::services::traits::UserServiceTrait;
+use crate::tests::mocks::fixtures::{TestUserService, UserServiceBuilder};
+use crate::tests::mocks::repositories::mock_session_repository::MockSessionRepository;
+use crate::utilities::token::hash_token;
+
+fn client(user_agent: &str) -> ClientInfo {
+    ClientInfo {
+        user_agent: Some(user_agent.to_string()),
+        ip: Some("127.0.0.1".to_string()),
+    }
+}
+
+// Signs up two users, the first one with two sessions
+async fn setup() -> (TestUserService, MockSessionRepository, Vec<String>) {
+    let builder = UserServiceBuilder::new();
+    let user_service = builder.build();
+    let (_, first_session) = user_service.signup_user("first@test.test".to_string(), "test1234".to_string(), "first".to_string(), client("laptop")).await.unwrap();
+    let second_session = match user_service.authenticate_user("first@test.test".to_string(), "test1234".to_string(), client("phone"), false).await.unwrap() {
+        Authentication::SignedIn(_, session) => session,
+        Authentication::TwoFactorRequired(_) => panic!("Two factor authentication isn't enabled")
+    };
+    let (_, other_user_session) = user_service.signup_user("second@test.test".to_string(), "test1234".to_string(), "second".to_string(), client("desktop")).await.unwrap();
+    (user_service, builder.session_repository, vec![first_session.get_id(), second_session.get_id(), other_user_session.get_id()])
+}
+
+#[tokio::test]
+pub async fn find_sessions() {
+    let (user_service, _, session_ids) = setup().await;
+
+    let sessions = user_service.find_sessions(0).await.unwrap();
+
+    // Sessions are only known by their public id, the hash of the session id
+    let mut found_public_ids: Vec<String> = sessions.iter().map(|session| session.public_id.clone()).collect();
+    found_public_ids.sort();
+    let mut expected_public_ids = vec![hash_token(&session_ids[0]), hash_token(&session_ids[1])];
+    expected_public_ids.sort();
+    assert_eq!(found_public_ids, expected_public_ids);
+    assert!(sessions.iter().any(|session| session.client == client("phone")));
+}
+
+#[tokio::test]
+pub async fn revoke_session() {
+    let (user_service, session_repository, session_ids) = setup().await;
+    let second_session = session_repository.find_by_id(&session_ids[1]).unwrap();
+
+    user_service.revoke_session(0, second_session.get_public_id()).await.unwrap();
+
+    assert!(session_repository.find_by_id(&session_ids[0]).is_ok());
+    assert_eq!(session_repository.find_by_id(&session_ids[1]), Err(ServerError::ResourceNotFound));
+}
+
+#[tokio::test]
+pub async fn revoke_session_of_other_user() {
+    let (user_service, session_repository, session_ids) = setup().await;
+    let other_user_session = session_repository.find_by_id(&session_ids[2]).unwrap();
+
+    let result = user_service.revoke_session(0, other_user_session.get_public_id()).await;
+
+    assert_eq!(result, Err(ServerError::ResourceNotFound));
+    assert!(session_repository.find_by_id(&session_ids[2]).is_ok());
+}
+
+#[tokio::test]
+pub async fn revoke_other_sessions() {
+    let (user_service, session_repository, session_ids) = setup().await;
+
+    user_service.revoke_other_sessions(0, session_ids[0].clone()).await.unwrap();
+
+    assert!(session_repository.find_by_id(&session_ids[0]).is_ok());
+    assert_eq!(session_repository.find_by_id(&session_ids[1]), Err(ServerError::ResourceNotFound));
+    // Sessions of other users should be left alone
+    assert!(session_repository.find_by_id(&session_ids[2]).is_ok());
+}
\ No newline at end of file
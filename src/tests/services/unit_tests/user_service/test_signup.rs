@@ -1,10 +1,13 @@
 use crate::entities::dtos::profile_dto::ProfileDTO;
-use crate::entities::dtos::session_dtos::Session;
-use crate::entities::user::User;
+use crate::entities::dtos::session_dtos::{ClientInfo, Session};
+use crate::entities::user::{Role, User};
 use crate::repositories::traits::UserRepositoryTrait;
 use crate::server_errors::ServerError;
+use crate::services::account_mailer::AccountMailer;
 use crate::services::traits::UserServiceTrait;
 use crate::services::user_service::UserService;
+use crate::tests::mocks::mock_mailer::MockMailer;
+use crate::tests::mocks::repositories::mock_one_time_token_repository::MockOneTimeTokenRepository;
 use crate::tests::mocks::repositories::mock_profile_repository::MockProfileRepository;
 use crate::tests::mocks::repositories::mock_session_repository::MockSessionRepository;
 use crate::tests::mocks::repositories::mock_transaction::MockTransactionCreator;
@@ -13,15 +16,17 @@ use crate::tests::mocks::utilities::secure_rand_generator::FakeRandomGenerator;
 
 #[tokio::test]
 pub async fn signup() {
-    let user_repository = MockUserRepository::new();
     let profile_repository = MockProfileRepository::new();
+    let user_repository = MockUserRepository::new(profile_repository.clone());
     let session_repository = MockSessionRepository::new();
+    let one_time_token_repository = MockOneTimeTokenRepository::new();
+    let account_mailer = AccountMailer::new(MockMailer::new(), "https://frontend.test".to_string());
     let transaction_creator = MockTransactionCreator::new();
     let random_number_generator = FakeRandomGenerator::new();
 
-    let user_service = UserService::new(transaction_creator, user_repository.clone(), profile_repository, session_repository, random_number_generator);
+    let user_service = UserService::new(transaction_creator, user_repository.clone(), profile_repository, session_repository, one_time_token_repository, account_mailer, random_number_generator);
 
-    let result = user_service.signup_user("test@test.test".to_string(), "test1234".to_string(), "test".to_string()).await;
+    let result = user_service.signup_user("test@test.test".to_string(), "test1234".to_string(), "test".to_string(), ClientInfo::default()).await;
     let (profile, session) = result.unwrap();
     let saved_user = user_repository.find_one_by_id(None, 0).await.unwrap();
     let expected_password = saved_user.password.clone();
@@ -29,7 +34,12 @@ pub async fn signup() {
         id: 0,
         email: "test@test.test".to_string(),
         password: expected_password, // Can't generate the same hash again due to salting
-        role: "user".to_string(),
+        role: Role::User,
+        suspended: false,
+        verified_at: None,
+        totp_secret: None,
+        totp_enabled_at: None,
+        totp_last_used_step: None,
     };
     let expected_profile = ProfileDTO {
         id: 0,
@@ -37,25 +47,30 @@ pub async fn signup() {
         display_name: None,
     };
     let expected_session = Session::new(
-        0.to_string(),
+        "token-0".to_string(),
         0,
         0,
+        Role::User,
+        ClientInfo::default(),
         session.get_time_until_expiration(),
-    );
+    ).with_timestamps(session.get_created_at(), session.get_last_seen_at())
+        .with_expiry(false, session.get_expires_at());
     assert_eq!((saved_user, profile, session), (expected_user, expected_profile, expected_session));
 }
 
 #[tokio::test]
 pub async fn signup_password_too_short() {
-    let user_repository = MockUserRepository::new();
     let profile_repository = MockProfileRepository::new();
+    let user_repository = MockUserRepository::new(profile_repository.clone());
     let session_repository = MockSessionRepository::new();
+    let one_time_token_repository = MockOneTimeTokenRepository::new();
+    let account_mailer = AccountMailer::new(MockMailer::new(), "https://frontend.test".to_string());
     let transaction_creator = MockTransactionCreator::new();
     let random_number_generator = FakeRandomGenerator::new();
 
-    let user_service = UserService::new(transaction_creator, user_repository.clone(), profile_repository, session_repository, random_number_generator);
+    let user_service = UserService::new(transaction_creator, user_repository.clone(), profile_repository, session_repository, one_time_token_repository, account_mailer, random_number_generator);
 
-    let signup_result = user_service.signup_user("test@test.test".to_string(), "1234567".to_string(), "test".to_string()).await;
+    let signup_result = user_service.signup_user("test@test.test".to_string(), "1234567".to_string(), "test".to_string(), ClientInfo::default()).await;
     let saved_user = user_repository.find_one_by_id(None, 0).await;
 
     assert_eq!(signup_result, Err(ServerError::PasswordTooShort));
@@ -65,15 +80,17 @@ pub async fn signup_password_too_short() {
 
 #[tokio::test]
 pub async fn password_too_long() {
-    let user_repository = MockUserRepository::new();
     let profile_repository = MockProfileRepository::new();
+    let user_repository = MockUserRepository::new(profile_repository.clone());
     let session_repository = MockSessionRepository::new();
+    let one_time_token_repository = MockOneTimeTokenRepository::new();
+    let account_mailer = AccountMailer::new(MockMailer::new(), "https://frontend.test".to_string());
     let transaction_creator = MockTransactionCreator::new();
     let random_number_generator = FakeRandomGenerator::new();
 
-    let user_service = UserService::new(transaction_creator, user_repository.clone(), profile_repository, session_repository, random_number_generator);
+    let user_service = UserService::new(transaction_creator, user_repository.clone(), profile_repository, session_repository, one_time_token_repository, account_mailer, random_number_generator);
 
-    let signup_result = user_service.signup_user("test@test.test".to_string(), "1111111111111111111111111111111111111111111111111111111111111".to_string(), "test".to_string()).await;
+    let signup_result = user_service.signup_user("test@test.test".to_string(), "1111111111111111111111111111111111111111111111111111111111111".to_string(), "test".to_string(), ClientInfo::default()).await;
     let saved_user = user_repository.find_one_by_id(None, 0).await;
 
     assert_eq!(signup_result, Err(ServerError::PasswordTooLong));
@@ -82,51 +99,53 @@ pub async fn password_too_long() {
 
 #[tokio::test]
 pub async fn signup_invalid_email() {
-    let user_repository = MockUserRepository::new();
     let profile_repository = MockProfileRepository::new();
+    let user_repository = MockUserRepository::new(profile_repository.clone());
     let session_repository = MockSessionRepository::new();
+    let one_time_token_repository = MockOneTimeTokenRepository::new();
+    let account_mailer = AccountMailer::new(MockMailer::new(), "https://frontend.test".to_string());
     let transaction_creator = MockTransactionCreator::new();
     let random_number_generator = FakeRandomGenerator::new();
 
-    let user_service = UserService::new(transaction_creator, user_repository.clone(), profile_repository, session_repository, random_number_generator);
+    let user_service = UserService::new(transaction_creator, user_repository.clone(), profile_repository, session_repository, one_time_token_repository, account_mailer, random_number_generator);
 
     // Missing @
-    let signup_result = user_service.signup_user("testtest.test".to_string(), "1234567".to_string(), "test".to_string()).await;
+    let signup_result = user_service.signup_user("testtest.test".to_string(), "1234567".to_string(), "test".to_string(), ClientInfo::default()).await;
     let saved_user = user_repository.find_one_by_id(None, 0).await;
 
     assert_eq!(signup_result, Err(ServerError::InvalidEmail));
     assert_eq!(saved_user, Err(ServerError::ResourceNotFound));
 
     // Missing tld
-    let signup_result = user_service.signup_user("test@test".to_string(), "1234567".to_string(), "test".to_string()).await;
+    let signup_result = user_service.signup_user("test@test".to_string(), "1234567".to_string(), "test".to_string(), ClientInfo::default()).await;
     let saved_user = user_repository.find_one_by_id(None, 0).await;
 
     assert_eq!(signup_result, Err(ServerError::InvalidEmail));
     assert_eq!(saved_user, Err(ServerError::ResourceNotFound));
 
     // Missing email username
-    let signup_result = user_service.signup_user("@test.test".to_string(), "1234567".to_string(), "test".to_string()).await;
+    let signup_result = user_service.signup_user("@test.test".to_string(), "1234567".to_string(), "test".to_string(), ClientInfo::default()).await;
     let saved_user = user_repository.find_one_by_id(None, 0).await;
 
     assert_eq!(signup_result, Err(ServerError::InvalidEmail));
     assert_eq!(saved_user, Err(ServerError::ResourceNotFound));
 
     // Only @
-    let signup_result = user_service.signup_user("@".to_string(), "1234567".to_string(), "test".to_string()).await;
+    let signup_result = user_service.signup_user("@".to_string(), "1234567".to_string(), "test".to_string(), ClientInfo::default()).await;
     let saved_user = user_repository.find_one_by_id(None, 0).await;
 
     assert_eq!(signup_result, Err(ServerError::InvalidEmail));
     assert_eq!(saved_user, Err(ServerError::ResourceNotFound));
 
     // Empty email
-    let signup_result = user_service.signup_user("".to_string(), "1234567".to_string(), "test".to_string()).await;
+    let signup_result = user_service.signup_user("".to_string(), "1234567".to_string(), "test".to_string(), ClientInfo::default()).await;
     let saved_user = user_repository.find_one_by_id(None, 0).await;
 
     assert_eq!(signup_result, Err(ServerError::InvalidEmail));
     assert_eq!(saved_user, Err(ServerError::ResourceNotFound));
 
     // Too long
-    let signup_result = user_service.signup_user("1234567890123456789012345678901234567890123456789012345678901".to_string(), "1234567".to_string(), "test".to_string()).await;
+    let signup_result = user_service.signup_user("1234567890123456789012345678901234567890123456789012345678901".to_string(), "1234567".to_string(), "test".to_string(), ClientInfo::default()).await;
     let saved_user = user_repository.find_one_by_id(None, 0).await;
 
     assert_eq!(signup_result, Err(ServerError::InvalidEmail));
@@ -135,15 +154,17 @@ pub async fn signup_invalid_email() {
 
 #[tokio::test]
 pub async fn signup_invalid_username() {
-    let user_repository = MockUserRepository::new();
     let profile_repository = MockProfileRepository::new();
+    let user_repository = MockUserRepository::new(profile_repository.clone());
     let session_repository = MockSessionRepository::new();
+    let one_time_token_repository = MockOneTimeTokenRepository::new();
+    let account_mailer = AccountMailer::new(MockMailer::new(), "https://frontend.test".to_string());
     let transaction_creator = MockTransactionCreator::new();
     let random_number_generator = FakeRandomGenerator::new();
 
-    let user_service = UserService::new(transaction_creator, user_repository.clone(), profile_repository, session_repository, random_number_generator);
+    let user_service = UserService::new(transaction_creator, user_repository.clone(), profile_repository, session_repository, one_time_token_repository, account_mailer, random_number_generator);
 
-    let signup_result = user_service.signup_user("test@test.test".to_string(), "1234567".to_string(), "".to_string()).await;
+    let signup_result = user_service.signup_user("test@test.test".to_string(), "1234567".to_string(), "".to_string(), ClientInfo::default()).await;
     let saved_user = user_repository.find_one_by_id(None, 0).await;
 
     assert_eq!(signup_result, Err(ServerError::InvalidUsername));
@@ -0,0 +1,174 @@
+use chrono::Utc;
+use crate::entities::dtos::session_dtos::{Authentication, ClientInfo};
+use crate::server_errors::ServerError;
+use crate::services::rate_limit_service::RateLimitService;
+use crate::services::traits::{RateLimitServiceTrait, UserServiceTrait};
+use crate::tests::mocks::fixtures::{TestUserService, UserServiceBuilder};
+use crate::tests::mocks::repositories::mock_rate_limit_repository::MockRateLimitRepository;
+use crate::utilities::totp;
+
+// Signs up a user and starts the enrolment, returns the decoded secret
+async fn setup() -> (TestUserService, Vec<u8>) {
+    let user_service = UserServiceBuilder::new().build();
+    user_service.signup_user("test@test.test".to_string(), "test1234".to_string(), "test".to_string(), ClientInfo::default()).await.unwrap();
+    let enrolment = user_service.start_totp_enrolment(0, "test1234".to_string()).await.unwrap();
+    (user_service, totp::base32_decode(&enrolment.secret).unwrap())
+}
+
+// Code of the current time step, offset by the given amount of steps
+fn code(secret: &[u8], offset: i64) -> String {
+    totp::generate_code(secret, totp::time_step(Utc::now().timestamp()) + offset).unwrap()
+}
+
+async fn sign_in(user_service: &TestUserService) -> Authentication {
+    user_service.authenticate_user("test@test.test".to_string(), "test1234".to_string(), ClientInfo::default(), false).await.unwrap()
+}
+
+async fn two_factor_token(user_service: &TestUserService) -> String {
+    match sign_in(user_service).await {
+        Authentication::TwoFactorRequired(token) => token,
+        Authentication::SignedIn(_, _) => panic!("Two factor authentication should be required")
+    }
+}
+
+#[tokio::test]
+pub async fn start_totp_enrolment() {
+    let user_service = UserServiceBuilder::new().build();
+    user_service.signup_user("test@test.test".to_string(), "test1234".to_string(), "test".to_string(), ClientInfo::default()).await.unwrap();
+
+    let enrolment = user_service.start_totp_enrolment(0, "test1234".to_string()).await.unwrap();
+
+    assert_eq!(totp::base32_decode(&enrolment.secret).unwrap().len(), 20);
+    assert!(enrolment.otpauth_uri.starts_with("otpauth://totp/Figure:test@test.test?"));
+    assert!(enrolment.otpauth_uri.contains(&format!("secret={}", enrolment.secret)));
+    // Not required until the enrolment is confirmed
+    assert!(matches!(sign_in(&user_service).await, Authentication::SignedIn(_, _)));
+}
+
+#[tokio::test]
+pub async fn start_totp_enrolment_with_wrong_password() {
+    let user_service = UserServiceBuilder::new().build();
+    user_service.signup_user("test@test.test".to_string(), "test1234".to_string(), "test".to_string(), ClientInfo::default()).await.unwrap();
+
+    let result = user_service.start_totp_enrolment(0, "wrong-password".to_string()).await;
+
+    assert!(matches!(result, Err(ServerError::WrongPassword)));
+    // No secret was set, so there is nothing to confirm
+    assert_eq!(user_service.confirm_totp_enrolment(0, "123456".to_string()).await, Err(ServerError::TwoFactorNotStarted));
+}
+
+#[tokio::test]
+pub async fn confirm_totp_enrolment() {
+    let (user_service, secret) = setup().await;
+
+    let recovery_codes = user_service.confirm_totp_enrolment(0, code(&secret, 0)).await.unwrap();
+
+    assert_eq!(recovery_codes.len(), 10);
+    assert_eq!(recovery_codes[0].len(), 19);
+    assert!(user_service.find_user_by_id(0).await.unwrap().user.two_factor_enabled);
+    assert!(matches!(user_service.start_totp_enrolment(0, "test1234".to_string()).await, Err(ServerError::TwoFactorAlreadyEnabled)));
+}
+
+#[tokio::test]
+pub async fn confirm_totp_enrolment_with_wrong_code() {
+    let (user_service, secret) = setup().await;
+    let wrong_code = code(&secret, 10);
+
+    let result = user_service.confirm_totp_enrolment(0, wrong_code).await;
+
+    assert_eq!(result, Err(ServerError::InvalidTwoFactorCode));
+    assert!(matches!(sign_in(&user_service).await, Authentication::SignedIn(_, _)));
+}
+
+#[tokio::test]
+pub async fn confirm_totp_enrolment_without_starting() {
+    let user_service = UserServiceBuilder::new().build();
+    user_service.signup_user("test@test.test".to_string(), "test1234".to_string(), "test".to_string(), ClientInfo::default()).await.unwrap();
+
+    let result = user_service.confirm_totp_enrolment(0, "123456".to_string()).await;
+
+    assert_eq!(result, Err(ServerError::TwoFactorNotStarted));
+}
+
+#[tokio::test]
+pub async fn sign_in_with_totp_code() {
+    let (user_service, secret) = setup().await;
+    user_service.confirm_totp_enrolment(0, code(&secret, 0)).await.unwrap();
+
+    let token = two_factor_token(&user_service).await;
+    // The code of the current step was used to confirm, the next step is accepted as well
+    let (profile, session, email) = user_service.complete_two_factor_sign_in(token, code(&secret, 1), ClientInfo::default(), false).await.unwrap();
+
+    assert_eq!(profile.id, 0);
+    assert_eq!(session.get_user_id(), 0);
+    assert_eq!(email, "test@test.test");
+}
+
+#[tokio::test]
+pub async fn sign_in_with_totp_code_resets_failed_sign_ins() {
+    let (user_service, secret) = setup().await;
+    user_service.confirm_totp_enrolment(0, code(&secret, 0)).await.unwrap();
+    let rate_limit_service = RateLimitService::new(MockRateLimitRepository::new());
+    // The email as entered at sign in, the failed sign ins are counted per lowercased email
+    for _ in 0..4 {
+        rate_limit_service.record_failed_sign_in("Test@Test.test", "203.0.113.1").await.unwrap();
+    }
+
+    let token = two_factor_token(&user_service).await;
+    let (_, _, email) = user_service.complete_two_factor_sign_in(token, code(&secret, 1), ClientInfo::default(), false).await.unwrap();
+    rate_limit_service.reset_failed_sign_ins(&email, "203.0.113.1").await.unwrap();
+    rate_limit_service.record_failed_sign_in("Test@Test.test", "203.0.113.1").await.unwrap();
+
+    // Without the reset this would have been the fifth failure in a row
+    assert_eq!(rate_limit_service.check_sign_in_lockout("Test@Test.test", "203.0.113.1").await, Ok(()));
+}
+
+#[tokio::test]
+pub async fn sign_in_with_used_totp_code() {
+    let (user_service, secret) = setup().await;
+    let used_code = code(&secret, 0);
+    user_service.confirm_totp_enrolment(0, used_code.clone()).await.unwrap();
+
+    let token = two_factor_token(&user_service).await;
+    let replay_result = user_service.complete_two_factor_sign_in(token.clone(), used_code, ClientInfo::default(), false).await;
+    // The token is used up by the wrong code
+    let retry_result = user_service.complete_two_factor_sign_in(token, code(&secret, 1), ClientInfo::default(), false).await;
+
+    assert_eq!(replay_result, Err(ServerError::InvalidTwoFactorCode));
+    assert_eq!(retry_result, Err(ServerError::InvalidToken));
+}
+
+#[tokio::test]
+pub async fn sign_in_with_recovery_code() {
+    let (user_service, secret) = setup().await;
+    let recovery_codes = user_service.confirm_totp_enrolment(0, code(&secret, 0)).await.unwrap();
+
+    let token = two_factor_token(&user_service).await;
+    // Case and dashes don't matter
+    let first_result = user_service.complete_two_factor_sign_in(token, recovery_codes[0].replace('-', "").to_uppercase(), ClientInfo::default(), false).await;
+    let token = two_factor_token(&user_service).await;
+    let second_result = user_service.complete_two_factor_sign_in(token, recovery_codes[0].clone(), ClientInfo::default(), false).await;
+
+    assert!(first_result.is_ok());
+    assert_eq!(second_result, Err(ServerError::InvalidTwoFactorCode));
+}
+
+#[tokio::test]
+pub async fn disable_totp() {
+    let (user_service, secret) = setup().await;
+    let recovery_codes = user_service.confirm_totp_enrolment(0, code(&secret, 0)).await.unwrap();
+
+    let wrong_password_result = user_service.disable_totp(0, "wrong-password".to_string()).await;
+    user_service.disable_totp(0, "test1234".to_string()).await.unwrap();
+
+    assert_eq!(wrong_password_result, Err(ServerError::WrongPassword));
+    assert!(matches!(sign_in(&user_service).await, Authentication::SignedIn(_, _)));
+    assert_eq!(user_service.disable_totp(0, "test1234".to_string()).await, Err(ServerError::TwoFactorNotEnabled));
+
+    // Recovery codes of the previous enrolment don't work after enrolling again
+    let secret = totp::base32_decode(&user_service.start_totp_enrolment(0, "test1234".to_string()).await.unwrap().secret).unwrap();
+    user_service.confirm_totp_enrolment(0, code(&secret, 0)).await.unwrap();
+    let token = two_factor_token(&user_service).await;
+    let result = user_service.complete_two_factor_sign_in(token, recovery_codes[0].clone(), ClientInfo::default(), false).await;
+    assert_eq!(result, Err(ServerError::InvalidTwoFactorCode));
+}
\ No newline at end of file
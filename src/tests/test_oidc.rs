@@ -0,0 +1,85 @@
+use chrono::Utc;
+use serde_json::{json, Value};
+use crate::entities::external_identity::ExternalIdentity;
+use crate::oidc::{Jwk, validate_id_token};
+use crate::server_errors::ServerError;
+use crate::tests::mocks::stand_in_identity_provider::{CLIENT_ID, jwks, KEY_ID, sign_id_token};
+
+const ISSUER: &str = "https://idp.test";
+const NONCE: &str = "nonce";
+
+fn keys() -> Vec<Jwk> {
+    serde_json::from_value(jwks()["keys"].clone()).unwrap()
+}
+
+fn claims() -> Value {
+    let now = Utc::now().timestamp();
+    json!({
+        "iss": ISSUER,
+        "sub": "subject",
+        "aud": CLIENT_ID,
+        "exp": now + 300,
+        "iat": now,
+        "nonce": NONCE,
+        "email": "test@test.test",
+        "email_verified": true,
+        "preferred_username": "test",
+    })
+}
+
+fn validate(claims: &Value) -> Result<ExternalIdentity, ServerError> {
+    let id_token = sign_id_token(&json!({ "alg": "RS256", "kid": KEY_ID }), claims);
+    validate_id_token(&id_token, &keys(), ISSUER, CLIENT_ID, NONCE, Utc::now().timestamp())
+}
+
+fn with(mut claims: Value, name: &str, value: Value) -> Value {
+    claims[name] = value;
+    claims
+}
+
+#[test]
+pub fn valid_id_token() {
+    assert_eq!(validate(&claims()), Ok(ExternalIdentity {
+        issuer: ISSUER.to_string(),
+        subject: "subject".to_string(),
+        email: Some("test@test.test".to_string()),
+        email_verified: true,
+        preferred_username: Some("test".to_string()),
+    }));
+    // Issued to several clients, with this one as the authorized party
+    let claims = with(with(claims(), "aud", json!([CLIENT_ID, "other"])), "azp", json!(CLIENT_ID));
+    assert!(validate(&claims).is_ok());
+    // Some providers send email_verified as a string
+    assert!(validate(&with(claims, "email_verified", json!("true"))).unwrap().email_verified);
+}
+
+#[test]
+pub fn id_token_with_invalid_claims() {
+    let now = Utc::now().timestamp();
+    assert_eq!(validate(&with(claims(), "iss", json!("https://other.test"))), Err(ServerError::InvalidOidcSignIn));
+    assert_eq!(validate(&with(claims(), "aud", json!("other"))), Err(ServerError::InvalidOidcSignIn));
+    assert_eq!(validate(&with(claims(), "aud", json!([CLIENT_ID, "other"]))), Err(ServerError::InvalidOidcSignIn));
+    assert_eq!(validate(&with(claims(), "nonce", json!("other"))), Err(ServerError::InvalidOidcSignIn));
+    assert_eq!(validate(&with(claims(), "exp", json!(now - 120))), Err(ServerError::InvalidOidcSignIn));
+    assert_eq!(validate(&with(claims(), "iat", json!(now + 120))), Err(ServerError::InvalidOidcSignIn));
+    assert_eq!(validate(&with(claims(), "sub", json!(""))), Err(ServerError::InvalidOidcSignIn));
+}
+
+#[test]
+pub fn id_token_with_invalid_signature() {
+    let id_token = sign_id_token(&json!({ "alg": "RS256", "kid": KEY_ID }), &claims());
+    let now = Utc::now().timestamp();
+
+    // Claims swapped for others, keeping the signature
+    let parts: Vec<&str> = id_token.split('.').collect();
+    let other_token = sign_id_token(&json!({ "alg": "RS256", "kid": KEY_ID }), &with(claims(), "sub", json!("other")));
+    let tampered = format!("{}.{}.{}", parts[0], other_token.split('.').nth(1).unwrap(), parts[2]);
+    assert_eq!(validate_id_token(&tampered, &keys(), ISSUER, CLIENT_ID, NONCE, now), Err(ServerError::InvalidOidcSignIn));
+
+    // Unsigned or signed with an unknown key
+    let unsigned = format!("{}.{}.", sign_id_token(&json!({ "alg": "none" }), &claims()).split('.').next().unwrap(), parts[1]);
+    assert_eq!(validate_id_token(&unsigned, &keys(), ISSUER, CLIENT_ID, NONCE, now), Err(ServerError::InvalidOidcSignIn));
+    let unknown_key = sign_id_token(&json!({ "alg": "RS256", "kid": "other" }), &claims());
+    assert_eq!(validate_id_token(&unknown_key, &keys(), ISSUER, CLIENT_ID, NONCE, now), Err(ServerError::InvalidOidcSignIn));
+    assert_eq!(validate_id_token(&id_token, &[], ISSUER, CLIENT_ID, NONCE, now), Err(ServerError::InvalidOidcSignIn));
+}
\ No newline at end of file
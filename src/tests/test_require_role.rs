@@ -0,0 +1,37 @@
+use axum::body::Body;
+use axum::extract::FromRequestParts;
+use axum::http::{Request, StatusCode};
+use crate::auth_layer::{Admin, Moderator, RequireRole, RequiredRole};
+use crate::entities::dtos::session_dtos::{SessionFromStore, SessionOption};
+use crate::entities::user::Role;
+
+async fn extract<R: RequiredRole>(role: Option<Role>) -> Result<RequireRole<R>, StatusCode> {
+    let session = role.map(|role| SessionFromStore::new("0".to_string(), 0, 0, role));
+    let request = Request::builder()
+        .extension(SessionOption::new(session))
+        .body(Body::empty())
+        .unwrap();
+    let (mut parts, _) = request.into_parts();
+    RequireRole::<R>::from_request_parts(&mut parts, &())
+        .await
+        .map_err(|response| response.status())
+}
+
+#[tokio::test]
+pub async fn require_role_without_session() {
+    assert_eq!(extract::<Moderator>(None).await.err(), Some(StatusCode::UNAUTHORIZED));
+}
+
+#[tokio::test]
+pub async fn require_role_insufficient() {
+    assert_eq!(extract::<Moderator>(Some(Role::User)).await.err(), Some(StatusCode::FORBIDDEN));
+    assert_eq!(extract::<Admin>(Some(Role::Moderator)).await.err(), Some(StatusCode::FORBIDDEN));
+}
+
+#[tokio::test]
+pub async fn require_role_sufficient() {
+    assert!(extract::<Moderator>(Some(Role::Moderator)).await.is_ok());
+    // Higher roles have the privileges of the lower ones
+    assert!(extract::<Moderator>(Some(Role::Admin)).await.is_ok());
+    assert!(extract::<Admin>(Some(Role::Admin)).await.is_ok());
+}
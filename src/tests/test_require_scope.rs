@@ -0,0 +1,35 @@
+use axum::body::Body;
+use axum::extract::FromRequestParts;
+use axum::http::{Request, StatusCode};
+use crate::auth_layer::{FiguresWriteScope, ProfileWriteScope, RequireScope, RequiredScope};
+use crate::entities::access_token::Scope;
+use crate::entities::dtos::session_dtos::{SessionFromStore, SessionOption};
+use crate::entities::user::Role;
+
+async fn extract<S: RequiredScope>(session: Option<SessionFromStore>) -> Result<RequireScope<S>, StatusCode> {
+    let request = Request::builder()
+        .extension(SessionOption::new(session))
+        .body(Body::empty())
+        .unwrap();
+    let (mut parts, _) = request.into_parts();
+    RequireScope::<S>::from_request_parts(&mut parts, &())
+        .await
+        .map_err(|response| response.status())
+}
+
+fn access_token_session(scopes: Vec<Scope>) -> SessionFromStore {
+    SessionFromStore::new("0".to_string(), 0, 0, Role::User).with_scopes(scopes)
+}
+
+#[tokio::test]
+pub async fn require_scope_of_access_token() {
+    assert!(extract::<FiguresWriteScope>(Some(access_token_session(vec![Scope::FiguresWrite]))).await.is_ok());
+    assert_eq!(extract::<ProfileWriteScope>(Some(access_token_session(vec![Scope::FiguresWrite]))).await.err(), Some(StatusCode::FORBIDDEN));
+}
+
+#[tokio::test]
+pub async fn require_scope_of_browser_session() {
+    // Sessions of a signed in browser aren't limited, requests without a session are left to the route
+    assert!(extract::<FiguresWriteScope>(Some(SessionFromStore::new("0".to_string(), 0, 0, Role::User))).await.is_ok());
+    assert!(extract::<FiguresWriteScope>(None).await.is_ok());
+}
\ No newline at end of file
@@ -0,0 +1,7 @@
+mod test_cursor;
+
+mod test_secure_rand_generator;
+mod test_csrf;
+mod test_totp;
+mod test_password;
+mod test_trusted_proxies;
\ No newline at end of file
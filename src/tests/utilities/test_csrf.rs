@@ -0,0 +1,31 @@
+use crate::server_errors::ServerError;
+use crate::utilities::csrf::CsrfTokens;
+
+#[test]
+pub fn csrf_token_of_session() {
+    let csrf_tokens = CsrfTokens::new(b"secret");
+
+    let token = csrf_tokens.create("session").unwrap();
+
+    assert_eq!(csrf_tokens.verify("session", &token), Ok(()));
+}
+
+#[test]
+pub fn csrf_token_of_other_session() {
+    let csrf_tokens = CsrfTokens::new(b"secret");
+
+    let token = csrf_tokens.create("other session").unwrap();
+
+    assert_eq!(csrf_tokens.verify("session", &token), Err(ServerError::InvalidCsrfToken));
+}
+
+#[test]
+pub fn csrf_token_created_with_other_key() {
+    let csrf_tokens = CsrfTokens::new(b"secret");
+    let other_csrf_tokens = CsrfTokens::new(b"other secret");
+
+    let token = other_csrf_tokens.create("session").unwrap();
+
+    assert_eq!(csrf_tokens.verify("session", &token), Err(ServerError::InvalidCsrfToken));
+    assert_eq!(csrf_tokens.verify("session", "not base64!"), Err(ServerError::InvalidCsrfToken));
+}
\ No newline at end of file
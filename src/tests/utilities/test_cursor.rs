@@ -0,0 +1,58 @@
+use crate::server_errors::ServerError;
+use crate::utilities::cursor::{CursorKind, CursorSigner};
+
+#[test]
+pub fn cursor_roundtrip() {
+    let signer = CursorSigner::new(b"secret");
+
+    let cursor = signer.encode(CursorKind::Figures, 42).unwrap();
+
+    assert_eq!(signer.decode(CursorKind::Figures, &cursor), Ok(42));
+}
+
+#[test]
+pub fn cursor_tampered() {
+    let signer = CursorSigner::new(b"secret");
+    let cursor = signer.encode(CursorKind::Figures, 42).unwrap();
+    let (_, signature) = cursor.split_once('.').unwrap();
+
+    // Swap in the payload of another cursor while keeping the signature
+    let other_cursor = signer.encode(CursorKind::Figures, 1).unwrap();
+    let (other_payload, _) = other_cursor.split_once('.').unwrap();
+    let tampered = format!("{}.{}", other_payload, signature);
+
+    assert_eq!(signer.decode(CursorKind::Figures, &tampered), Err(ServerError::InvalidCursor));
+}
+
+#[test]
+pub fn cursor_signed_with_other_key() {
+    let signer = CursorSigner::new(b"secret");
+    let other_signer = CursorSigner::new(b"other secret");
+
+    let cursor = other_signer.encode(CursorKind::Figures, 42).unwrap();
+
+    assert_eq!(signer.decode(CursorKind::Figures, &cursor), Err(ServerError::InvalidCursor));
+}
+
+#[test]
+pub fn cursor_malformed() {
+    let signer = CursorSigner::new(b"secret");
+
+    assert_eq!(signer.decode(CursorKind::Figures, ""), Err(ServerError::InvalidCursor));
+    assert_eq!(signer.decode(CursorKind::Figures, "42"), Err(ServerError::InvalidCursor));
+    assert_eq!(signer.decode(CursorKind::Figures, "not.base64!"), Err(ServerError::InvalidCursor));
+}
+
+#[test]
+pub fn cursor_of_other_listing() {
+    let signer = CursorSigner::new(b"secret");
+
+    let cursor = signer.encode(CursorKind::Figures, 42).unwrap();
+
+    // Would skip 42 results if taken as the offset of a search, or start after an unrelated like
+    assert_eq!(signer.decode(CursorKind::SearchResults, &cursor), Err(ServerError::InvalidCursor));
+    assert_eq!(signer.decode(CursorKind::Likes, &cursor), Err(ServerError::InvalidCursor));
+    let search_cursor = signer.encode(CursorKind::SearchResults, 20).unwrap();
+    assert_eq!(signer.decode(CursorKind::Figures, &search_cursor), Err(ServerError::InvalidCursor));
+    assert_eq!(signer.decode(CursorKind::SearchResults, &search_cursor), Ok(20));
+}
@@ -0,0 +1,35 @@
+use crate::server_errors::ServerError;
+use crate::utilities::password::{hash_password, PasswordHashPolicy, verify_password};
+
+const WEAK_POLICY: PasswordHashPolicy = PasswordHashPolicy {
+    memory_cost: 1024,
+    time_cost: 1,
+    parallelism: 1,
+};
+
+#[tokio::test]
+pub async fn verify_with_parameters_of_hash() {
+    let password_hash = hash_password("password".to_string(), WEAK_POLICY).await.unwrap();
+
+    assert_eq!(verify_password("password".to_string(), password_hash.clone()).await, Ok(()));
+    assert_eq!(verify_password("other".to_string(), password_hash).await, Err(ServerError::WrongPassword));
+}
+
+#[tokio::test]
+pub async fn needs_rehash_below_policy() {
+    let password_hash = hash_password("password".to_string(), WEAK_POLICY).await.unwrap();
+
+    assert!(!WEAK_POLICY.needs_rehash(&password_hash));
+    assert!(PasswordHashPolicy { memory_cost: 2048, ..WEAK_POLICY }.needs_rehash(&password_hash));
+    assert!(PasswordHashPolicy { time_cost: 2, ..WEAK_POLICY }.needs_rehash(&password_hash));
+    assert!(PasswordHashPolicy { parallelism: 2, ..WEAK_POLICY }.needs_rehash(&password_hash));
+}
+
+#[test]
+pub fn needs_rehash_of_other_algorithm() {
+    // Argon2i hash with the default parameters, only its algorithm differs from the policy
+    let argon2i_hash = "$argon2i$v=19$m=8192,t=5,p=1$c2FsdHNhbHQ$R0Tsn3Nr0YAtjDVjvtRIaVKGmk8JDiL+2NCUPUJZ/Ig";
+
+    assert!(PasswordHashPolicy::default().needs_rehash(argon2i_hash));
+    assert!(PasswordHashPolicy::default().needs_rehash("not a hash"));
+}
\ No newline at end of file
@@ -0,0 +1,20 @@
+use crate::utilities::secure_rand_generator::ChaCha20;
+use crate::utilities::traits::RandomNumberGenerator;
+
+#[test]
+pub fn token_is_256_bit_and_url_safe() {
+    let generator = ChaCha20::new();
+
+    let token = generator.generate_token().unwrap();
+
+    // 32 bytes base64url encoded without padding
+    assert_eq!(token.len(), 43);
+    assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+}
+
+#[test]
+pub fn tokens_are_unique() {
+    let generator = ChaCha20::new();
+
+    assert_ne!(generator.generate_token().unwrap(), generator.generate_token().unwrap());
+}
\ No newline at end of file
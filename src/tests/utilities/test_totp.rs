@@ -0,0 +1,31 @@
+use crate::utilities::totp::{base32_decode, base32_encode, generate_code, time_step, verify_code};
+
+// Shared secret of the SHA1 test vectors in RFC 6238
+const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+#[test]
+pub fn totp_rfc_test_vectors() {
+    assert_eq!(generate_code(RFC_SECRET, time_step(59)).unwrap(), "287082");
+    assert_eq!(generate_code(RFC_SECRET, time_step(1111111109)).unwrap(), "081804");
+    assert_eq!(generate_code(RFC_SECRET, time_step(1234567890)).unwrap(), "005924");
+}
+
+#[test]
+pub fn totp_verify_with_drift() {
+    let step = time_step(1234567890);
+    let code = generate_code(RFC_SECRET, step).unwrap();
+
+    assert_eq!(verify_code(RFC_SECRET, &code, step).unwrap(), Some(step));
+    assert_eq!(verify_code(RFC_SECRET, &code, step + 1).unwrap(), Some(step));
+    assert_eq!(verify_code(RFC_SECRET, &code, step + 2).unwrap(), None);
+}
+
+#[test]
+pub fn base32_roundtrip() {
+    assert_eq!(base32_encode(RFC_SECRET), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+    assert_eq!(base32_decode("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap(), RFC_SECRET);
+    assert_eq!(base32_decode(&base32_encode(b"hello")).unwrap(), b"hello");
+    // Lowercase is accepted, characters outside of the alphabet aren't
+    assert_eq!(base32_decode("nbswy3dp").unwrap(), b"hello");
+    assert_eq!(base32_decode("NBSWY3D1"), None);
+}
\ No newline at end of file
@@ -0,0 +1,71 @@
+use std::net::IpAddr;
+use axum::http::{HeaderMap, HeaderValue};
+use crate::utilities::trusted_proxies::TrustedProxies;
+
+fn ip(ip: &str) -> IpAddr {
+    ip.parse().unwrap()
+}
+
+fn forwarded_for(values: &[&str]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for value in values {
+        headers.append("x-forwarded-for", HeaderValue::from_str(value).unwrap());
+    }
+    headers
+}
+
+#[test]
+pub fn trusted_proxies_ranges() {
+    let trusted_proxies = TrustedProxies::parse("10.0.0.0/8, 192.168.1.1, fd00::/8").unwrap();
+
+    assert!(trusted_proxies.contains(&ip("10.1.2.3")));
+    assert!(trusted_proxies.contains(&ip("192.168.1.1")));
+    assert!(trusted_proxies.contains(&ip("fd12::1")));
+    assert!(trusted_proxies.contains(&ip("::ffff:10.0.0.1")));
+    assert!(!trusted_proxies.contains(&ip("11.0.0.1")));
+    assert!(!trusted_proxies.contains(&ip("192.168.1.2")));
+}
+
+#[test]
+pub fn trusted_proxies_invalid() {
+    assert!(TrustedProxies::parse("10.0.0.0/33").is_err());
+    assert!(TrustedProxies::parse("proxy").is_err());
+    assert!(TrustedProxies::parse("").is_ok());
+}
+
+#[test]
+pub fn client_ip_ignores_forwarded_for_from_untrusted_peer() {
+    let trusted_proxies = TrustedProxies::parse("10.0.0.0/8").unwrap();
+
+    let client_ip = trusted_proxies.client_ip(ip("1.1.1.1"), &forwarded_for(&["2.2.2.2"]));
+
+    assert_eq!(client_ip, ip("1.1.1.1"));
+}
+
+#[test]
+pub fn client_ip_takes_right_most_untrusted_hop() {
+    let trusted_proxies = TrustedProxies::parse("10.0.0.0/8").unwrap();
+
+    // The client made up the first hop, the proxies added the others
+    let client_ip = trusted_proxies.client_ip(ip("10.0.0.1"), &forwarded_for(&["3.3.3.3, 2.2.2.2", "10.0.0.2"]));
+
+    assert_eq!(client_ip, ip("2.2.2.2"));
+}
+
+#[test]
+pub fn client_ip_stops_at_invalid_hop() {
+    let trusted_proxies = TrustedProxies::parse("10.0.0.0/8").unwrap();
+
+    let client_ip = trusted_proxies.client_ip(ip("10.0.0.1"), &forwarded_for(&["2.2.2.2, unknown, 10.0.0.2"]));
+
+    assert_eq!(client_ip, ip("10.0.0.2"));
+}
+
+#[test]
+pub fn client_ip_without_trusted_proxies() {
+    let trusted_proxies = TrustedProxies::default();
+
+    let client_ip = trusted_proxies.client_ip(ip("10.0.0.1"), &forwarded_for(&["2.2.2.2"]));
+
+    assert_eq!(client_ip, ip("10.0.0.1"));
+}
\ No newline at end of file
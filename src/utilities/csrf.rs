@@ -0,0 +1,41 @@
+use std::sync::Arc;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use crate::server_errors::ServerError;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Creates and verifies CSRF tokens, which are bound to the session they were created for.
+// Nothing has to be stored, and a token stops working along with its session.
+#[derive(Clone)]
+pub struct CsrfTokens {
+    key: Arc<Vec<u8>>,
+}
+
+impl CsrfTokens {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_vec()),
+        }
+    }
+
+    pub fn create(&self, session_id: &str) -> Result<String, ServerError> {
+        Ok(URL_SAFE_NO_PAD.encode(self.mac(session_id)?.finalize().into_bytes()))
+    }
+
+    pub fn verify(&self, session_id: &str, token: &str) -> Result<(), ServerError> {
+        let token = URL_SAFE_NO_PAD.decode(token).map_err(|_| ServerError::InvalidCsrfToken)?;
+        self.mac(session_id)?
+            .verify_slice(&token)
+            .map_err(|_| ServerError::InvalidCsrfToken)
+    }
+
+    fn mac(&self, session_id: &str) -> Result<HmacSha256, ServerError> {
+        let mut mac = HmacSha256::new_from_slice(&self.key)
+            .map_err(|e| ServerError::InternalError(Arc::new(anyhow::Error::msg(e.to_string()))))?;
+        mac.update(session_id.as_bytes());
+        Ok(mac)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use crate::entities::types::IdType;
+use crate::server_errors::ServerError;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Bump when the ordering of paginated listings changes so that old cursors get rejected
+const CURSOR_VERSION: u8 = 1;
+
+// What the position held by a cursor refers to, listings only accept cursors of their own kind
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorKind {
+    Figures,
+    Likes,
+    Follows,
+    Comments,
+    Users,
+    // Number of ranked results to skip rather than an id
+    SearchResults,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CursorPayload {
+    v: u8,
+    kind: CursorKind,
+    last_id: IdType,
+}
+
+// Creates and verifies opaque pagination cursors ("<payload>.<signature>", both base64url encoded).
+// Clients can't forge or tamper with them, so the position they hold can be changed later on.
+#[derive(Clone)]
+pub struct CursorSigner {
+    key: Arc<Vec<u8>>,
+}
+
+impl CursorSigner {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_vec()),
+        }
+    }
+
+    pub fn encode(&self, kind: CursorKind, last_id: IdType) -> Result<String, ServerError> {
+        let payload = serde_json::to_vec(&CursorPayload { v: CURSOR_VERSION, kind, last_id })
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        let payload = URL_SAFE_NO_PAD.encode(payload);
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(&payload)?.finalize().into_bytes());
+        Ok(format!("{}.{}", payload, signature))
+    }
+
+    pub fn decode(&self, kind: CursorKind, cursor: &str) -> Result<IdType, ServerError> {
+        let (payload, signature) = cursor.split_once('.').ok_or(ServerError::InvalidCursor)?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| ServerError::InvalidCursor)?;
+        self.mac(payload)?
+            .verify_slice(&signature)
+            .map_err(|_| ServerError::InvalidCursor)?;
+
+        let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| ServerError::InvalidCursor)?;
+        match serde_json::from_slice::<CursorPayload>(&payload) {
+            Ok(payload) if payload.v == CURSOR_VERSION && payload.kind == kind => Ok(payload.last_id),
+            _ => Err(ServerError::InvalidCursor)
+        }
+    }
+
+    fn mac(&self, payload: &str) -> Result<HmacSha256, ServerError> {
+        let mut mac = HmacSha256::new_from_slice(&self.key)
+            .map_err(|e| ServerError::InternalError(Arc::new(anyhow::Error::msg(e.to_string()))))?;
+        mac.update(payload.as_bytes());
+        Ok(mac)
+    }
+}
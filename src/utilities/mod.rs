@@ -1,3 +1,9 @@
 pub mod secure_rand_generator;
 pub mod traits;
-pub mod logging;
\ No newline at end of file
+pub mod logging;
+pub mod cursor;
+pub mod token;
+pub mod csrf;
+pub mod totp;
+pub mod password;
+pub mod trusted_proxies;
\ No newline at end of file
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
+use argon2::password_hash::SaltString;
+use rand_core::OsRng;
+use crate::server_errors::ServerError;
+
+const HASH_LENGTH: usize = 32;
+
+// Argon2id cost of new password hashes, memory cost in KiB.
+// Hashes with a lower cost are replaced when their user signs in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PasswordHashPolicy {
+    pub memory_cost: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+}
+
+impl PasswordHashPolicy {
+    pub fn params(&self) -> Result<Params, ServerError> {
+        Params::new(self.memory_cost, self.time_cost, self.parallelism, Some(HASH_LENGTH))
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    }
+
+    // Hashes that can't be parsed need a rehash as well, they can't be verified anyway
+    pub fn needs_rehash(&self, password_hash: &str) -> bool {
+        let parsed_hash = match PasswordHash::new(password_hash) {
+            Ok(hash) => hash,
+            Err(_e) => return true
+        };
+        if Algorithm::try_from(parsed_hash.algorithm) != Ok(Algorithm::Argon2id)
+            || parsed_hash.version != Some(Version::V0x13.into()) {
+            return true;
+        }
+        match Params::try_from(&parsed_hash) {
+            Ok(params) => params.m_cost() < self.memory_cost || params.t_cost() < self.time_cost || params.p_cost() < self.parallelism,
+            Err(_e) => true
+        }
+    }
+}
+
+impl Default for PasswordHashPolicy {
+    fn default() -> Self {
+        Self {
+            memory_cost: 8192,
+            time_cost: 5,
+            parallelism: 1,
+        }
+    }
+}
+
+// Hashing takes long on purpose, so it runs on the blocking thread pool instead of stalling the runtime
+pub async fn hash_password(password: String, policy: PasswordHashPolicy) -> Result<String, ServerError> {
+    tokio::task::spawn_blocking(move || {
+        let password_salt = SaltString::generate(&mut OsRng);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, policy.params()?)
+            .hash_password(password.as_bytes(), &password_salt)
+            .map(|password_hash| password_hash.to_string())
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))
+    })
+        .await
+        .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?
+}
+
+// Verifies with the algorithm and parameters stored in the hash, not those of the current policy
+pub async fn verify_password(password: String, password_hash: String) -> Result<(), ServerError> {
+    tokio::task::spawn_blocking(move || {
+        let parsed_hash = PasswordHash::new(&password_hash)
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        let algorithm = Algorithm::try_from(parsed_hash.algorithm)
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+        let version = match parsed_hash.version {
+            Some(version) => Version::try_from(version)
+                .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?,
+            None => Version::default()
+        };
+        let params = Params::try_from(&parsed_hash)
+            .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+
+        Argon2::new(algorithm, version, params)
+            .verify_password(password.as_bytes(), &parsed_hash)
+            .map_err(|_e| ServerError::WrongPassword)
+    })
+        .await
+        .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?
+}
\ No newline at end of file
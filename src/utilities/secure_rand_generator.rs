@@ -18,10 +18,10 @@ impl ChaCha20 {
 }
 
 impl RandomNumberGenerator for ChaCha20 {
-    fn generate(&self) -> Result<u64, ServerError> {
+    fn fill_bytes(&self, bytes: &mut [u8]) -> Result<(), ServerError> {
         self.generator
             .lock()
-            .map(|mut generator| generator.next_u64())
+            .map(|mut generator| generator.fill_bytes(bytes))
             .map_err(|e| ServerError::InternalError(Arc::new(anyhow::Error::msg(e.to_string()))))
     }
 }
\ No newline at end of file
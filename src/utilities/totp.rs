@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use url::Url;
+use crate::server_errors::ServerError;
+
+type HmacSha1 = Hmac<Sha1>;
+
+// RFC 6238 defaults, the only parameters every authenticator app supports
+const TOTP_STEP: i64 = 30;
+const TOTP_DIGITS: u32 = 6;
+// Codes of the steps next to the current one are accepted as well, for clocks that are a bit off
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+pub fn time_step(unix_time: i64) -> i64 {
+    unix_time.div_euclid(TOTP_STEP)
+}
+
+// RFC 4226 code of the counter, which is the time step for TOTP
+pub fn generate_code(secret: &[u8], counter: i64) -> Result<String, ServerError> {
+    let mut mac = HmacSha1::new_from_slice(secret)
+        .map_err(|e| ServerError::InternalError(Arc::new(anyhow::Error::msg(e.to_string()))))?;
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    // Dynamic truncation
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([hash[offset] & 0x7f, hash[offset + 1], hash[offset + 2], hash[offset + 3]]);
+    Ok(format!("{:0width$}", binary % 10u32.pow(TOTP_DIGITS), width = TOTP_DIGITS as usize))
+}
+
+// Returns the time step the code belongs to, if it matches one of the steps around the given one
+pub fn verify_code(secret: &[u8], code: &str, step: i64) -> Result<Option<i64>, ServerError> {
+    for candidate in (step - ALLOWED_DRIFT_STEPS)..=(step + ALLOWED_DRIFT_STEPS) {
+        if generate_code(secret, candidate)? == code {
+            return Ok(Some(candidate));
+        }
+    }
+    Ok(None)
+}
+
+pub fn is_code_format(code: &str) -> bool {
+    code.len() == TOTP_DIGITS as usize && code.chars().all(|c| c.is_ascii_digit())
+}
+
+// URI authenticator apps read from a QR code
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> Result<String, ServerError> {
+    let mut uri = Url::parse("otpauth://totp/")
+        .map_err(|e| ServerError::InternalError(Arc::new(e.into())))?;
+    uri.set_path(&format!("{}:{}", issuer, account));
+    uri.query_pairs_mut()
+        .append_pair("secret", secret)
+        .append_pair("issuer", issuer)
+        .append_pair("algorithm", "SHA1")
+        .append_pair("digits", &TOTP_DIGITS.to_string())
+        .append_pair("period", &TOTP_STEP.to_string());
+    Ok(uri.to_string())
+}
+
+// RFC 4648 base32 without padding, the encoding authenticator apps expect secrets in
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len() * 8 / 5 + 1);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    encoded
+}
+
+pub fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in encoded.trim_end_matches('=').bytes() {
+        let value = BASE32_ALPHABET.iter().position(|a| *a == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+    Some(decoded)
+}
\ No newline at end of file
@@ -1,5 +1,14 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use crate::server_errors::ServerError;
 
 pub trait RandomNumberGenerator: Send + Sync {
-    fn generate(&self) -> Result<u64, ServerError>;
+    fn fill_bytes(&self, bytes: &mut [u8]) -> Result<(), ServerError>;
+
+    // 256 bit random token, base64url encoded so it can be used in cookies and links
+    fn generate_token(&self) -> Result<String, ServerError> {
+        let mut bytes = [0u8; 32];
+        self.fill_bytes(&mut bytes)?;
+        Ok(URL_SAFE_NO_PAD.encode(bytes))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,93 @@
+use std::net::IpAddr;
+use anyhow::anyhow;
+use axum::http::HeaderMap;
+
+// Proxies in front of the server, given as addresses or CIDR ranges.
+// X-Forwarded-For is only read when the request comes from one of them, anyone else could send any value.
+#[derive(Clone, Debug, Default)]
+pub struct TrustedProxies {
+    ranges: Vec<(IpAddr, u8)>,
+}
+
+impl TrustedProxies {
+    // Comma separated, ex. "10.0.0.0/8, 127.0.0.1"
+    pub fn parse(value: &str) -> Result<Self, anyhow::Error> {
+        let ranges = value.split(',')
+            .map(str::trim)
+            .filter(|range| !range.is_empty())
+            .map(parse_range)
+            .collect::<Result<Vec<(IpAddr, u8)>, anyhow::Error>>()?;
+        Ok(Self { ranges })
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        self.ranges.iter().any(|(network, prefix)| in_range(ip, network, *prefix))
+    }
+
+    // Ip of the client that connected from the peer. Proxies append the address they received
+    // the request from, so the right-most hop that isn't a trusted proxy is the client,
+    // everything left of it could have been made up by the client.
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !self.contains(&peer) {
+            return peer;
+        }
+
+        let mut client = peer;
+        let hops = headers.get_all("x-forwarded-for").iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<&str>>();
+        for hop in hops.into_iter().rev() {
+            // A hop that isn't an address wasn't added by a trusted proxy
+            let hop = match hop.trim().parse::<IpAddr>() {
+                Ok(hop) => hop,
+                Err(_) => return client
+            };
+            client = hop;
+            if !self.contains(&hop) {
+                return client;
+            }
+        }
+        client
+    }
+}
+
+fn parse_range(range: &str) -> Result<(IpAddr, u8), anyhow::Error> {
+    let (address, prefix) = match range.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (range, None)
+    };
+    let address = address.parse::<IpAddr>()
+        .map_err(|_| anyhow!("Invalid trusted proxy address: {}", range))?;
+    let max_prefix = match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128
+    };
+    let prefix = match prefix {
+        Some(prefix) => prefix.parse::<u8>()
+            .ok()
+            .filter(|prefix| *prefix <= max_prefix)
+            .ok_or_else(|| anyhow!("Invalid trusted proxy prefix: {}", range))?,
+        None => max_prefix
+    };
+    Ok((address, prefix))
+}
+
+fn in_range(ip: &IpAddr, network: &IpAddr, prefix: u8) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(*ip) & mask == u32::from(*network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(*ip) & mask == u128::from(*network) & mask
+        }
+        // Ipv4 clients of a dual stack socket show up as mapped ipv6 addresses
+        (IpAddr::V6(ip), IpAddr::V4(_)) => match ip.to_ipv4_mapped() {
+            Some(ip) => in_range(&IpAddr::V4(ip), network, prefix),
+            None => false
+        },
+        (IpAddr::V4(_), IpAddr::V6(_)) => false
+    }
+}
\ No newline at end of file